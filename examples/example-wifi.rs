@@ -1,18 +1,18 @@
+use qr2term::payload::{Security, Wifi};
+
 fn main() {
     println!("WiFi network name: ");
     let wifi_network = std::io::stdin().lines().next().unwrap().unwrap();
     println!("\nPassword: ");
     let pass = std::io::stdin().lines().next().unwrap().unwrap();
     println!("\nType (WEP or [WPA - hit Enter as default]): ");
-    let _network_type = std::io::stdin().lines().next().unwrap().unwrap();
-    let network_type = if _network_type.is_empty() {
-        "WPA".to_string()
+    let network_type = std::io::stdin().lines().next().unwrap().unwrap();
+    let security = if network_type.eq_ignore_ascii_case("WEP") {
+        Security::Wep
     } else {
-        _network_type
+        Security::Wpa
     };
 
-    let input =
-        "WIFI:S:".to_string() + &wifi_network + ";T:" + &network_type + ";P:" + &pass + ";;";
-
-    qr2term::print_qr(input).unwrap();
+    let wifi = Wifi::new(wifi_network).password(pass).security(security);
+    qr2term::print_qr(wifi.to_string()).unwrap();
 }
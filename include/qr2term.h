@@ -0,0 +1,60 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+/* C interface of qr2term, built with the `ffi` feature. */
+
+#ifndef QR2TERM_H
+#define QR2TERM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define QR2TERM_OK 0
+#define QR2TERM_ERR_INVALID 1
+#define QR2TERM_ERR_QR 2
+#define QR2TERM_ERR_IO 3
+
+#define QR2TERM_DENSITY_HALF_BLOCK 0
+#define QR2TERM_DENSITY_FULL 1
+#define QR2TERM_DENSITY_BRAILLE 2
+
+#define QR2TERM_MAX_SCALE 16
+#define QR2TERM_MAX_QUIET_ZONE 64
+
+typedef struct {
+    /* One of the QR2TERM_DENSITY_* constants. */
+    int density;
+    /* Whether to use ANSI colors. */
+    bool colors;
+    /* Whether to swap dark and light pixels. */
+    bool invert;
+    /* How many times each pixel is repeated, from 1 to QR2TERM_MAX_SCALE. */
+    uint32_t scale;
+    /* Width of the light border around the code, in pixels, up to QR2TERM_MAX_QUIET_ZONE. */
+    uint32_t quiet_zone;
+    /* Error correction level, from 0 (L) to 3 (H). */
+    int ec_level;
+} Qr2termOptions;
+
+/* Get the default rendering options. */
+Qr2termOptions qr2term_default_options(void);
+
+/* Print data as QR code in the terminal, returns QR2TERM_OK or an error code. */
+int qr2term_print(const char *data);
+
+/* Same as qr2term_print, using the given options or the defaults if NULL. */
+int qr2term_print_with(const char *data, const Qr2termOptions *options);
+
+/* Generate data as QR code string, release it with free. Returns NULL on failure. */
+char *qr2term_generate(const char *data, const Qr2termOptions *options);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,347 @@
+//! Text annotations around rendered codes, and framed panels holding them.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::annotate::Annotations;
+//!
+//! let annotations = Annotations::new()
+//!     .top("Pair your device")
+//!     .bottom("https://example.com/pair?code=1234")
+//!     .right("Expires in\n5 minutes");
+//! qr2term::print_qr_annotated("https://example.com/pair?code=1234", &annotations).unwrap();
+//! ```
+
+use std::io::{Result as IoResult, Write};
+
+use crate::matrix::Matrix;
+use crate::render::{Color, Renderer};
+
+/// Columns between the code and annotations on its right.
+const GAP: usize = 2;
+
+/// Box drawing characters of the panel frame: corners, horizontal and vertical lines.
+const FRAME_UNICODE: [char; 6] = ['┌', '┐', '└', '┘', '─', '│'];
+
+/// Frame characters for terminals without Unicode support.
+const FRAME_ASCII: [char; 6] = ['+', '+', '+', '+', '-', '|'];
+
+/// Where an annotation is placed relative to the code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// Above the code, centered.
+    Top,
+
+    /// Below the code, centered.
+    Bottom,
+
+    /// To the right of the code, from its top down.
+    Right,
+}
+
+/// Annotations placed around a code, such as a title, the raw data and an expiry note.
+///
+/// Annotations at the same position are stacked in the order they were added. Text may
+/// span multiple lines.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Annotations {
+    annotations: Vec<(Position, String)>,
+}
+
+impl Annotations {
+    /// Construct without any annotations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an annotation at the given position.
+    pub fn add<S: Into<String>>(mut self, position: Position, text: S) -> Self {
+        self.annotations.push((position, text.into()));
+        self
+    }
+
+    /// Add an annotation above the code.
+    pub fn top<S: Into<String>>(self, text: S) -> Self {
+        self.add(Position::Top, text)
+    }
+
+    /// Add an annotation below the code.
+    pub fn bottom<S: Into<String>>(self, text: S) -> Self {
+        self.add(Position::Bottom, text)
+    }
+
+    /// Add an annotation to the right of the code.
+    pub fn right<S: Into<String>>(self, text: S) -> Self {
+        self.add(Position::Right, text)
+    }
+
+    /// Get the lines of text at the given position.
+    fn lines_at(&self, position: Position) -> impl Iterator<Item = &str> {
+        self.annotations
+            .iter()
+            .filter(move |(at, _)| *at == position)
+            .flat_map(|(_, text)| text.lines())
+    }
+
+    /// Render the code with its annotations as lines of text, without line endings.
+    pub fn lines(&self, renderer: &Renderer, matrix: &Matrix<Color>) -> IoResult<Vec<String>> {
+        let width = renderer.width(matrix);
+        let centered = |line: &str| {
+            let indent = width.saturating_sub(line.chars().count()) / 2;
+            format!("{:indent$}{}", "", line, indent = indent)
+        };
+
+        let mut lines: Vec<String> = self.lines_at(Position::Top).map(centered).collect();
+        let mut code = renderer.lines(matrix)?;
+        let right: Vec<&str> = self.lines_at(Position::Right).collect();
+        code.resize_with(code.len().max(right.len()), || " ".repeat(width));
+        for (i, line) in code.into_iter().enumerate() {
+            match right.get(i) {
+                Some(text) => lines.push(format!("{}{:gap$}{}", line, "", text, gap = GAP)),
+                None => lines.push(line),
+            }
+        }
+        lines.extend(self.lines_at(Position::Bottom).map(centered));
+        Ok(lines)
+    }
+
+    /// Render the code with its annotations to the given writer.
+    pub fn render<W: Write>(
+        &self,
+        renderer: &Renderer,
+        matrix: &Matrix<Color>,
+        target: &mut W,
+    ) -> IoResult<()> {
+        for line in self.lines(renderer, matrix)? {
+            writeln!(target, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Framed panel with a heading, the code and instructions below it.
+///
+/// Instructions are wrapped to fit the panel, which is as narrow as the code allows when
+/// a [maximum width](Panel::max_width) is given.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::annotate::Panel;
+///
+/// let panel = Panel::new("Scan me")
+///     .text("Open the camera app on your phone and point it at the code to sign in.");
+/// qr2term::print_qr_panel("https://example.com/login?token=abc", &panel).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Panel {
+    heading: String,
+    text: String,
+    ascii: bool,
+    pub(crate) max_width: Option<usize>,
+}
+
+impl Panel {
+    /// Construct a panel with the given heading.
+    pub fn new<S: Into<String>>(heading: S) -> Self {
+        Self {
+            heading: heading.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the instruction text below the code.
+    pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Set whether to draw the frame with ASCII characters, instead of box drawing ones.
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Set the maximum width of the panel in columns, including the frame.
+    ///
+    /// The panel never gets narrower than the code and its frame.
+    pub fn max_width(mut self, columns: usize) -> Self {
+        self.max_width = Some(columns);
+        self
+    }
+
+    /// Get the width of the panel in columns for the given code width.
+    pub fn width(&self, code_width: usize) -> usize {
+        let natural = self
+            .heading
+            .lines()
+            .chain(self.text.lines())
+            .map(|line| line.chars().count())
+            .fold(code_width, usize::max);
+        let content = match self.max_width {
+            Some(max) => natural.min(max.saturating_sub(4)).max(code_width),
+            None => natural,
+        };
+        content + 4
+    }
+
+    /// Render the panel around the code as lines of text, without line endings.
+    pub fn lines(&self, renderer: &Renderer, matrix: &Matrix<Color>) -> IoResult<Vec<String>> {
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = if self.ascii {
+            FRAME_ASCII
+        } else {
+            FRAME_UNICODE
+        };
+        let code_width = renderer.width(matrix);
+        let content = self.width(code_width) - 4;
+        let row = |line: &str, width: usize| {
+            let left = (content - width) / 2;
+            let right = content - width - left;
+            format!(
+                "{} {:left$}{}{:right$} {}",
+                vertical,
+                "",
+                line,
+                "",
+                vertical,
+                left = left,
+                right = right,
+            )
+        };
+        let rule: String = std::iter::repeat(horizontal).take(content + 2).collect();
+
+        let mut lines = vec![format!("{}{}{}", top_left, rule, top_right)];
+        for line in wrap(&self.heading, content) {
+            lines.push(row(&line, line.chars().count()));
+        }
+        lines.push(row("", 0));
+        for line in renderer.lines(matrix)? {
+            lines.push(row(&line, code_width));
+        }
+        if !self.text.is_empty() {
+            lines.push(row("", 0));
+            for line in wrap(&self.text, content) {
+                let width = line.chars().count();
+                lines.push(row(
+                    &format!("{:width$}", line, width = content),
+                    content.max(width),
+                ));
+            }
+        }
+        lines.push(format!("{}{}{}", bottom_left, rule, bottom_right));
+        Ok(lines)
+    }
+
+    /// Render the panel around the code to the given writer.
+    pub fn render<W: Write>(
+        &self,
+        renderer: &Renderer,
+        matrix: &Matrix<Color>,
+        target: &mut W,
+    ) -> IoResult<()> {
+        for line in self.lines(renderer, matrix)? {
+            writeln!(target, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wrap text at word boundaries into lines of at most `width` characters.
+///
+/// Words longer than the width are split.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            let len = line.chars().count();
+            if len > 0 && len + 1 + word.len() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{QrDark, QrLight};
+
+    #[test]
+    fn wrap_words() {
+        assert_eq!(wrap("scan the code", 8), ["scan the", "code"]);
+        assert_eq!(wrap("a verylongword", 4), ["a", "very", "long", "word"]);
+        assert_eq!(wrap("one\n\ntwo", 10), ["one", "", "two"]);
+    }
+
+    #[test]
+    fn panel() {
+        let mut matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        matrix.surround(1, QrLight);
+        let renderer = Renderer::default().colors(false);
+        let panel = Panel::new("Scan")
+            .text("point your camera here")
+            .max_width(12);
+        assert_eq!(panel.width(4), 12);
+        assert_eq!(
+            panel.lines(&renderer, &matrix).unwrap(),
+            [
+                "┌──────────┐",
+                "│   Scan   │",
+                "│          │",
+                "│    ▄     │",
+                "│     ▀    │",
+                "│          │",
+                "│ point    │",
+                "│ your     │",
+                "│ camera   │",
+                "│ here     │",
+                "└──────────┘",
+            ]
+        );
+        let ascii = Panel::new("Hi")
+            .ascii(true)
+            .lines(&renderer, &matrix)
+            .unwrap();
+        assert_eq!(ascii.first().unwrap(), "+------+");
+        assert_eq!(ascii.len(), 6);
+    }
+
+    #[test]
+    fn positions() {
+        let mut matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        matrix.surround(1, QrLight);
+        let renderer = Renderer::default().colors(false);
+        let annotations = Annotations::new()
+            .top("T")
+            .bottom("wide bottom")
+            .right("r1\nr2")
+            .right("r3")
+            .top("T2");
+        assert_eq!(
+            annotations.lines(&renderer, &matrix).unwrap(),
+            [
+                " T",
+                " T2",
+                " ▄    r1",
+                "  ▀   r2",
+                "      r3",
+                "wide bottom",
+            ]
+        );
+        assert_eq!(
+            Annotations::new().lines(&renderer, &matrix).unwrap(),
+            renderer.lines(&matrix).unwrap()
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Generating and printing many codes at once.
+//!
+//! A [`Batch`] can be cancelled through an [`AtomicBool`], such as one set from a Ctrl+C
+//! handler, to stop long running jobs cleanly. Cancellation is checked between codes, so
+//! no code is left half printed with the terminal colors still set. A progress callback
+//! set through [`Batch::on_progress`] lets tools drive their own progress bars.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::sync::atomic::AtomicBool;
+//!
+//! use qr2term::batch::Batch;
+//!
+//! let cancel = AtomicBool::new(false);
+//! let printed = Batch::new()
+//!     .cancel(&cancel)
+//!     .print(["ticket-1", "ticket-2", "ticket-3"])
+//!     .unwrap();
+//! assert_eq!(printed, 3);
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::{generate_qr_string_with, options, print_qr_with, Error, Options};
+
+/// Callback reporting the number of finished items, and the total number of items.
+type Progress<'a> = Arc<dyn Fn(usize, usize) + Send + Sync + 'a>;
+
+/// Job generating or printing a code for each of a list of items.
+#[derive(Clone)]
+pub struct Batch<'a> {
+    options: Options,
+    cancel: Option<&'a AtomicBool>,
+    progress: Option<Progress<'a>>,
+}
+
+impl Default for Batch<'_> {
+    fn default() -> Self {
+        Self {
+            options: options(),
+            cancel: None,
+            progress: None,
+        }
+    }
+}
+
+impl fmt::Debug for Batch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field("options", &self.options)
+            .field("cancel", &self.cancel)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl<'a> Batch<'a> {
+    /// Construct a new batch, using the default options with the environment overrides
+    /// applied, like the top-level functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the options to generate and render codes with.
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set a flag to cancel the batch with, once set to `true`.
+    ///
+    /// Items not yet started when the flag is set fail with [`Error::Cancelled`].
+    pub fn cancel(mut self, cancel: &'a AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Set a callback invoked after each finished item, with the number of finished items
+    /// and the total number of items.
+    ///
+    /// With the `parallel` feature, it is called from the generating threads.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use qr2term::batch::Batch;
+    ///
+    /// Batch::new()
+    ///     .on_progress(|done, total| eprintln!("Generated {} of {} codes", done, total))
+    ///     .generate_strings(["ticket-1", "ticket-2"]);
+    /// ```
+    pub fn on_progress<F: Fn(usize, usize) + Send + Sync + 'a>(mut self, progress: F) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Report that an item finished, given the counter of finished items.
+    fn finished(&self, done: &AtomicUsize, total: usize) {
+        let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(progress) = &self.progress {
+            progress(done, total);
+        }
+    }
+
+    /// Whether the batch is cancelled.
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .map_or(false, |cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    /// Generate a `String` for each of the given `items` as QR code.
+    ///
+    /// Results are returned in the order of the items. With the `parallel` feature, codes
+    /// are encoded and rendered on all available cores.
+    pub fn generate_strings<I>(&self, items: I) -> Vec<Result<String, Error>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]> + Sync,
+    {
+        let items: Vec<I::Item> = items.into_iter().collect();
+        let done = AtomicUsize::new(0);
+        #[cfg(feature = "parallel")]
+        {
+            let threads = std::thread::available_parallelism()
+                .map_or(1, |threads| threads.get())
+                .min(items.len());
+            if threads > 1 {
+                return self.generate_strings_par(&items, threads, &done);
+            }
+        }
+        items
+            .iter()
+            .map(|data| self.generate_string(data, &done, items.len()))
+            .collect()
+    }
+
+    /// Generate a `String` for each of the given `items` on the given number of threads.
+    #[cfg(feature = "parallel")]
+    fn generate_strings_par<D>(
+        &self,
+        items: &[D],
+        threads: usize,
+        done: &AtomicUsize,
+    ) -> Vec<Result<String, Error>>
+    where
+        D: AsRef<[u8]> + Sync,
+    {
+        let chunk_size = (items.len() + threads - 1) / threads;
+        std::thread::scope(|scope| {
+            let chunks: Vec<_> = items
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|data| self.generate_string(data, done, items.len()))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            chunks
+                .into_iter()
+                .flat_map(|chunk| chunk.join().expect("QR code generation panicked"))
+                .collect()
+        })
+    }
+
+    /// Generate a `String` for `data` as QR code, unless cancelled.
+    fn generate_string<D: AsRef<[u8]>>(
+        &self,
+        data: D,
+        done: &AtomicUsize,
+        total: usize,
+    ) -> Result<String, Error> {
+        if self.cancelled() {
+            return Err(Error::Cancelled);
+        }
+        let code = generate_qr_string_with(data, &self.options);
+        self.finished(done, total);
+        code
+    }
+
+    /// Print each of the given `items` as QR code in the terminal, separated by empty
+    /// lines.
+    ///
+    /// Returns the number of printed codes. Stops at the first error, or with
+    /// [`Error::Cancelled`] once cancelled.
+    pub fn print<I>(&self, items: I) -> Result<usize, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let items: Vec<I::Item> = items.into_iter().collect();
+        let done = AtomicUsize::new(0);
+        for data in &items {
+            if self.cancelled() {
+                return Err(Error::Cancelled);
+            }
+            if done.load(Ordering::Relaxed) > 0 {
+                println!();
+            }
+            print_qr_with(data, &self.options)?;
+            self.finished(&done, items.len());
+        }
+        Ok(items.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_strings() {
+        let batch = Batch::new().options(Options::default());
+        let codes = batch.generate_strings(["a", "b", "c"]);
+        assert_eq!(codes.len(), 3);
+        assert_eq!(
+            codes[2].as_ref().unwrap(),
+            &generate_qr_string_with("c", &Options::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn cancel() {
+        let cancel = AtomicBool::new(true);
+        let batch = Batch::new().cancel(&cancel);
+        assert!(batch
+            .generate_strings(["a", "b"])
+            .iter()
+            .all(|code| matches!(code, Err(Error::Cancelled))));
+        assert!(matches!(batch.print(["a"]), Err(Error::Cancelled)));
+
+        cancel.store(false, Ordering::Relaxed);
+        assert_eq!(batch.generate_strings(["a"]).len(), 1);
+    }
+
+    #[test]
+    fn progress() {
+        let calls = std::sync::Mutex::new(Vec::new());
+        Batch::new()
+            .on_progress(|done, total| calls.lock().unwrap().push((done, total)))
+            .generate_strings(["a", "b", "c"]);
+        let mut calls = calls.into_inner().unwrap();
+        calls.sort_unstable();
+        assert_eq!(calls, [(1, 3), (2, 3), (3, 3)]);
+    }
+}
@@ -0,0 +1,663 @@
+//! Command line argument parsing.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use qr2term::qr::EcLevel;
+use qr2term::render::{TermColor, Theme};
+
+use crate::completions::Shell;
+use crate::error::Error;
+use crate::payload::{Kind, Payload};
+
+/// Usage information, shown with `--help`.
+pub const USAGE: &str = "\
+Print text as QR code in the terminal.
+
+Usage: qr2term [OPTIONS] [TEXT]
+       qr2term [OPTIONS] <COMMAND> [FIELDS]
+       qr2term completions <SHELL>
+
+Arguments:
+  [TEXT]  Text to encode, read from stdin if not given or '-'
+
+Commands:
+  wifi    WiFi network: --ssid, --password, --security <wpa|wep|none>, --hidden
+  totp    One-time password: --account, --secret, --issuer, --digits, --period
+  vcard   Contact card: --name, --org, --phone, --email, --url
+  mailto  Email message: --to, --cc, --subject, --body
+  tel     Phone number: --number
+  geo     Location: --lat, --lon, --query
+  url     Web link: --url
+
+  Missing required fields are prompted for when running in a terminal.
+
+  completions  Print a completion script for bash, zsh, fish or powershell
+
+Options:
+  -f, --file <PATH>      Read data to encode from a file
+      --trim-newline     Strip a trailing newline from the data
+  -t, --format <FORMAT>  Output format, guessed from the output file extension by default
+                         [possible values: ansi, utf8, ascii, braille, svg, png, sixel, kitty]
+  -o, --output <PATH>    Write the output to a file instead of stdout
+      --preview          Also print the code in the terminal when writing to a file
+  -q, --quiet            Don't print labels, separators and hints, only the codes
+      --json[=<STREAM>]  Print metadata about each code as JSON object
+                         [default: stderr] [possible values: stdout, stderr]
+      --copy             Also copy the encoded data to the clipboard, using OSC 52
+      --each-line        Print a code for each input line, replacing the previous one
+      --watch            Print the code again each time the input file changes
+      --batch <PATH>     Print a labeled code for each 'label<TAB>payload' line in a file,
+                         or write a file per entry if '--output' is a directory
+      --ec <LEVEL>       Error correction level [default: M] [possible values: L, M, Q, H]
+      --qr-version <N>   QR code version from 1 to 40, the smallest that fits by default
+      --quiet-zone <N>   Width of the light border around the code [default: 2]
+      --invert           Swap dark and light pixels, for dark terminal backgrounds
+      --theme <NAME>     Color theme for ANSI output [default: classic]
+                         [possible values: classic, solarized, gruvbox, nord]
+      --fg <COLOR>       Color of dark pixels for ANSI output, overriding the theme
+      --bg <COLOR>       Color of light pixels for ANSI output, overriding the theme
+                         [possible values: black, white, 0 to 255, #rrggbb]
+      --scale <N>        Size of each pixel, multiplied [default: 1]
+      --strict           Fail instead of warning when the colors are hard to scan
+  -h, --help             Print help
+  -V, --version          Print version
+
+Exit status:
+  0  Success
+  1  Reading input or writing output failed
+  2  Invalid arguments, or colors too hard to scan with '--strict'
+  3  Data too long to fit in a QR code
+  4  Terminal too narrow to show the code
+  5  Unknown output format, or one that can't be printed in the terminal
+";
+
+/// What the command line tool should do.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Print usage information.
+    Help,
+
+    /// Print the version.
+    Version,
+
+    /// Print a QR code.
+    Print(Args),
+
+    /// Print a shell completion script.
+    Completions(Shell),
+}
+
+/// Where to read the data to encode from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Text given as argument.
+    Text(String),
+
+    /// Data read from stdin.
+    #[default]
+    Stdin,
+
+    /// Data read from a file.
+    File(PathBuf),
+
+    /// Payload built from fields given to a subcommand.
+    Payload(Payload),
+}
+
+/// Output format.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Half blocks with ANSI colors.
+    #[default]
+    Ansi,
+
+    /// Plain half block glyphs.
+    Utf8,
+
+    /// Plain ASCII characters.
+    Ascii,
+
+    /// Plain braille glyphs.
+    Braille,
+
+    /// SVG image.
+    Svg,
+
+    /// PNG image.
+    Png,
+
+    /// Sixel image, for terminals supporting it.
+    Sixel,
+
+    /// Kitty graphics protocol image, for terminals supporting it.
+    Kitty,
+}
+
+impl Format {
+    /// All output formats.
+    const ALL: [Format; 8] = [
+        Format::Ansi,
+        Format::Utf8,
+        Format::Ascii,
+        Format::Braille,
+        Format::Svg,
+        Format::Png,
+        Format::Sixel,
+        Format::Kitty,
+    ];
+
+    /// Parse a format from its name.
+    fn parse(name: &str) -> Result<Self, Error> {
+        let lower = name.to_ascii_lowercase();
+        Format::ALL
+            .into_iter()
+            .find(|format| format.name() == lower)
+            .ok_or_else(|| Error::UnsupportedFormat(format!("unknown format '{}'", name)))
+    }
+
+    /// The name of this format, as given on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Ansi => "ansi",
+            Format::Utf8 => "utf8",
+            Format::Ascii => "ascii",
+            Format::Braille => "braille",
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Sixel => "sixel",
+            Format::Kitty => "kitty",
+        }
+    }
+
+    /// Guess the format from the extension of the given output file.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "svg" => Some(Format::Svg),
+            "png" => Some(Format::Png),
+            "txt" => Some(Format::Utf8),
+            _ => None,
+        }
+    }
+
+    /// File extension for output in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ansi | Format::Utf8 | Format::Ascii | Format::Braille => "txt",
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Sixel => "six",
+            Format::Kitty => "kitty",
+        }
+    }
+
+    /// Whether this is a text format, of which the printed rows can be cleared.
+    pub fn is_text(self) -> bool {
+        matches!(
+            self,
+            Format::Ansi | Format::Utf8 | Format::Ascii | Format::Braille
+        )
+    }
+}
+
+/// Standard output stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// Standard output.
+    Stdout,
+
+    /// Standard error.
+    Stderr,
+}
+
+/// Arguments for printing a QR code.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    /// Where to read the data to encode from.
+    pub input: Input,
+
+    /// Whether to strip a trailing newline from the data.
+    pub trim_newline: bool,
+
+    /// Output format, or `None` to pick one based on the output.
+    pub format: Option<Format>,
+
+    /// File to write the output to, or `None` for stdout.
+    pub output: Option<PathBuf>,
+
+    /// Whether to also print the code in the terminal when writing to a file.
+    pub preview: bool,
+
+    /// Whether to only print the codes, without labels, separators and hints.
+    pub quiet: bool,
+
+    /// Stream to print metadata about each code to, as JSON.
+    pub json: Option<Stream>,
+
+    /// Whether to also copy the encoded data to the clipboard.
+    pub copy: bool,
+
+    /// Whether to print a code for each input line.
+    pub each_line: bool,
+
+    /// Whether to print the code again each time the input file changes.
+    pub watch: bool,
+
+    /// File with labeled entries to print a code for each.
+    pub batch: Option<PathBuf>,
+
+    /// Error correction level.
+    pub ec_level: EcLevel,
+
+    /// QR code version, or `None` to pick the smallest that fits.
+    pub version: Option<i16>,
+
+    /// Width of the quiet zone.
+    pub quiet_zone: usize,
+
+    /// Whether to swap dark and light pixels.
+    pub invert: bool,
+
+    /// Color theme, or `None` for the default.
+    pub theme: Option<Theme>,
+
+    /// Color of dark pixels, overriding the theme.
+    pub fg: Option<TermColor>,
+
+    /// Color of light pixels, overriding the theme.
+    pub bg: Option<TermColor>,
+
+    /// How many times each pixel is repeated horizontally and vertically.
+    pub scale: usize,
+
+    /// Whether to fail instead of warning when the colors are hard to scan.
+    pub strict: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            input: Input::default(),
+            trim_newline: false,
+            format: None,
+            output: None,
+            preview: false,
+            quiet: false,
+            json: None,
+            copy: false,
+            each_line: false,
+            watch: false,
+            batch: None,
+            ec_level: EcLevel::M,
+            version: None,
+            quiet_zone: qr2term::QUIET_ZONE_WIDTH,
+            invert: false,
+            theme: None,
+            fg: None,
+            bg: None,
+            scale: 1,
+            strict: false,
+        }
+    }
+}
+
+impl Args {
+    /// The output format to use.
+    pub fn format(&self) -> Format {
+        self.format
+            .or_else(|| self.output.as_deref().and_then(Format::from_path))
+            .unwrap_or_default()
+    }
+
+    /// The color theme to use, with colors overridden where given.
+    pub fn theme(&self) -> Theme {
+        let theme = self.theme.unwrap_or_default();
+        Theme {
+            dark: self.fg.unwrap_or(theme.dark),
+            light: self.bg.unwrap_or(theme.light),
+        }
+    }
+}
+
+/// Parse the given command line arguments, excluding the binary name.
+pub fn parse<I: IntoIterator<Item = OsString>>(args: I) -> Result<Command, Error> {
+    let mut parsed = Args::default();
+    let mut input = None;
+    let mut only_positional = false;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let arg = into_string(arg)?;
+
+        if only_positional || !arg.starts_with('-') || arg == "-" {
+            if arg == "completions" && !only_positional && input.is_none() {
+                let shell = match args.next() {
+                    Some(shell) => Shell::parse(&into_string(shell)?)?,
+                    None => {
+                        return Err(Error::Usage(
+                            "subcommand 'completions' requires a shell".into(),
+                        ))
+                    }
+                };
+                if args.next().is_some() {
+                    return Err(Error::Usage(
+                        "subcommand 'completions' takes no other arguments".into(),
+                    ));
+                }
+                return Ok(Command::Completions(shell));
+            }
+            let text = match (arg.as_str(), Kind::from_name(&arg)) {
+                ("-", _) if !only_positional => Input::Stdin,
+                (_, Some(kind)) if !only_positional && input.is_none() => {
+                    Input::Payload(Payload::new(kind))
+                }
+                _ => Input::Text(arg),
+            };
+            set_input(&mut input, text)?;
+            continue;
+        }
+
+        // Support both --option value and --option=value
+        let (name, mut inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || match inline.take() {
+            Some(value) => Ok(value),
+            None => args
+                .next()
+                .ok_or_else(|| format!("option '{}' requires a value", name))
+                .and_then(into_string),
+        };
+
+        match name {
+            "--" => only_positional = true,
+            "-f" | "--file" => set_input(&mut input, Input::File(value()?.into()))?,
+            "--trim-newline" => parsed.trim_newline = true,
+            "-t" | "--format" => parsed.format = Some(Format::parse(&value()?)?),
+            "-o" | "--output" => parsed.output = Some(value()?.into()),
+            "--preview" => parsed.preview = true,
+            "-q" | "--quiet" => parsed.quiet = true,
+            "--json" => {
+                parsed.json = match inline.take().as_deref() {
+                    None | Some("stderr") => Some(Stream::Stderr),
+                    Some("stdout") => Some(Stream::Stdout),
+                    Some(stream) => {
+                        return Err(Error::Usage(format!("unknown stream '{}'", stream)))
+                    }
+                }
+            }
+            "--copy" => parsed.copy = true,
+            "--each-line" => parsed.each_line = true,
+            "--watch" => parsed.watch = true,
+            "--batch" => parsed.batch = Some(value()?.into()),
+            "--ec" => parsed.ec_level = parse_ec_level(&value()?)?,
+            "--qr-version" => match value()?.parse() {
+                Ok(version @ 1..=40) => parsed.version = Some(version),
+                _ => {
+                    return Err(Error::Usage(
+                        "QR code version must be a number from 1 to 40".into(),
+                    ))
+                }
+            },
+            "--quiet-zone" => parsed.quiet_zone = parse_number(&value()?, "quiet zone")?,
+            "--invert" => parsed.invert = true,
+            "--theme" => parsed.theme = Some(parse_theme(&value()?)?),
+            "--fg" => parsed.fg = Some(parse_color(&value()?)?),
+            "--bg" => parsed.bg = Some(parse_color(&value()?)?),
+            "--scale" => match parse_number(&value()?, "scale")? {
+                0 => return Err(Error::Usage("scale must be at least 1".into())),
+                scale => parsed.scale = scale,
+            },
+            "--strict" => parsed.strict = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            _ => {
+                let known = match &mut input {
+                    Some(Input::Payload(payload)) => payload.set(name, value)?,
+                    _ => false,
+                };
+                if !known {
+                    return Err(Error::Usage(format!("unknown option '{}'", name)));
+                }
+            }
+        }
+    }
+
+    if parsed.batch.is_some() && (input.is_some() || parsed.each_line || parsed.watch) {
+        return Err(Error::Usage(
+            "option '--batch' can't be combined with other input".into(),
+        ));
+    }
+    parsed.input = input.unwrap_or_default();
+    if parsed.watch && !matches!(parsed.input, Input::File(_)) {
+        return Err(Error::Usage("option '--watch' requires '--file'".into()));
+    }
+    if parsed.watch && parsed.each_line {
+        return Err(Error::Usage(
+            "options '--watch' and '--each-line' can't be combined".into(),
+        ));
+    }
+    Ok(Command::Print(parsed))
+}
+
+/// Parse an error correction level.
+fn parse_ec_level(level: &str) -> Result<EcLevel, String> {
+    match level.to_ascii_uppercase().as_str() {
+        "L" => Ok(EcLevel::L),
+        "M" => Ok(EcLevel::M),
+        "Q" => Ok(EcLevel::Q),
+        "H" => Ok(EcLevel::H),
+        _ => Err(format!("unknown error correction level '{}'", level)),
+    }
+}
+
+/// Parse a built-in theme name.
+fn parse_theme(name: &str) -> Result<Theme, String> {
+    Theme::named(&name.to_ascii_lowercase()).ok_or_else(|| {
+        format!(
+            "unknown theme '{}', expected one of: {}",
+            name,
+            Theme::NAMES.join(", ")
+        )
+    })
+}
+
+/// Parse a color name, ANSI palette index or `#rrggbb` hex color.
+fn parse_color(color: &str) -> Result<TermColor, String> {
+    let invalid = || format!("invalid color '{}'", color);
+    match color.to_ascii_lowercase().as_str() {
+        "black" => Ok(TermColor::Black),
+        "white" => Ok(TermColor::White),
+        hex if hex.starts_with('#') => {
+            let hex = &hex[1..];
+            if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+            Ok(TermColor::Rgb(channel(0)?, channel(2)?, channel(4)?))
+        }
+        index => index.parse().map(TermColor::Ansi).map_err(|_| invalid()),
+    }
+}
+
+/// Parse a non-negative number, described as `what` in errors.
+fn parse_number(value: &str, what: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} must be a non-negative number, got '{}'", what, value))
+}
+
+/// Set the input, failing if it was set before.
+fn set_input(input: &mut Option<Input>, value: Input) -> Result<(), String> {
+    if input.is_some() {
+        return Err("only one input can be given".into());
+    }
+    *input = Some(value);
+    Ok(())
+}
+
+/// Convert an argument into a string, failing if it isn't valid UTF-8.
+fn into_string(arg: OsString) -> Result<String, String> {
+    arg.into_string()
+        .map_err(|arg| format!("argument is not valid UTF-8: {:?}", arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(args: &[&str]) -> Result<Command, Error> {
+        parse(args.iter().map(OsString::from))
+    }
+
+    fn parse_args(args: &[&str]) -> Args {
+        match parse_str(args) {
+            Ok(Command::Print(args)) => args,
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_input() {
+        assert_eq!(parse_args(&[]).input, Input::Stdin);
+        assert_eq!(parse_args(&["-"]).input, Input::Stdin);
+        assert_eq!(parse_args(&["hello"]).input, Input::Text("hello".into()));
+        assert_eq!(
+            parse_args(&["--", "--help"]).input,
+            Input::Text("--help".into())
+        );
+        assert_eq!(
+            parse_args(&["--file", "a.txt"]).input,
+            Input::File("a.txt".into())
+        );
+        assert_eq!(
+            parse_args(&["--file=a.txt"]).input,
+            Input::File("a.txt".into())
+        );
+        assert!(parse_str(&["a", "b"]).is_err());
+        assert!(parse_str(&["a", "-f", "b"]).is_err());
+        assert!(parse_str(&["-f"]).is_err());
+    }
+
+    #[test]
+    fn parse_options() {
+        assert!(matches!(parse_str(&["-h"]), Ok(Command::Help)));
+        assert!(matches!(
+            parse_str(&["text", "--version"]),
+            Ok(Command::Version)
+        ));
+        assert!(parse_args(&["--trim-newline"]).trim_newline);
+        assert_eq!(parse_args(&[]).format(), Format::Ansi);
+        assert_eq!(parse_args(&["-t", "SVG"]).format(), Format::Svg);
+        assert_eq!(parse_args(&["--format=braille"]).format(), Format::Braille);
+        assert!(matches!(
+            parse_str(&["--format", "gif"]),
+            Err(Error::UnsupportedFormat(_))
+        ));
+        assert!(matches!(parse_str(&["--unknown"]), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn parse_output() {
+        let args = parse_args(&["-o", "code.PNG", "--preview"]);
+        assert_eq!(args.output, Some("code.PNG".into()));
+        assert_eq!(args.format(), Format::Png);
+        assert!(args.preview);
+        assert!(!args.copy);
+        assert!(parse_args(&["--copy"]).copy);
+        assert!(parse_args(&["-q"]).quiet);
+        assert_eq!(parse_args(&["--json"]).json, Some(Stream::Stderr));
+        assert_eq!(parse_args(&["--json=stdout"]).json, Some(Stream::Stdout));
+        assert_eq!(
+            parse_args(&["--json", "stdout"]).input,
+            Input::Text("stdout".into())
+        );
+        assert!(parse_str(&["--json=file"]).is_err());
+        assert_eq!(parse_args(&["-o", "code"]).format(), Format::Ansi);
+        assert_eq!(
+            parse_args(&["-o", "a.png", "-t", "svg"]).format(),
+            Format::Svg
+        );
+    }
+
+    #[test]
+    fn parse_generation() {
+        let args = parse_args(&[]);
+        assert_eq!(args.ec_level, EcLevel::M);
+        assert_eq!(args.version, None);
+        assert_eq!(args.quiet_zone, 2);
+
+        let args = parse_args(&["--ec", "h", "--qr-version=7", "--quiet-zone", "4"]);
+        assert_eq!(args.ec_level, EcLevel::H);
+        assert_eq!(args.version, Some(7));
+        assert_eq!(args.quiet_zone, 4);
+
+        assert!(parse_str(&["--ec", "X"]).is_err());
+        assert!(parse_str(&["--qr-version", "41"]).is_err());
+        assert!(parse_str(&["--quiet-zone", "-1"]).is_err());
+    }
+
+    #[test]
+    fn parse_streaming() {
+        assert!(parse_args(&["--each-line"]).each_line);
+        assert!(parse_args(&["--watch", "-f", "a.txt"]).watch);
+        assert!(parse_str(&["--watch"]).is_err());
+        assert!(parse_str(&["--watch", "--each-line", "-f", "a.txt"]).is_err());
+        assert_eq!(
+            parse_args(&["--batch", "devices.tsv"]).batch,
+            Some("devices.tsv".into())
+        );
+        assert!(parse_str(&["--batch", "devices.tsv", "text"]).is_err());
+        assert!(parse_str(&["--batch", "devices.tsv", "--each-line"]).is_err());
+    }
+
+    #[test]
+    fn parse_style() {
+        let args = parse_args(&[]);
+        assert!(!args.invert);
+        assert_eq!(args.theme(), Theme::CLASSIC);
+        assert_eq!(args.scale, 1);
+        assert!(!args.strict);
+
+        let args = parse_args(&[
+            "--strict",
+            "--invert",
+            "--theme",
+            "Nord",
+            "--fg=#FF8000",
+            "--scale",
+            "2",
+        ]);
+        assert!(args.invert);
+        assert_eq!(args.theme().dark, TermColor::Rgb(0xFF, 0x80, 0x00));
+        assert_eq!(args.theme().light, Theme::named("nord").unwrap().light);
+        assert_eq!(args.scale, 2);
+        assert!(args.strict);
+        assert_eq!(parse_args(&["--bg", "231"]).bg, Some(TermColor::Ansi(231)));
+        assert_eq!(parse_args(&["--bg", "black"]).bg, Some(TermColor::Black));
+
+        assert!(parse_str(&["--theme", "pink"]).is_err());
+        assert!(parse_str(&["--fg", "#12345"]).is_err());
+        assert!(parse_str(&["--fg", "256"]).is_err());
+        assert!(parse_str(&["--scale", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_payload() {
+        let mut wifi = Payload::new(Kind::Wifi);
+        wifi.set("--ssid", || Ok("Cafe".into())).unwrap();
+        assert_eq!(
+            parse_args(&["-t", "utf8", "wifi", "--ssid=Cafe", "--scale", "2"]).input,
+            Input::Payload(wifi)
+        );
+        assert_eq!(
+            parse_args(&["--", "wifi"]).input,
+            Input::Text("wifi".into())
+        );
+        assert!(parse_str(&["--ssid", "Cafe", "wifi"]).is_err());
+        assert!(parse_str(&["tel", "--ssid", "Cafe"]).is_err());
+        assert!(parse_str(&["wifi", "text"]).is_err());
+    }
+}
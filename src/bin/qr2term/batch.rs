@@ -0,0 +1,93 @@
+//! Batch mode, printing a labeled code for each entry in a file.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::args::Args;
+use crate::error::Error;
+use crate::{generate, render, show};
+
+/// Print a labeled code for each `label<TAB>payload` line in the given batch file.
+///
+/// Labels and separators are left out in quiet mode. When an output directory is given,
+/// writes a file named after the label for each entry instead. Empty lines are skipped.
+pub fn batch(args: &Args, path: &Path) -> Result<(), Error> {
+    let file = File::open(path).map_err(|err| Error::Read(path.into(), err))?;
+    if let Some(dir) = &args.output {
+        fs::create_dir_all(dir).map_err(|err| Error::Write(dir.clone(), err))?;
+    }
+
+    let mut entries = 0;
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|err| Error::Read(path.into(), err))?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if line.is_empty() {
+            continue;
+        }
+        let (label, payload) = line.split_once('\t').ok_or_else(|| {
+            let err = io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "line {}: expected label and payload separated by a tab",
+                    number + 1
+                ),
+            );
+            Error::Read(path.into(), err)
+        })?;
+
+        match &args.output {
+            Some(dir) => {
+                let format = args.format();
+                let path = entry_path(dir, label, number + 1, format.extension());
+                let output = render(args, &generate(args, payload.as_bytes())?.1, format)?;
+                fs::write(&path, output).map_err(|err| Error::Write(path, err))?;
+            }
+            None => {
+                if !args.quiet {
+                    if entries > 0 {
+                        println!();
+                    }
+                    println!("{}", label);
+                }
+                show(args, payload.as_bytes(), &mut 0)?;
+            }
+        }
+        entries += 1;
+    }
+    Ok(())
+}
+
+/// Path of the output file for an entry, naming it after its label.
+///
+/// Characters that aren't safe in file names are replaced, and entries without label are
+/// named after their line number.
+fn entry_path(dir: &Path, label: &str, line: usize, extension: &str) -> PathBuf {
+    let mut name: String = label
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    if name.trim_matches('.').is_empty() {
+        name = format!("line-{}", line);
+    }
+    dir.join(format!("{}.{}", name, extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_paths() {
+        let dir = Path::new("out");
+        assert_eq!(
+            entry_path(dir, "Room 1/a", 3, "svg"),
+            Path::new("out/Room_1_a.svg")
+        );
+        assert_eq!(entry_path(dir, "..", 3, "png"), Path::new("out/line-3.png"));
+        assert_eq!(entry_path(dir, "", 7, "txt"), Path::new("out/line-7.txt"));
+    }
+}
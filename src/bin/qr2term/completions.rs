@@ -0,0 +1,385 @@
+//! Shell completion scripts.
+
+use std::fmt::Write;
+
+use qr2term::render::Theme;
+
+use crate::payload::Kind;
+
+/// Shell to generate a completion script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// Bash.
+    Bash,
+
+    /// Z shell.
+    Zsh,
+
+    /// Fish.
+    Fish,
+
+    /// PowerShell.
+    Powershell,
+}
+
+impl Shell {
+    /// Parse a shell from its name.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" => Ok(Shell::Powershell),
+            _ => Err(format!(
+                "unknown shell '{}', expected one of: bash, zsh, fish, powershell",
+                name
+            )),
+        }
+    }
+}
+
+/// Value an option takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    /// A flag without value.
+    None,
+
+    /// A file path.
+    Path,
+
+    /// One of the given values.
+    Choice(&'static [&'static str]),
+
+    /// Any value, that can't be completed.
+    Any,
+}
+
+/// Command line option, as used for completion.
+struct Opt {
+    short: Option<char>,
+    long: &'static str,
+    value: Value,
+    help: &'static str,
+}
+
+/// All options of the command line tool, matching the usage information.
+#[rustfmt::skip]
+const OPTIONS: &[Opt] = &[
+    opt(Some('f'), "file", Value::Path, "Read data to encode from a file"),
+    opt(None, "trim-newline", Value::None, "Strip a trailing newline"),
+    opt(Some('t'), "format", Value::Choice(FORMATS), "Output format"),
+    opt(Some('o'), "output", Value::Path, "Write the output to a file"),
+    opt(None, "preview", Value::None, "Also print the code when writing to a file"),
+    opt(Some('q'), "quiet", Value::None, "Only print the codes"),
+    opt(None, "json", Value::None, "Print metadata about each code as JSON"),
+    opt(None, "copy", Value::None, "Also copy the encoded data to the clipboard"),
+    opt(None, "each-line", Value::None, "Print a code for each input line"),
+    opt(None, "watch", Value::None, "Print the code again when the file changes"),
+    opt(None, "batch", Value::Path, "Print a labeled code for each entry in a file"),
+    opt(None, "ec", Value::Choice(&["L", "M", "Q", "H"]), "Error correction level"),
+    opt(None, "qr-version", Value::Any, "QR code version from 1 to 40"),
+    opt(None, "quiet-zone", Value::Any, "Width of the light border"),
+    opt(None, "invert", Value::None, "Swap dark and light pixels"),
+    opt(None, "theme", Value::Choice(&Theme::NAMES), "Color theme"),
+    opt(None, "fg", Value::Any, "Color of dark pixels"),
+    opt(None, "bg", Value::Any, "Color of light pixels"),
+    opt(None, "scale", Value::Any, "Size of each pixel, multiplied"),
+    opt(None, "strict", Value::None, "Fail when the colors are hard to scan"),
+    opt(Some('h'), "help", Value::None, "Print help"),
+    opt(Some('V'), "version", Value::None, "Print version"),
+];
+
+/// Names of all output formats.
+const FORMATS: &[&str] = &[
+    "ansi", "utf8", "ascii", "braille", "svg", "png", "sixel", "kitty",
+];
+
+/// Construct an option, keeping the table above compact.
+const fn opt(short: Option<char>, long: &'static str, value: Value, help: &'static str) -> Opt {
+    Opt {
+        short,
+        long,
+        value,
+        help,
+    }
+}
+
+/// Generate the completion script for the given shell.
+pub fn generate(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash(),
+        Shell::Zsh => zsh(),
+        Shell::Fish => fish(),
+        Shell::Powershell => powershell(),
+    }
+}
+
+/// Names of all subcommands.
+fn commands() -> Vec<&'static str> {
+    let mut commands: Vec<_> = Kind::ALL.iter().map(|kind| kind.name()).collect();
+    commands.push("completions");
+    commands
+}
+
+/// Generate the bash completion script.
+fn bash() -> String {
+    let mut options: Vec<String> = Vec::new();
+    let mut values = String::new();
+    for opt in OPTIONS {
+        let names = match opt.short {
+            Some(short) => format!("-{}|--{}", short, opt.long),
+            None => format!("--{}", opt.long),
+        };
+        options.extend(opt.short.map(|short| format!("-{}", short)));
+        options.push(format!("--{}", opt.long));
+        let reply = match opt.value {
+            Value::None => continue,
+            Value::Path => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+            Value::Choice(choices) => format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                choices.join(" ")
+            ),
+            Value::Any => "COMPREPLY=()".to_string(),
+        };
+        let _ = writeln!(
+            values,
+            "        {})\n            {}\n            return;;",
+            names, reply
+        );
+    }
+
+    let mut fields = String::new();
+    for kind in Kind::ALL {
+        let _ = writeln!(
+            fields,
+            "            {}) words=\"$words {}\";;",
+            kind.name(),
+            field_options(kind).join(" ")
+        );
+    }
+    let _ = writeln!(
+        fields,
+        "            completions) COMPREPLY=($(compgen -W \"bash zsh fish powershell\" -- \"$cur\")); return;;"
+    );
+
+    format!(
+        "_qr2term() {{
+    local cur prev words word
+    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"
+    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"
+
+    case \"$prev\" in
+{values}    esac
+
+    words=\"{options} {commands}\"
+    for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do
+        case \"$word\" in
+{fields}        esac
+    done
+    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))
+}}
+
+complete -F _qr2term qr2term
+",
+        values = values,
+        options = options.join(" "),
+        commands = commands().join(" "),
+        fields = fields,
+    )
+}
+
+/// Generate the zsh completion script.
+fn zsh() -> String {
+    let mut specs = String::new();
+    for opt in OPTIONS {
+        let action = match opt.value {
+            Value::None => String::new(),
+            Value::Path => ":path:_files".to_string(),
+            Value::Choice(choices) => format!(":value:({})", choices.join(" ")),
+            Value::Any => ":value: ".to_string(),
+        };
+        let help = opt.help.replace('\'', "'\\''");
+        match opt.short {
+            Some(short) => {
+                let _ = writeln!(
+                    specs,
+                    "    '(-{short} --{long})'{{-{short},--{long}}}'[{help}]{action}' \\",
+                    short = short,
+                    long = opt.long,
+                    help = help,
+                    action = action
+                );
+            }
+            None => {
+                let _ = writeln!(specs, "    '--{}[{}]{}' \\", opt.long, help, action);
+            }
+        }
+    }
+
+    let mut fields = String::new();
+    for kind in Kind::ALL {
+        let _ = writeln!(
+            fields,
+            "        {}) _values -w field {} ;;",
+            kind.name(),
+            field_options(kind).join(" ")
+        );
+    }
+
+    format!(
+        "#compdef qr2term
+
+_qr2term() {{
+    local curcontext=\"$curcontext\" state line
+    _arguments -s -C \\
+{specs}    '1:: :->command' \\
+    '*:: :->fields'
+
+    case $state in
+    command)
+        _values command {commands}
+        ;;
+    fields)
+        case $line[1] in
+{fields}        completions) _values shell bash zsh fish powershell ;;
+        esac
+        ;;
+    esac
+}}
+
+_qr2term \"$@\"
+",
+        specs = specs,
+        commands = commands().join(" "),
+        fields = fields,
+    )
+}
+
+/// Generate the fish completion script.
+fn fish() -> String {
+    let mut out = String::from("complete -c qr2term -f\n");
+    for opt in OPTIONS {
+        let _ = write!(out, "complete -c qr2term");
+        if let Some(short) = opt.short {
+            let _ = write!(out, " -s {}", short);
+        }
+        let _ = write!(out, " -l {}", opt.long);
+        match opt.value {
+            Value::None => {}
+            Value::Path => out.push_str(" -r -F"),
+            Value::Choice(choices) => {
+                let _ = write!(out, " -x -a '{}'", choices.join(" "));
+            }
+            Value::Any => out.push_str(" -x"),
+        }
+        let _ = writeln!(out, " -d '{}'", opt.help.replace('\'', "\\'"));
+    }
+
+    let commands = commands().join(" ");
+    for command in commands.split(' ') {
+        let _ = writeln!(
+            out,
+            "complete -c qr2term -n 'not __fish_seen_subcommand_from {}' -a {}",
+            commands, command
+        );
+    }
+    for kind in Kind::ALL {
+        for (field, label) in kind.fields() {
+            let _ = writeln!(
+                out,
+                "complete -c qr2term -n '__fish_seen_subcommand_from {}' -l {} -d '{}'",
+                kind.name(),
+                field,
+                label
+            );
+        }
+    }
+    out.push_str(
+        "complete -c qr2term -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish powershell'\n",
+    );
+    out
+}
+
+/// Generate the PowerShell completion script.
+fn powershell() -> String {
+    let mut words: Vec<String> = Vec::new();
+    for opt in OPTIONS {
+        words.extend(opt.short.map(|short| format!("-{}", short)));
+        words.push(format!("--{}", opt.long));
+    }
+    words.extend(commands().iter().map(|command| command.to_string()));
+    for kind in Kind::ALL {
+        for option in field_options(kind) {
+            if !words.contains(&option) {
+                words.push(option);
+            }
+        }
+    }
+    let words: Vec<String> = words.iter().map(|word| format!("'{}'", word)).collect();
+
+    format!(
+        "Register-ArgumentCompleter -Native -CommandName qr2term -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+    @({words}) |
+        Where-Object {{ $_ -like \"$wordToComplete*\" }} |
+        ForEach-Object {{
+            [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+        }}
+}}
+",
+        words = words.join(", "),
+    )
+}
+
+/// The options for the fields of the given payload kind.
+fn field_options(kind: Kind) -> Vec<String> {
+    kind.fields()
+        .iter()
+        .map(|(field, _)| format!("--{}", field))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::{self, Command};
+    use std::ffi::OsString;
+
+    fn parses(args: &[&str]) -> bool {
+        args::parse(args.iter().map(OsString::from)).is_ok()
+    }
+
+    #[test]
+    fn options_match_parser() {
+        for opt in OPTIONS {
+            let long = format!("--{}", opt.long);
+            match opt.value {
+                Value::None => assert!(parses(&[&long, "--file", "a"]), "{}", long),
+                Value::Path | Value::Any => assert!(parses(&[&long, "1"]), "{}", long),
+                Value::Choice(choices) => {
+                    for choice in choices {
+                        assert!(parses(&[&long, choice]), "{} {}", long, choice);
+                    }
+                }
+            }
+            if let Some(short) = opt.short {
+                assert!(args::USAGE.contains(&format!("-{}, {}", short, long)));
+            } else {
+                assert!(args::USAGE.contains(&format!("    {}", long)));
+            }
+        }
+    }
+
+    #[test]
+    fn generate_scripts() {
+        assert!(generate(Shell::Bash).contains("complete -F _qr2term qr2term"));
+        assert!(generate(Shell::Bash).contains("wifi) words=\"$words --ssid"));
+        assert!(generate(Shell::Zsh).starts_with("#compdef qr2term"));
+        assert!(generate(Shell::Fish).contains("-s t -l format -x -a 'ansi utf8"));
+        assert!(generate(Shell::Powershell).contains("'--theme'"));
+        assert_eq!(
+            args::parse(["completions", "fish"].iter().map(OsString::from)).ok(),
+            Some(Command::Completions(Shell::Fish))
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Command line tool errors.
+
+use std::path::PathBuf;
+use std::{fmt, io};
+
+use qr2term::payload::PayloadError;
+use qr2term::QrError;
+
+/// Error that makes the command line tool fail.
+#[derive(Debug)]
+pub enum Error {
+    /// The command line arguments are invalid.
+    Usage(String),
+
+    /// Reading input or writing output failed.
+    Io(io::Error),
+
+    /// Reading the given input file failed.
+    Read(PathBuf, io::Error),
+
+    /// Writing the given output file failed.
+    Write(PathBuf, io::Error),
+
+    /// Generating the QR code failed.
+    Qr(QrError),
+
+    /// Building the payload from its fields failed.
+    Payload(PayloadError),
+
+    /// The code is wider than the terminal it is printed in.
+    TerminalTooSmall {
+        /// Width of the code in columns.
+        width: usize,
+
+        /// Width of the terminal in columns.
+        columns: usize,
+    },
+
+    /// The output format is unknown, or can't be printed where the output goes.
+    UnsupportedFormat(String),
+}
+
+impl Error {
+    /// Exit code of the process for this error, as documented in the usage information.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) => 2,
+            Error::Qr(QrError::DataTooLong)
+            | Error::Payload(PayloadError::Encode(QrError::DataTooLong)) => 3,
+            Error::TerminalTooSmall { .. } => 4,
+            Error::UnsupportedFormat(_) => 5,
+            _ => 1,
+        }
+    }
+
+    /// Whether this error is about how the tool was invoked.
+    pub fn is_usage(&self) -> bool {
+        matches!(self, Error::Usage(_) | Error::UnsupportedFormat(_))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage(err) => write!(f, "{}", err),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Read(path, err) => write!(f, "failed to read '{}': {}", path.display(), err),
+            Error::Write(path, err) => {
+                write!(f, "failed to write '{}': {}", path.display(), err)
+            }
+            Error::Qr(err) => write!(f, "failed to generate QR code: {}", err),
+            Error::Payload(err) => write!(f, "{}", err),
+            Error::TerminalTooSmall { width, columns } => write!(
+                f,
+                "code is {} columns wide, but the terminal has only {}",
+                width, columns
+            ),
+            Error::UnsupportedFormat(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(err: String) -> Self {
+        Error::Usage(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        // The renderer reports invalid options through its I/O errors
+        if err
+            .get_ref()
+            .map_or(false, |inner| inner.is::<qr2term::Error>())
+        {
+            return qr2term::Error::from(err).into();
+        }
+        Error::Io(err)
+    }
+}
+
+impl From<qr2term::Error> for Error {
+    fn from(err: qr2term::Error) -> Self {
+        match err {
+            qr2term::Error::Qr(err) => Error::Qr(err),
+            qr2term::Error::Payload(err) => Error::Payload(err),
+            qr2term::Error::Io(err) => Error::Io(err),
+            qr2term::Error::TerminalTooSmall { width, columns } => {
+                Error::TerminalTooSmall { width, columns }
+            }
+            err @ qr2term::Error::InvalidOption(_) => Error::Usage(err.to_string()),
+            err => Error::Io(io::Error::new(io::ErrorKind::Other, err)),
+        }
+    }
+}
+
+impl From<QrError> for Error {
+    fn from(err: QrError) -> Self {
+        Error::Qr(err)
+    }
+}
+
+impl From<PayloadError> for Error {
+    fn from(err: PayloadError) -> Self {
+        Error::Payload(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(Error::Usage("bad".into()).exit_code(), 2);
+        assert_eq!(Error::Qr(QrError::DataTooLong).exit_code(), 3);
+        assert_eq!(
+            Error::Payload(PayloadError::Encode(QrError::DataTooLong)).exit_code(),
+            3
+        );
+        assert_eq!(Error::Qr(QrError::InvalidVersion).exit_code(), 1);
+        let small = Error::TerminalTooSmall {
+            width: 50,
+            columns: 40,
+        };
+        assert_eq!(small.exit_code(), 4);
+        assert_eq!(Error::UnsupportedFormat("gif".into()).exit_code(), 5);
+
+        let strict = io::Error::new(
+            io::ErrorKind::InvalidInput,
+            qr2term::Error::InvalidOption("low contrast".into()),
+        );
+        assert_eq!(Error::from(strict).exit_code(), 2);
+        assert_eq!(
+            Error::from(io::Error::from(io::ErrorKind::BrokenPipe)).exit_code(),
+            1
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Command line tool printing text as QR code in the terminal.
+
+mod args;
+mod batch;
+mod completions;
+mod error;
+mod payload;
+mod stream;
+
+use std::io::{self, Read, Write};
+use std::{fs, process};
+
+use args::{Args, Command, Format, Input, Stream};
+use crossterm::cursor::MoveUp;
+use crossterm::queue;
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::tty::IsTty;
+use error::Error;
+use qr2term::matrix::Matrix;
+use qr2term::qr::Qr;
+use qr2term::render::{Color, Density, QrLight, Renderer};
+use qr2term::term::ColorDepth;
+
+/// Size of image pixels per barcode pixel, for image formats.
+const IMAGE_SCALE: usize = 8;
+
+fn main() {
+    let command = match args::parse(std::env::args_os().skip(1)) {
+        Ok(command) => command,
+        Err(err) => fail(&err, false),
+    };
+    let quiet = matches!(&command, Command::Print(args) if args.quiet);
+    if let Err(err) = run(command) {
+        fail(&err, quiet);
+    }
+}
+
+/// Print the given error and exit with its exit code, hinting at the help unless quiet.
+fn fail(err: &Error, quiet: bool) -> ! {
+    eprintln!("qr2term: error: {}", err);
+    if err.is_usage() && !quiet {
+        eprintln!("\nFor more information, try '--help'.");
+    }
+    process::exit(err.exit_code());
+}
+
+/// Run the given command.
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Help => print!("{}", args::USAGE),
+        Command::Version => println!("qr2term {}", env!("CARGO_PKG_VERSION")),
+        Command::Print(args) => print(&args)?,
+        Command::Completions(shell) => print!("{}", completions::generate(shell)),
+    }
+    Ok(())
+}
+
+/// Print the QR code described by the given arguments.
+fn print(args: &Args) -> Result<(), Error> {
+    if let Some(path) = &args.batch {
+        return batch::batch(args, path);
+    }
+    if args.each_line {
+        return stream::each_line(args);
+    }
+    if args.watch {
+        return stream::watch(args);
+    }
+    show(args, &read_input(args)?, &mut 0)
+}
+
+/// Show the QR code for the given data, replacing the `shown` rows printed before.
+///
+/// Updates `shown` to the number of rows printed to the terminal, if known.
+fn show(args: &Args, data: &[u8], shown: &mut usize) -> Result<(), Error> {
+    let (qr, matrix) = generate(args, data)?;
+    if args.copy {
+        copy(data)?;
+    }
+
+    let format = args.format();
+    if args.output.is_none() {
+        check_terminal(args, &matrix, format)?;
+    }
+    if !args.quiet && !args.strict {
+        for warning in renderer(args, format).map_or(Vec::new(), |r| r.warnings()) {
+            eprintln!("qr2term: warning: {}", warning);
+        }
+    }
+    let output = render(args, &matrix, format)?;
+
+    // Metadata on stdout goes above the code, and is replaced along with it
+    let mut stdout = Vec::new();
+    if let Some(stream) = args.json {
+        let json = metadata(args, &qr, &matrix, format, data.len());
+        match stream {
+            Stream::Stdout => writeln!(stdout, "{}", json)?,
+            Stream::Stderr => writeln!(io::stderr(), "{}", json)?,
+        }
+    }
+    let format = match &args.output {
+        Some(path) => {
+            fs::write(path, output).map_err(|err| Error::Write(path.clone(), err))?;
+            if !args.preview {
+                write_stdout(&stdout, *shown)?;
+                *shown = 0;
+                return Ok(());
+            }
+            check_terminal(args, &matrix, Format::Ansi)?;
+            stdout.extend(render(args, &matrix, Format::Ansi)?);
+            Format::Ansi
+        }
+        None => {
+            stdout.extend(output);
+            format
+        }
+    };
+
+    write_stdout(&stdout, *shown)?;
+    *shown = if format.is_text() && io::stdout().is_tty() {
+        stdout.iter().filter(|b| **b == b'\n').count()
+    } else {
+        0
+    };
+    Ok(())
+}
+
+/// Generate the QR code for the given data, and its matrix including the quiet zone.
+fn generate(args: &Args, data: &[u8]) -> Result<(Qr, Matrix<Color>), Error> {
+    let qr = match args.version {
+        Some(version) => Qr::with_version(data, version, args.ec_level)?,
+        None => Qr::with_error_correction_level(data, args.ec_level)?,
+    };
+    let mut matrix = qr.to_matrix();
+    matrix.surround(args.quiet_zone, QrLight);
+    Ok((qr, matrix))
+}
+
+/// Describe the generated code as JSON object, for automation around the tool.
+///
+/// The rendered size in columns and rows is `null` for image formats.
+fn metadata(
+    args: &Args,
+    qr: &Qr,
+    matrix: &Matrix<Color>,
+    format: Format,
+    payload_length: usize,
+) -> String {
+    let (cols, rows) = match renderer(args, format) {
+        Some(renderer) => (
+            renderer.width(matrix).to_string(),
+            renderer.height(matrix).to_string(),
+        ),
+        None => ("null".into(), "null".into()),
+    };
+    format!(
+        "{{\"version\":{},\"ec_level\":\"{:?}\",\"modules\":{},\"quiet_zone\":{},\
+        \"format\":\"{}\",\"cols\":{},\"rows\":{},\"payload_length\":{}}}",
+        qr.version(),
+        qr.error_correction_level(),
+        matrix.size() - 2 * args.quiet_zone,
+        args.quiet_zone,
+        format.name(),
+        cols,
+        rows,
+        payload_length,
+    )
+}
+
+/// Write the given output to stdout, clearing the given number of rows above first.
+///
+/// Rows are only cleared if stdout is a terminal, to keep escape sequences out of files
+/// and pipes.
+fn write_stdout(output: &[u8], clear: usize) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    if clear > 0 && stdout.is_tty() {
+        let rows = u16::try_from(clear).unwrap_or(u16::MAX);
+        queue!(stdout, MoveUp(rows), Clear(ClearType::FromCursorDown))?;
+    }
+    stdout.write_all(output)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Copy the given data to the clipboard, through the terminal attached to stdout or stderr.
+fn copy(data: &[u8]) -> Result<(), Error> {
+    if io::stdout().is_tty() {
+        qr2term::clipboard::copy(data, &mut io::stdout().lock())?;
+    } else if io::stderr().is_tty() {
+        qr2term::clipboard::copy(data, &mut io::stderr().lock())?;
+    } else {
+        return Err(Error::Usage("option '--copy' requires a terminal".into()));
+    }
+    Ok(())
+}
+
+/// Check whether the code can be printed in the given format to the terminal on stdout.
+///
+/// Always succeeds if stdout isn't a terminal.
+fn check_terminal(args: &Args, matrix: &Matrix<Color>, format: Format) -> Result<(), Error> {
+    if !io::stdout().is_tty() {
+        return Ok(());
+    }
+    if format == Format::Png {
+        return Err(Error::UnsupportedFormat(
+            "format 'png' can't be printed in the terminal, use '--output'".into(),
+        ));
+    }
+    if let (Some(renderer), Some(columns)) = (renderer(args, format), qr2term::term::columns()) {
+        let width = renderer.width(matrix);
+        if width > columns {
+            return Err(Error::TerminalTooSmall { width, columns });
+        }
+    }
+    Ok(())
+}
+
+/// The renderer for the given text format, or `None` for image formats.
+///
+/// Colors are limited to what the terminal supports, unless writing to a file.
+fn renderer(args: &Args, format: Format) -> Option<Renderer> {
+    let (density, colors) = match format {
+        Format::Ansi => (Density::HalfBlock, true),
+        Format::Utf8 => (Density::HalfBlock, false),
+        Format::Ascii => (Density::Full, false),
+        Format::Braille => (Density::Braille, false),
+        Format::Svg | Format::Png | Format::Sixel | Format::Kitty => return None,
+    };
+
+    // Stick to ASCII in terminals with a legacy character set, unless asked otherwise
+    let density = match (args.format, &args.output, qr2term::term::locale_utf8()) {
+        (None, None, Some(false)) => Density::Full,
+        _ => density,
+    };
+    Some(
+        Renderer::default()
+            .density(density)
+            .colors(colors)
+            .theme(args.theme())
+            .invert(args.invert)
+            .scale(args.scale)
+            .strict(args.strict)
+            .color_depth(match args.output {
+                Some(_) => ColorDepth::TrueColor,
+                None => qr2term::term::capabilities().color,
+            }),
+    )
+}
+
+/// Render the barcode in the given format, styled as described by the arguments.
+fn render(args: &Args, matrix: &Matrix<Color>, format: Format) -> Result<Vec<u8>, Error> {
+    if let Some(renderer) = renderer(args, format) {
+        let mut out = Vec::new();
+        renderer.render(matrix, &mut out)?;
+        return Ok(out);
+    }
+
+    // Image exporters always draw dark on light, so invert the pixels up front
+    let inverted;
+    let image = if args.invert {
+        inverted = Matrix::new(matrix.pixels().iter().map(|pixel| !*pixel).collect());
+        &inverted
+    } else {
+        matrix
+    };
+    let scale = IMAGE_SCALE * args.scale;
+
+    match format {
+        Format::Ansi | Format::Utf8 | Format::Ascii | Format::Braille => {
+            unreachable!("text formats are rendered above")
+        }
+        Format::Svg => Ok(qr2term::export::svg(image, scale).into_bytes()),
+        Format::Png => Ok(qr2term::export::png(image, scale)),
+        Format::Sixel => Ok(qr2term::export::sixel(image, scale).into_bytes()),
+        Format::Kitty => Ok(qr2term::export::kitty(image, scale).into_bytes()),
+    }
+}
+
+/// Read the data to encode, as raw bytes.
+fn read_input(args: &Args) -> Result<Vec<u8>, Error> {
+    let mut data = match &args.input {
+        Input::Text(text) => text.clone().into_bytes(),
+        Input::Stdin => {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data)?;
+            data
+        }
+        Input::File(path) => fs::read(path).map_err(|err| Error::Read(path.clone(), err))?,
+        Input::Payload(payload) => payload.build()?.into_bytes(),
+    };
+
+    if args.trim_newline {
+        if data.ends_with(b"\r\n") {
+            data.truncate(data.len() - 2);
+        } else if data.ends_with(b"\n") {
+            data.pop();
+        }
+    }
+    Ok(data)
+}
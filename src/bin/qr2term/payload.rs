@@ -0,0 +1,344 @@
+//! Payload subcommands, building the data to encode from separate fields.
+
+use std::io::{self, BufRead, Write};
+
+use crossterm::tty::IsTty;
+use qr2term::payload::{Email, Geo, Phone, Security, Totp, Url, VCard, Wifi};
+
+use crate::error::Error;
+
+/// Kind of payload to build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// WiFi network credentials.
+    Wifi,
+
+    /// Time based one-time password secret.
+    Totp,
+
+    /// Contact card.
+    VCard,
+
+    /// Email message.
+    Mailto,
+
+    /// Phone number.
+    Tel,
+
+    /// Geographic location.
+    Geo,
+
+    /// Web link.
+    Url,
+}
+
+impl Kind {
+    /// All payload kinds.
+    pub const ALL: [Kind; 7] = [
+        Kind::Wifi,
+        Kind::Totp,
+        Kind::VCard,
+        Kind::Mailto,
+        Kind::Tel,
+        Kind::Geo,
+        Kind::Url,
+    ];
+
+    /// Get the payload kind for the given subcommand name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Kind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The subcommand name of this payload kind.
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Wifi => "wifi",
+            Kind::Totp => "totp",
+            Kind::VCard => "vcard",
+            Kind::Mailto => "mailto",
+            Kind::Tel => "tel",
+            Kind::Geo => "geo",
+            Kind::Url => "url",
+        }
+    }
+
+    /// The fields of this payload kind, as option name and prompt label.
+    pub fn fields(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Kind::Wifi => &[
+                ("ssid", "Network name"),
+                ("password", "Password"),
+                ("security", "Security"),
+                ("hidden", "Hidden"),
+            ],
+            Kind::Totp => &[
+                ("account", "Account"),
+                ("secret", "Secret (Base32)"),
+                ("issuer", "Issuer"),
+                ("digits", "Digits"),
+                ("period", "Period"),
+            ],
+            Kind::VCard => &[
+                ("name", "Name"),
+                ("org", "Organization"),
+                ("phone", "Phone"),
+                ("email", "Email"),
+                ("url", "Website"),
+            ],
+            Kind::Mailto => &[
+                ("to", "To"),
+                ("cc", "Cc"),
+                ("subject", "Subject"),
+                ("body", "Body"),
+            ],
+            Kind::Tel => &[("number", "Phone number")],
+            Kind::Geo => &[
+                ("lat", "Latitude"),
+                ("lon", "Longitude"),
+                ("query", "Query"),
+            ],
+            Kind::Url => &[("url", "URL")],
+        }
+    }
+}
+
+/// Payload subcommand with the fields given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payload {
+    /// Kind of payload.
+    pub kind: Kind,
+
+    /// Given fields, as option name and value.
+    values: Vec<(&'static str, String)>,
+}
+
+impl Payload {
+    /// Construct a payload of the given kind without any fields set.
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            values: Vec::new(),
+        }
+    }
+
+    /// Set the field for option `name`, taking its value from `value`.
+    ///
+    /// Returns `false` if this payload kind has no such option.
+    pub fn set<F>(&mut self, name: &str, value: F) -> Result<bool, String>
+    where
+        F: FnOnce() -> Result<String, String>,
+    {
+        let field = name.strip_prefix("--").and_then(|name| {
+            self.kind
+                .fields()
+                .iter()
+                .map(|(field, _)| *field)
+                .find(|field| *field == name)
+        });
+        let field = match field {
+            Some(field) => field,
+            None => return Ok(false),
+        };
+        if self.get(field).is_some() {
+            return Err(format!("option '{}' can only be given once", name));
+        }
+
+        // Flags don't take a value
+        let value = match (self.kind, field) {
+            (Kind::Wifi, "hidden") => String::from("true"),
+            _ => value()?,
+        };
+        self.values.push((field, value));
+        Ok(true)
+    }
+
+    /// Build the payload, prompting for missing required fields.
+    pub fn build(&self) -> Result<String, Error> {
+        let payload = match self.kind {
+            Kind::Wifi => {
+                let security = match self.get("security") {
+                    None => Security::Wpa,
+                    Some(security) => match security.to_ascii_lowercase().as_str() {
+                        "wpa" => Security::Wpa,
+                        "wep" => Security::Wep,
+                        "none" => Security::None,
+                        _ => return Err(usage(format!("unknown security '{}'", security))),
+                    },
+                };
+                let mut wifi = Wifi::new(self.require("ssid")?)
+                    .security(security)
+                    .hidden(self.get("hidden").is_some());
+                if security != Security::None {
+                    wifi = wifi.password(self.require("password")?);
+                }
+                wifi.to_string()
+            }
+            Kind::Totp => {
+                let mut totp =
+                    Totp::from_base32(self.require("account")?, &self.require("secret")?)?;
+                if let Some(issuer) = self.get("issuer") {
+                    totp = totp.issuer(issuer);
+                }
+                if let Some(digits) = self.get("digits") {
+                    totp = totp.digits(parse(digits, "digits")?);
+                }
+                if let Some(period) = self.get("period") {
+                    totp = totp.period(parse(period, "period")?);
+                }
+                totp.to_string()
+            }
+            Kind::VCard => {
+                let mut vcard = VCard::new(self.require("name")?);
+                if let Some(org) = self.get("org") {
+                    vcard = vcard.org(org);
+                }
+                if let Some(phone) = self.get("phone") {
+                    vcard = vcard.phone(phone);
+                }
+                if let Some(email) = self.get("email") {
+                    vcard = vcard.email(email);
+                }
+                if let Some(url) = self.get("url") {
+                    vcard = vcard.url(url);
+                }
+                vcard.to_string()
+            }
+            Kind::Mailto => {
+                let mut email = Email::new(self.require("to")?);
+                if let Some(cc) = self.get("cc") {
+                    email = email.cc(cc);
+                }
+                if let Some(subject) = self.get("subject") {
+                    email = email.subject(subject);
+                }
+                if let Some(body) = self.get("body") {
+                    email = email.body(body);
+                }
+                email.to_string()
+            }
+            Kind::Tel => Phone::new(self.require("number")?)?.to_string(),
+            Kind::Geo => {
+                let lat = parse(&self.require("lat")?, "latitude")?;
+                let lon = parse(&self.require("lon")?, "longitude")?;
+                let mut geo = Geo::new(lat, lon)?;
+                if let Some(query) = self.get("query") {
+                    geo = geo.query(query);
+                }
+                geo.to_string()
+            }
+            Kind::Url => Url::new(self.require("url")?)?.to_string(),
+        };
+        Ok(payload)
+    }
+
+    /// Get the value of the given field, if set.
+    fn get(&self, field: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the value of the given required field, prompting for it if not set.
+    ///
+    /// Fails if the field isn't set and stdin isn't a terminal to prompt on.
+    fn require(&self, field: &str) -> Result<String, Error> {
+        if let Some(value) = self.get(field) {
+            return Ok(value.into());
+        }
+
+        let missing = || usage(format!("missing required option '--{}'", field));
+        let stdin = io::stdin();
+        if !stdin.is_tty() {
+            return Err(missing());
+        }
+        let label = self
+            .kind
+            .fields()
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map_or(field, |(_, label)| *label);
+
+        loop {
+            eprint!("{}: ", label);
+            io::stderr().flush()?;
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Err(missing());
+            }
+            let value = line.trim_end_matches(['\r', '\n']);
+            if !value.is_empty() {
+                return Ok(value.into());
+            }
+        }
+    }
+}
+
+/// Parse a numeric field value, described as `what` in errors.
+fn parse<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| usage(format!("invalid {} '{}'", what, value)))
+}
+
+/// Construct a usage error.
+fn usage(message: String) -> Error {
+    Error::Usage(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(kind: Kind, fields: &[(&str, &str)]) -> Payload {
+        let mut payload = Payload::new(kind);
+        for (name, value) in fields {
+            let value = value.to_string();
+            assert_eq!(payload.set(name, || Ok(value)), Ok(true));
+        }
+        payload
+    }
+
+    #[test]
+    fn set_fields() {
+        let mut wifi = Payload::new(Kind::Wifi);
+        assert_eq!(wifi.set("--ssid", || Ok("Cafe".into())), Ok(true));
+        assert_eq!(
+            wifi.set("--hidden", || panic!("flag takes no value")),
+            Ok(true)
+        );
+        assert_eq!(wifi.set("--number", || Ok("1".into())), Ok(false));
+        assert!(wifi.set("--ssid", || Ok("Other".into())).is_err());
+    }
+
+    #[test]
+    fn build_payloads() {
+        let wifi = payload(Kind::Wifi, &[("--ssid", "Cafe"), ("--password", "secret")]);
+        assert_eq!(wifi.build().unwrap(), "WIFI:S:Cafe;T:WPA;P:secret;;");
+        let open = payload(Kind::Wifi, &[("--ssid", "Cafe"), ("--security", "none")]);
+        assert_eq!(open.build().unwrap(), "WIFI:S:Cafe;T:nopass;;");
+
+        let totp = payload(
+            Kind::Totp,
+            &[
+                ("--account", "alice"),
+                ("--secret", "JBSWY3DP"),
+                ("--digits", "8"),
+            ],
+        );
+        let totp = totp.build().unwrap();
+        assert!(totp.starts_with("otpauth://totp/alice?secret=JBSWY3DP"));
+        assert!(totp.contains("digits=8"));
+
+        let mailto = payload(Kind::Mailto, &[("--to", "a@b.c"), ("--subject", "Hi")]);
+        assert_eq!(mailto.build().unwrap(), "mailto:a@b.c?subject=Hi");
+
+        let url = payload(Kind::Url, &[("--url", "https://example.com/")]);
+        assert_eq!(url.build().unwrap(), "https://example.com/");
+
+        let geo = payload(Kind::Geo, &[("--lat", "north"), ("--lon", "0")]);
+        assert!(matches!(geo.build(), Err(Error::Usage(_))));
+        let wifi = payload(Kind::Wifi, &[("--ssid", "Cafe"), ("--security", "wpa4")]);
+        assert!(matches!(wifi.build(), Err(Error::Usage(_))));
+    }
+}
@@ -0,0 +1,62 @@
+//! Streaming modes, printing a fresh code for new input.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Cursor};
+use std::thread;
+use std::time::Duration;
+
+use crate::args::{Args, Input};
+use crate::error::Error;
+use crate::{read_input, show};
+
+/// How often to check the watched file for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Print a code for each line of input, replacing the previous one.
+///
+/// Empty lines are skipped.
+pub fn each_line(args: &Args) -> Result<(), Error> {
+    let reader: Box<dyn BufRead> = match &args.input {
+        Input::Text(text) => Box::new(Cursor::new(text.clone().into_bytes())),
+        Input::Stdin => Box::new(io::stdin().lock()),
+        Input::File(path) => Box::new(BufReader::new(
+            File::open(path).map_err(|err| Error::Read(path.clone(), err))?,
+        )),
+        Input::Payload(payload) => Box::new(Cursor::new(payload.build()?.into_bytes())),
+    };
+
+    let mut shown = 0;
+    for line in reader.split(b'\n') {
+        let mut line = line?;
+        if line.ends_with(b"\r") {
+            line.pop();
+        }
+        if !line.is_empty() {
+            show(args, &line, &mut shown)?;
+        }
+    }
+    Ok(())
+}
+
+/// Print the code for the input file each time it changes, replacing the previous one.
+///
+/// Runs until interrupted.
+pub fn watch(args: &Args) -> Result<(), Error> {
+    let path = match &args.input {
+        Input::File(path) => path,
+        _ => return Err(Error::Usage("option '--watch' requires '--file'".into())),
+    };
+
+    let mut shown = 0;
+    let mut last_modified = None;
+    loop {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|err| Error::Read(path.clone(), err))?;
+        if last_modified != Some(modified) {
+            last_modified = Some(modified);
+            show(args, &read_input(args)?, &mut shown)?;
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
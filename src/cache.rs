@@ -0,0 +1,146 @@
+//! Memoization of rendered QR codes.
+//!
+//! Interactive tools redraw the same code on every resize or screen refresh. A [`Cache`]
+//! keeps the most recently rendered codes, keyed by their data and options, so redrawing
+//! skips encoding and rendering entirely.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::cache::Cache;
+//!
+//! let mut cache = Cache::new(8);
+//! for _ in 0..3 {
+//!     print!("{}", cache.generate_qr_string("https://rust-lang.org/").unwrap());
+//! }
+//! assert_eq!(cache.len(), 1);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::{generate_qr_string_with, options, Error, Options};
+
+/// Cache of rendered QR codes, evicting the least recently used one when full.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    capacity: usize,
+
+    /// Entries from least to most recently used.
+    entries: VecDeque<Entry>,
+}
+
+/// Rendered code, with the data and options it was rendered from.
+#[derive(Debug, Clone)]
+struct Entry {
+    data: Vec<u8>,
+    options: Options,
+    rendered: String,
+}
+
+impl Cache {
+    /// Construct a new cache holding up to `capacity` codes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be at least 1");
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Generate a `String` from the given `data` as QR code, or get it from the cache.
+    ///
+    /// Same as [`generate_qr_string`](crate::generate_qr_string), using the default
+    /// options with the environment overrides applied.
+    pub fn generate_qr_string<D: AsRef<[u8]>>(&mut self, data: D) -> Result<&str, Error> {
+        self.generate_qr_string_with(data, &options())
+    }
+
+    /// Generate a `String` from the given `data` as QR code with the given options, or get
+    /// it from the cache.
+    pub fn generate_qr_string_with<D: AsRef<[u8]>>(
+        &mut self,
+        data: D,
+        options: &Options,
+    ) -> Result<&str, Error> {
+        let data = data.as_ref();
+        let hit = self
+            .entries
+            .iter()
+            .position(|entry| entry.data == data && entry.options == *options);
+        let entry = match hit.and_then(|index| self.entries.remove(index)) {
+            Some(entry) => entry,
+            None => {
+                let rendered = generate_qr_string_with(data, options)?;
+                if self.entries.len() == self.capacity {
+                    self.entries.pop_front();
+                }
+                Entry {
+                    data: data.into(),
+                    options: options.clone(),
+                    rendered,
+                }
+            }
+        };
+        self.entries.push_back(entry);
+        Ok(&self.entries.back().expect("entry was just added").rendered)
+    }
+
+    /// Get the number of cached codes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no codes.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all cached codes.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used() {
+        let mut cache = Cache::new(2);
+        let options = Options::default();
+        let a = cache
+            .generate_qr_string_with("a", &options)
+            .unwrap()
+            .to_owned();
+        assert_eq!(a, generate_qr_string_with("a", &options).unwrap());
+        cache.generate_qr_string_with("b", &options).unwrap();
+        cache.generate_qr_string_with("a", &options).unwrap();
+        cache.generate_qr_string_with("c", &options).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // Evicted the least recently used "b", keeping "a"
+        let data: Vec<_> = cache.entries.iter().map(|e| e.data.clone()).collect();
+        assert_eq!(data, [b"a".to_vec(), b"c".to_vec()]);
+
+        // Keyed by options too
+        let inverted = options.clone().invert(true);
+        let b = cache
+            .generate_qr_string_with("a", &inverted)
+            .unwrap()
+            .to_owned();
+        assert_ne!(a, b);
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache
+            .generate_qr_string_with([b'a'; 4000], &options)
+            .is_err());
+        assert_eq!(cache.len(), 2);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
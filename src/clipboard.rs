@@ -0,0 +1,40 @@
+//! Copying to the clipboard through the terminal.
+//!
+//! Uses the OSC 52 escape sequence, which most terminal emulators support and which
+//! keeps working over SSH, as the terminal itself places the data on the clipboard.
+
+use std::io::{self, Write};
+
+use crate::payload::base64;
+
+/// Build the OSC 52 escape sequence placing `data` on the system clipboard.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(qr2term::clipboard::osc52(b"hello"), "\x1b]52;c;aGVsbG8=\x07");
+/// ```
+pub fn osc52(data: &[u8]) -> String {
+    format!("\x1b]52;c;{}\x07", base64::encode(data))
+}
+
+/// Copy `data` to the clipboard, by writing the OSC 52 escape sequence to the given
+/// terminal.
+///
+/// Terminals not supporting OSC 52 silently ignore the sequence.
+pub fn copy<W: Write>(data: &[u8], terminal: &mut W) -> io::Result<()> {
+    terminal.write_all(osc52(data).as_bytes())?;
+    terminal.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_writes_sequence() {
+        let mut out = Vec::new();
+        copy(b"", &mut out).unwrap();
+        assert_eq!(out, b"\x1b]52;c;\x07");
+    }
+}
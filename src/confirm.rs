@@ -0,0 +1,112 @@
+//! Reading a yes or no answer from the user.
+
+use std::io::{self, BufRead};
+
+/// Read whether the user answers yes from stdin.
+///
+/// On a terminal a single key press is read, where `y` and Enter confirm while `n`, `q`
+/// and Ctrl+C decline. Other keys, including Esc and keys sending escape sequences such
+/// as the arrows, are ignored. Otherwise a line is read, confirming if it is empty or yes.
+/// Declines at the end of input.
+pub(crate) fn read() -> io::Result<bool> {
+    #[cfg(feature = "crossterm")]
+    {
+        use crossterm::tty::IsTty;
+        if io::stdin().is_tty() {
+            return read_key();
+        }
+    }
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Ok(false);
+    }
+    Ok(line_answer(&line))
+}
+
+/// Read a single key press with the terminal in raw mode, ending the line afterwards.
+#[cfg(feature = "crossterm")]
+fn read_key() -> io::Result<bool> {
+    use std::io::Read;
+
+    /// Leaves raw mode when dropped, also when reading fails.
+    struct RawMode;
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            let _ = crossterm::terminal::disable_raw_mode();
+        }
+    }
+
+    let answer = {
+        crossterm::terminal::enable_raw_mode()?;
+        let _raw_mode = RawMode;
+        keys_answer(io::stdin().lock().bytes())?
+    };
+    println!();
+    Ok(answer)
+}
+
+/// Answer given by the first answering key press in `bytes`, declining at the end.
+///
+/// Escape sequences are consumed up to their final byte, so their bytes don't answer.
+#[cfg_attr(not(feature = "crossterm"), allow(dead_code))]
+fn keys_answer<I: Iterator<Item = io::Result<u8>>>(mut bytes: I) -> io::Result<bool> {
+    let mut next = bytes.next().transpose()?;
+    while let Some(byte) = next {
+        next = bytes.next().transpose()?;
+        if byte == b'\x1b' && matches!(next, Some(b'[' | b'O')) {
+            // Skip the parameters of control sequences, up to and including the final byte
+            loop {
+                match bytes.next().transpose()? {
+                    Some(b'\x40'..=b'\x7e') => break,
+                    Some(_) => continue,
+                    None => return Ok(false),
+                }
+            }
+            next = bytes.next().transpose()?;
+        } else if let Some(answer) = key_answer(byte) {
+            return Ok(answer);
+        }
+    }
+    Ok(false)
+}
+
+/// Answer given by a key press, or `None` for keys that don't answer.
+#[cfg_attr(not(feature = "crossterm"), allow(dead_code))]
+fn key_answer(byte: u8) -> Option<bool> {
+    match byte {
+        b'y' | b'Y' | b'\r' | b'\n' => Some(true),
+        b'n' | b'N' | b'q' | b'Q' | b'\x03' | b'\x04' => Some(false),
+        _ => None,
+    }
+}
+
+/// Answer given by a line of input.
+fn line_answer(line: &str) -> bool {
+    matches!(line.trim().to_ascii_lowercase().as_str(), "" | "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        assert_eq!(key_answer(b'y'), Some(true));
+        assert_eq!(key_answer(b'\r'), Some(true));
+        assert_eq!(key_answer(b'\x1b'), None);
+        assert_eq!(key_answer(b'N'), Some(false));
+        assert_eq!(key_answer(b'x'), None);
+
+        let keys = |keys: &[u8]| keys_answer(keys.iter().map(|byte| Ok(*byte))).unwrap();
+        assert!(keys(b"\x1b[Ay"));
+        assert!(!keys(b"\x1bOQ\x1b[1;5Cn"));
+        assert!(keys(b"\x1by"));
+        assert!(!keys(b"\x1b[A"));
+
+        assert!(line_answer("\n"));
+        assert!(line_answer(" Yes\r\n"));
+        assert!(!line_answer("no\n"));
+        assert!(!line_answer("maybe"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Data Matrix (ECC 200) barcode type.
+//!
+//! Only square symbols are supported, as the rendering pipeline works on square matrices.
+
+use qrcode::types::Color;
+
+use crate::matrix::Matrix;
+use crate::QrError;
+
+/// Square symbol size, as width, data codewords, error correction codewords, data region
+/// width and number of interleaved blocks.
+#[rustfmt::skip]
+const SIZES: [(usize, usize, usize, usize, usize); 24] = [
+    (10, 3, 5, 8, 1), (12, 5, 7, 10, 1), (14, 8, 10, 12, 1), (16, 12, 12, 14, 1),
+    (18, 18, 14, 16, 1), (20, 22, 18, 18, 1), (22, 30, 20, 20, 1), (24, 36, 24, 22, 1),
+    (26, 44, 28, 24, 1), (32, 62, 36, 14, 1), (36, 86, 42, 16, 1), (40, 114, 48, 18, 1),
+    (44, 144, 56, 20, 1), (48, 174, 68, 22, 1), (52, 204, 84, 24, 2), (64, 280, 112, 14, 2),
+    (72, 368, 144, 16, 4), (80, 456, 192, 18, 4), (88, 576, 224, 20, 4), (96, 696, 272, 22, 4),
+    (104, 816, 336, 24, 6), (120, 1050, 408, 18, 6), (132, 1304, 496, 20, 8),
+    (144, 1558, 620, 22, 10),
+];
+
+/// Raw Data Matrix code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataMatrix {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl DataMatrix {
+    /// Construct a new Data Matrix code, using the smallest square symbol the data fits in.
+    ///
+    /// Data is encoded in ASCII mode, packing digit pairs into a single codeword. Fails
+    /// with [`QrError::DataTooLong`] if the data doesn't fit in the largest symbol.
+    pub fn from<D: AsRef<[u8]>>(data: D) -> Result<Self, QrError> {
+        let mut codewords = encode_ascii(data.as_ref());
+        let &(size, data_len, ecc_len, region, blocks) = SIZES
+            .iter()
+            .find(|(_, data_len, ..)| *data_len >= codewords.len())
+            .ok_or(QrError::DataTooLong)?;
+
+        pad(&mut codewords, data_len);
+        let codewords = add_error_correction(&codewords, ecc_len, blocks);
+        Ok(Self {
+            size,
+            modules: layout(&codewords, size, region),
+        })
+    }
+
+    /// Get the width and height of this code in pixels, from 10 to 144.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Create pixel matrix from this code.
+    pub fn to_matrix(&self) -> Matrix<Color> {
+        Matrix::new(
+            self.modules
+                .iter()
+                .map(|dark| if *dark { Color::Dark } else { Color::Light })
+                .collect(),
+        )
+    }
+}
+
+/// Encode data as ASCII mode codewords.
+fn encode_ascii(data: &[u8]) -> Vec<u8> {
+    let mut codewords = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        match (data[i], data.get(i + 1)) {
+            (a @ b'0'..=b'9', Some(b @ b'0'..=b'9')) => {
+                codewords.push(130 + (a - b'0') * 10 + (b - b'0'));
+                i += 2;
+                continue;
+            }
+            (byte @ 0..=127, _) => codewords.push(byte + 1),
+            // Upper shift, to encode the byte minus 128
+            (byte, _) => codewords.extend([235, byte - 127]),
+        }
+        i += 1;
+    }
+    codewords
+}
+
+/// Pad codewords up to the data capacity of the symbol.
+fn pad(codewords: &mut Vec<u8>, data_len: usize) {
+    if codewords.len() < data_len {
+        codewords.push(129);
+    }
+    while codewords.len() < data_len {
+        // Pseudo random padding, based on the 1-based codeword position
+        let position = codewords.len() + 1;
+        let value = 129 + (149 * position) % 253 + 1;
+        codewords.push(if value > 254 { value - 254 } else { value } as u8);
+    }
+}
+
+/// Append the interleaved Reed-Solomon error correction codewords.
+fn add_error_correction(data: &[u8], ecc_len: usize, blocks: usize) -> Vec<u8> {
+    let (exp, log) = galois_tables();
+    let mul = |a: u8, b: u8| {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            exp[(log[a as usize] as usize + log[b as usize] as usize) % 255]
+        }
+    };
+
+    // Generator polynomial with roots 2^1 ..= 2^n, highest degree coefficient first
+    let block_ecc_len = ecc_len / blocks;
+    let mut generator = vec![1u8];
+    for root in &exp[1..=block_ecc_len] {
+        let mut next = vec![0; generator.len() + 1];
+        for (j, coefficient) in generator.iter().enumerate() {
+            next[j] ^= coefficient;
+            next[j + 1] ^= mul(*coefficient, *root);
+        }
+        generator = next;
+    }
+
+    let mut codewords = data.to_vec();
+    codewords.resize(data.len() + ecc_len, 0);
+    for block in 0..blocks {
+        let mut remainder = vec![0u8; block_ecc_len];
+        for &codeword in data.iter().skip(block).step_by(blocks) {
+            let factor = codeword ^ remainder[0];
+            remainder.rotate_left(1);
+            remainder[block_ecc_len - 1] = 0;
+            for (r, g) in remainder.iter_mut().zip(&generator[1..]) {
+                *r ^= mul(*g, factor);
+            }
+        }
+        for (i, ecc) in remainder.into_iter().enumerate() {
+            codewords[data.len() + block + i * blocks] = ecc;
+        }
+    }
+    codewords
+}
+
+/// Exponent and logarithm tables of GF(256) with the Data Matrix polynomial 0x12D.
+fn galois_tables() -> ([u8; 256], [u8; 256]) {
+    let (mut exp, mut log) = ([0u8; 256], [0u8; 256]);
+    let mut value = 1u16;
+    for (i, entry) in exp.iter_mut().enumerate().take(255) {
+        *entry = value as u8;
+        log[value as usize] = i as u8;
+        value <<= 1;
+        if value >= 256 {
+            value ^= 0x12D;
+        }
+    }
+    exp[255] = exp[0];
+    (exp, log)
+}
+
+/// Place codewords in a symbol of the given size, including finder patterns.
+fn layout(codewords: &[u8], size: usize, region: usize) -> Vec<bool> {
+    let regions = size / (region + 2);
+    let mapping = Mapping::place(codewords, regions * region);
+
+    let mut modules = vec![false; size * size];
+    for row in 0..size {
+        for col in 0..size {
+            let (y, x) = (row % (region + 2), col % (region + 2));
+            modules[row * size + col] = match (y, x) {
+                // Solid left and bottom edges, alternating top and right edges
+                (_, 0) => true,
+                (y, _) if y == region + 1 => true,
+                (0, x) => x % 2 == 0,
+                (y, x) if x == region + 1 => y % 2 == 1,
+                (y, x) => {
+                    let row = row / (region + 2) * region + y - 1;
+                    let col = col / (region + 2) * region + x - 1;
+                    mapping.modules[row * mapping.width + col] == Some(true)
+                }
+            };
+        }
+    }
+    modules
+}
+
+/// Codeword placement in the data regions combined, following ISO/IEC 16022 annex F.
+struct Mapping<'a> {
+    codewords: &'a [u8],
+    width: usize,
+    modules: Vec<Option<bool>>,
+}
+
+impl<'a> Mapping<'a> {
+    /// Place all codewords in a mapping matrix of the given width.
+    fn place(codewords: &'a [u8], width: usize) -> Self {
+        let mut mapping = Self {
+            codewords,
+            width,
+            modules: vec![None; width * width],
+        };
+        let size = width as isize;
+        let (mut row, mut col, mut index) = (4isize, 0isize, 0);
+        loop {
+            if row == size && col == 0 {
+                mapping.corner(index, &CORNER_1);
+                index += 1;
+            }
+            if row == size - 2 && col == 0 && size % 4 != 0 {
+                mapping.corner(index, &CORNER_2);
+                index += 1;
+            }
+            if row == size - 2 && col == 0 && size % 8 == 4 {
+                mapping.corner(index, &CORNER_3);
+                index += 1;
+            }
+            if row == size + 4 && col == 2 && size % 8 == 0 {
+                mapping.corner(index, &CORNER_4);
+                index += 1;
+            }
+
+            // Sweep upwards to the right diagonally
+            loop {
+                if row < size && col >= 0 && !mapping.is_set(row, col) {
+                    mapping.utah(row, col, index);
+                    index += 1;
+                }
+                row -= 2;
+                col += 2;
+                if row < 0 || col >= size {
+                    break;
+                }
+            }
+            row += 1;
+            col += 3;
+
+            // Sweep downwards to the left diagonally
+            loop {
+                if row >= 0 && col < size && !mapping.is_set(row, col) {
+                    mapping.utah(row, col, index);
+                    index += 1;
+                }
+                row += 2;
+                col -= 2;
+                if row >= size || col < 0 {
+                    break;
+                }
+            }
+            row += 3;
+            col += 1;
+
+            if row >= size && col >= size {
+                break;
+            }
+        }
+
+        // Fill the unused bottom right corner with a fixed pattern
+        if !mapping.is_set(size - 1, size - 1) {
+            let last = mapping.modules.len() - 1;
+            mapping.modules[last] = Some(true);
+            mapping.modules[last - width - 1] = Some(true);
+            mapping.modules[last - 1] = Some(false);
+            mapping.modules[last - width] = Some(false);
+        }
+        mapping
+    }
+
+    fn is_set(&self, row: isize, col: isize) -> bool {
+        self.modules[row as usize * self.width + col as usize].is_some()
+    }
+
+    /// Place one bit of a codeword, wrapping around the edges as the placement requires.
+    fn module(&mut self, mut row: isize, mut col: isize, index: usize, bit: u32) {
+        let size = self.width as isize;
+        if row < 0 {
+            row += size;
+            col += 4 - (size + 4) % 8;
+        }
+        if col < 0 {
+            col += size;
+            row += 4 - (size + 4) % 8;
+        }
+        let codeword = self.codewords.get(index).copied().unwrap_or(0);
+        self.modules[row as usize * self.width + col as usize] =
+            Some(codeword >> (8 - bit) & 1 == 1);
+    }
+
+    /// Place a codeword in the regular L shaped pattern, with its last bit at the given spot.
+    fn utah(&mut self, row: isize, col: isize, index: usize) {
+        for (bit, (dy, dx)) in UTAH.iter().enumerate() {
+            self.module(row + dy, col + dx, index, bit as u32 + 1);
+        }
+    }
+
+    /// Place a codeword in one of the special corner patterns.
+    fn corner(&mut self, index: usize, corner: &[(Edge, Edge); 8]) {
+        let size = self.width as isize;
+        let resolve = |edge: Edge| match edge {
+            Edge::Start(offset) => offset,
+            Edge::End(offset) => size - offset,
+        };
+        for (bit, (row, col)) in corner.iter().enumerate() {
+            self.module(resolve(*row), resolve(*col), index, bit as u32 + 1);
+        }
+    }
+}
+
+/// Offsets of the bits of a codeword in the regular pattern, relative to its last bit.
+const UTAH: [(isize, isize); 8] = [
+    (-2, -2),
+    (-2, -1),
+    (-1, -2),
+    (-1, -1),
+    (-1, 0),
+    (0, -2),
+    (0, -1),
+    (0, 0),
+];
+
+/// Coordinate in a corner pattern, relative to the start or end of the mapping matrix.
+#[derive(Clone, Copy)]
+enum Edge {
+    Start(isize),
+    End(isize),
+}
+
+use Edge::{End, Start};
+
+#[rustfmt::skip]
+const CORNER_1: [(Edge, Edge); 8] = [
+    (End(1), Start(0)), (End(1), Start(1)), (End(1), Start(2)), (Start(0), End(2)),
+    (Start(0), End(1)), (Start(1), End(1)), (Start(2), End(1)), (Start(3), End(1)),
+];
+
+#[rustfmt::skip]
+const CORNER_2: [(Edge, Edge); 8] = [
+    (End(3), Start(0)), (End(2), Start(0)), (End(1), Start(0)), (Start(0), End(4)),
+    (Start(0), End(3)), (Start(0), End(2)), (Start(0), End(1)), (Start(1), End(1)),
+];
+
+#[rustfmt::skip]
+const CORNER_3: [(Edge, Edge); 8] = [
+    (End(3), Start(0)), (End(2), Start(0)), (End(1), Start(0)), (Start(0), End(2)),
+    (Start(0), End(1)), (Start(1), End(1)), (Start(2), End(1)), (Start(3), End(1)),
+];
+
+#[rustfmt::skip]
+const CORNER_4: [(Edge, Edge); 8] = [
+    (End(1), Start(0)), (End(1), End(1)), (Start(0), End(3)), (Start(0), End(2)),
+    (Start(0), End(1)), (Start(1), End(3)), (Start(1), End(2)), (Start(1), End(1)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Codewords of the example in ISO/IEC 16022.
+    #[test]
+    fn codewords() {
+        let mut codewords = encode_ascii(b"123456");
+        assert_eq!(codewords, [142, 164, 186]);
+        pad(&mut codewords, 3);
+        assert_eq!(
+            add_error_correction(&codewords, 5, 1),
+            [142, 164, 186, 114, 25, 5, 88, 102]
+        );
+
+        let mut padded = encode_ascii(b"A");
+        pad(&mut padded, 5);
+        assert_eq!(padded, [66, 129, 70, 220, 115]);
+        assert_eq!(encode_ascii(&[0xE9]), [235, 106]);
+    }
+
+    /// Every module of the mapping matrix should be covered, by the full set of codewords.
+    #[test]
+    fn placement_covers_mapping() {
+        for (size, data_len, ecc_len, region, _) in SIZES {
+            let codewords = vec![0xFF; data_len + ecc_len];
+            let width = size / (region + 2) * region;
+            let mapping = Mapping::place(&codewords, width);
+            assert!(mapping.modules.iter().all(Option::is_some), "{}", size);
+
+            let dark = mapping.modules.iter().filter(|m| **m == Some(true)).count();
+            assert!(dark >= codewords.len() * 8, "{}", size);
+            assert!(dark <= codewords.len() * 8 + 2, "{}", size);
+        }
+    }
+
+    #[test]
+    fn symbols() {
+        let code = DataMatrix::from("123456").unwrap();
+        assert_eq!(code.size(), 10);
+        let matrix = code.to_matrix();
+        let pixels = matrix.pixels();
+
+        // Finder pattern: solid left and bottom edge, alternating top and right edge
+        for i in 0..10 {
+            assert_eq!(pixels[i * 10], Color::Dark);
+            assert_eq!(pixels[90 + i], Color::Dark);
+            assert_eq!(pixels[i] == Color::Dark, i % 2 == 0);
+            assert_eq!(pixels[i * 10 + 9] == Color::Dark, i % 2 == 1);
+        }
+
+        assert_eq!(DataMatrix::from("a".repeat(100)).unwrap().size(), 40);
+        assert_eq!(DataMatrix::from("1".repeat(3116)).unwrap().size(), 144);
+        assert_eq!(
+            DataMatrix::from("a".repeat(1559)).err(),
+            Some(QrError::DataTooLong)
+        );
+    }
+}
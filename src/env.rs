@@ -0,0 +1,151 @@
+//! Rendering overrides from environment variables.
+//!
+//! Applied on top of the [default options](crate::default_options) of the top-level
+//! functions, so end users can tune how any tool built on this crate renders codes,
+//! without that tool exposing options for it:
+//!
+//! - `QR2TERM_STYLE`: `ansi`, `utf8`, `ascii` or `braille`, like the formats of the
+//!   command line tool.
+//! - `QR2TERM_THEME`: name of a built-in [`Theme`].
+//! - `QR2TERM_INVERT`: `1` or `0`, to swap dark and light pixels.
+//! - `QR2TERM_QUIET_ZONE`: width of the light border, in pixels.
+//! - `QR2TERM_SCALE`: how many times each pixel is repeated.
+//! - `QR2TERM_ALT_TEXT`: `only` or `1` to print codes as plain text instead, `alongside`
+//!   to print both, see [`AltText`].
+//!
+//! Invalid values are ignored.
+
+use crate::render::{Density, Theme};
+use crate::{AltText, Options};
+
+/// Overrides set through environment variables.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Overrides {
+    /// Density and whether to use colors.
+    style: Option<(Density, bool)>,
+    theme: Option<Theme>,
+    invert: Option<bool>,
+    quiet_zone: Option<usize>,
+    scale: Option<usize>,
+    alt_text: Option<AltText>,
+}
+
+impl Overrides {
+    /// Read the overrides from the environment of this process.
+    pub fn from_env() -> Self {
+        Self::parse(|name| std::env::var(name).ok())
+    }
+
+    /// Parse the overrides from variables looked up through `var`.
+    fn parse<F: Fn(&str) -> Option<String>>(var: F) -> Self {
+        let var = |name| var(name).map(|value| value.trim().to_ascii_lowercase());
+        Self {
+            style: var("QR2TERM_STYLE").and_then(|style| match style.as_str() {
+                "ansi" => Some((Density::HalfBlock, true)),
+                "utf8" => Some((Density::HalfBlock, false)),
+                "ascii" => Some((Density::Full, false)),
+                "braille" => Some((Density::Braille, false)),
+                _ => None,
+            }),
+            theme: var("QR2TERM_THEME").and_then(|theme| Theme::named(&theme)),
+            invert: var("QR2TERM_INVERT").and_then(|invert| match invert.as_str() {
+                "1" | "true" | "yes" | "on" => Some(true),
+                "0" | "false" | "no" | "off" => Some(false),
+                _ => None,
+            }),
+            quiet_zone: var("QR2TERM_QUIET_ZONE").and_then(|width| width.parse().ok()),
+            scale: var("QR2TERM_SCALE")
+                .and_then(|scale| scale.parse().ok())
+                .filter(|scale| *scale > 0),
+            alt_text: var("QR2TERM_ALT_TEXT").and_then(|alt_text| match alt_text.as_str() {
+                "only" | "1" | "true" | "yes" | "on" => Some(AltText::Only),
+                "alongside" => Some(AltText::Alongside),
+                "off" | "0" | "false" | "no" => Some(AltText::Off),
+                _ => None,
+            }),
+        }
+    }
+
+    /// Apply the overrides to the given options.
+    pub fn apply(&self, mut options: Options) -> Options {
+        if let Some((density, colors)) = self.style {
+            options = options.density(density).colors(colors);
+        }
+        if let Some(theme) = self.theme {
+            options = options.theme(theme);
+        }
+        if let Some(invert) = self.invert {
+            options = options.invert(invert);
+        }
+        if let Some(quiet_zone) = self.quiet_zone {
+            options = options.quiet_zone(quiet_zone);
+        }
+        if let Some(scale) = self.scale {
+            options = options.scale(scale);
+        }
+        if let Some(alt_text) = self.alt_text {
+            options = options.alt_text(alt_text);
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qr::EcLevel;
+
+    fn parse(vars: &[(&str, &str)]) -> Overrides {
+        Overrides::parse(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn parse_vars() {
+        assert_eq!(parse(&[]), Overrides::default());
+        assert_eq!(parse(&[]).apply(Options::default()), Options::default());
+
+        let overrides = parse(&[
+            ("QR2TERM_STYLE", "ASCII"),
+            ("QR2TERM_THEME", "nord"),
+            ("QR2TERM_INVERT", "1"),
+            ("QR2TERM_QUIET_ZONE", "4"),
+            ("QR2TERM_SCALE", "2"),
+            ("QR2TERM_ALT_TEXT", "Alongside"),
+        ]);
+        assert_eq!(overrides.style, Some((Density::Full, false)));
+        assert_eq!(overrides.theme, Theme::named("nord"));
+        assert_eq!(overrides.invert, Some(true));
+        assert_eq!(overrides.quiet_zone, Some(4));
+        assert_eq!(overrides.scale, Some(2));
+        assert_eq!(overrides.alt_text, Some(AltText::Alongside));
+        assert_eq!(
+            overrides.apply(Options::default().ec_level(EcLevel::H)),
+            Options::default()
+                .ec_level(EcLevel::H)
+                .density(Density::Full)
+                .colors(false)
+                .theme(Theme::named("nord").unwrap())
+                .invert(true)
+                .quiet_zone(4)
+                .scale(2)
+                .alt_text(AltText::Alongside)
+        );
+        assert_eq!(
+            parse(&[("QR2TERM_ALT_TEXT", "1")]).alt_text,
+            Some(AltText::Only)
+        );
+
+        let invalid = parse(&[
+            ("QR2TERM_STYLE", "fancy"),
+            ("QR2TERM_INVERT", "maybe"),
+            ("QR2TERM_QUIET_ZONE", "-1"),
+            ("QR2TERM_SCALE", "0"),
+            ("QR2TERM_ALT_TEXT", "braille"),
+        ]);
+        assert_eq!(invalid, Overrides::default());
+    }
+}
@@ -0,0 +1,116 @@
+//! Crate error type.
+
+use std::string::FromUtf8Error;
+use std::{error, fmt, io};
+
+use crate::payload::PayloadError;
+use crate::QrError;
+
+/// Error returned by the functions of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// The data couldn't be encoded as QR code, for example because it is too long.
+    Qr(QrError),
+
+    /// The payload fields don't meet the requirements of the payload format.
+    Payload(PayloadError),
+
+    /// Writing the rendered code failed.
+    Io(io::Error),
+
+    /// The code is wider than the terminal it is printed in.
+    TerminalTooSmall {
+        /// Width of the code in columns.
+        width: usize,
+
+        /// Width of the terminal in columns.
+        columns: usize,
+    },
+
+    /// The code is wider than the [maximum width](crate::render::Renderer::max_width).
+    TooWide {
+        /// Width of the code in columns.
+        needed: usize,
+
+        /// Maximum width in columns.
+        max: usize,
+    },
+
+    /// The operation was cancelled through its cancellation flag.
+    Cancelled,
+
+    /// A rendering or generation option is out of range.
+    InvalidOption(String),
+
+    /// The rendered code is not valid UTF-8.
+    Utf8(FromUtf8Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Qr(err) => write!(f, "failed to generate QR code: {}", err),
+            Error::Payload(err) => write!(f, "{}", err),
+            Error::Io(err) => write!(f, "failed to write QR code: {}", err),
+            Error::TerminalTooSmall { width, columns } => write!(
+                f,
+                "code is {} columns wide, but the terminal has only {}",
+                width, columns
+            ),
+            Error::TooWide { needed, max } => write!(
+                f,
+                "code is {} columns wide, but may be at most {}",
+                needed, max
+            ),
+            Error::Cancelled => f.write_str("cancelled"),
+            Error::InvalidOption(err) => write!(f, "invalid option: {}", err),
+            Error::Utf8(err) => write!(f, "rendered QR code is not valid UTF-8: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Qr(err) => Some(err),
+            Error::Payload(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Utf8(err) => Some(err),
+            Error::TerminalTooSmall { .. }
+            | Error::TooWide { .. }
+            | Error::Cancelled
+            | Error::InvalidOption(_) => None,
+        }
+    }
+}
+
+impl From<QrError> for Error {
+    fn from(err: QrError) -> Self {
+        Error::Qr(err)
+    }
+}
+
+impl From<PayloadError> for Error {
+    fn from(err: PayloadError) -> Self {
+        Error::Payload(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        // Unwrap errors the renderer reports through its I/O errors
+        if err.get_ref().map_or(false, |inner| inner.is::<Error>()) {
+            let inner = err.into_inner().expect("error has an inner error");
+            return *inner
+                .downcast::<Error>()
+                .expect("inner error is a crate error");
+        }
+        Error::Io(err)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Self {
+        Error::Utf8(err)
+    }
+}
@@ -0,0 +1,248 @@
+//! Exporting barcodes as images.
+//!
+//! Renders a pixel matrix into image formats, for saving to a file or displaying in
+//! terminals supporting inline graphics. All exporters draw each pixel as a square of
+//! `scale` by `scale` image pixels, dark on light.
+
+use crate::matrix::Matrix;
+use crate::payload::base64;
+use crate::render::{Color, QrDark};
+
+/// Maximum number of Base64 characters in a single kitty graphics escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Export a matrix as SVG image.
+///
+/// Dark pixels are drawn as a single path on a light background.
+pub fn svg(matrix: &Matrix<Color>, scale: usize) -> String {
+    let size = matrix.size();
+    let mut path = String::new();
+    for (row, pixels) in matrix.pixels().chunks(size.max(1)).enumerate() {
+        for (col, pixel) in pixels.iter().enumerate() {
+            if *pixel == QrDark {
+                path.push_str(&format!("M{},{}h1v1h-1z", col, row));
+            }
+        }
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" \
+        width=\"{px}\" height=\"{px}\" viewBox=\"0 0 {size} {size}\" \
+        shape-rendering=\"crispEdges\">\n\
+        <rect width=\"{size}\" height=\"{size}\" fill=\"#ffffff\"/>\n\
+        <path d=\"{path}\" fill=\"#000000\"/>\n\
+        </svg>\n",
+        px = size * scale,
+        size = size,
+        path = path,
+    )
+}
+
+/// Export a matrix as PNG image.
+///
+/// Produces a 1 bit grayscale image. Image data is stored without compression, which
+/// barely matters for images this small.
+pub fn png(matrix: &Matrix<Color>, scale: usize) -> Vec<u8> {
+    let size = matrix.size();
+    let px = size * scale;
+
+    // Scanlines prefixed with filter type 0, light pixels are 1 bits
+    let stride = (px + 7) / 8;
+    let mut raw = Vec::with_capacity((stride + 1) * px);
+    for y in 0..px {
+        raw.push(0);
+        let mut line = vec![0u8; stride];
+        for x in 0..px {
+            if matrix.pixels()[(y / scale) * size + x / scale] != QrDark {
+                line[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        raw.extend_from_slice(&line);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(px as u32).to_be_bytes());
+    header.extend_from_slice(&(px as u32).to_be_bytes());
+    header.extend_from_slice(&[1, 0, 0, 0, 0]);
+
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut out, b"IHDR", &header);
+    png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Export a matrix as sixel escape sequence, for display in terminals supporting sixel.
+pub fn sixel(matrix: &Matrix<Color>, scale: usize) -> String {
+    let size = matrix.size();
+    let px = size * scale;
+    let dark = |x: usize, y: usize| matrix.pixels()[(y / scale) * size + x / scale] == QrDark;
+
+    // Enter sixel mode with square pixels, and define black and white colors
+    let mut out = format!(
+        "\x1bP0;1q\"1;1;{px};{px}#0;2;0;0;0#1;2;100;100;100",
+        px = px
+    );
+    for band in (0..px).step_by(6) {
+        for (color, is_dark) in [(1, false), (0, true)] {
+            out.push_str(&format!("#{}", color));
+            let sixels: Vec<u8> = (0..px)
+                .map(|x| {
+                    (0..6)
+                        .filter(|dy| band + dy < px && dark(x, band + dy) == is_dark)
+                        .fold(0, |bits, dy| bits | 1 << dy)
+                })
+                .collect();
+            push_sixel_runs(&mut out, &sixels);
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Export a matrix as kitty graphics protocol escape sequence, for display in terminals
+/// supporting it.
+pub fn kitty(matrix: &Matrix<Color>, scale: usize) -> String {
+    let data = base64::encode(&png(matrix, scale));
+    let chunks: Vec<&str> = data
+        .as_bytes()
+        .chunks(KITTY_CHUNK_SIZE)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+
+    let mut out = String::with_capacity(data.len() + chunks.len() * 16);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        if i == 0 {
+            out.push_str(&format!("\x1b_Gf=100,a=T,m={};{}\x1b\\", more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// Push sixel characters for the given column bits, run length encoding repeats.
+fn push_sixel_runs(out: &mut String, sixels: &[u8]) {
+    let mut i = 0;
+    while i < sixels.len() {
+        let run = sixels[i..].iter().take_while(|s| **s == sixels[i]).count();
+        let c = (63 + sixels[i]) as char;
+        if run > 3 {
+            out.push_str(&format!("!{}{}", run, c));
+        } else {
+            (0..run).for_each(|_| out.push(c));
+        }
+        i += run;
+    }
+}
+
+/// Append a PNG chunk with the given type and data.
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wrap data in a zlib stream using stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        out.push(blocks.peek().is_none() as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+/// Compute the CRC-32 checksum, as used by PNG.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Compute the Adler-32 checksum, as used by zlib.
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::QrLight;
+
+    fn matrix() -> Matrix<Color> {
+        Matrix::new(vec![QrDark, QrLight, QrLight, QrDark])
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn svg_paths() {
+        let svg = svg(&matrix(), 4);
+        assert!(svg.contains("width=\"8\" height=\"8\" viewBox=\"0 0 2 2\""));
+        assert!(svg.contains("d=\"M0,0h1v1h-1zM1,1h1v1h-1z\""));
+    }
+
+    #[test]
+    fn png_layout() {
+        let png = png(&matrix(), 3);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 6, 0, 0, 0, 6]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+
+        // First scanline is 3 dark then 3 light pixels
+        let idat = &png[33 + 8..];
+        assert_eq!(&idat[..2], &[0x78, 0x01]);
+        assert_eq!(&idat[7..9], &[0, 0b0001_1100]);
+    }
+
+    #[test]
+    fn sixel_bands() {
+        let sixel = sixel(&matrix(), 3);
+        assert!(sixel.starts_with("\x1bP0;1q\"1;1;6;6"));
+        assert!(sixel.ends_with("\x1b\\"));
+        assert!(sixel.contains("#1wwwFFF$#0FFFwww$-"));
+    }
+
+    #[test]
+    fn kitty_chunks() {
+        let kitty = kitty(&Matrix::new(vec![QrDark; 21 * 21]), 8);
+        assert!(kitty.starts_with("\x1b_Gf=100,a=T,m=1;iVBORw0KGgo"));
+        assert!(kitty.contains("\x1b_Gm=0;"));
+    }
+}
@@ -0,0 +1,291 @@
+//! C interface, for linking the renderer from other languages.
+//!
+//! Build a shared library with:
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! The matching declarations are in [`include/qr2term.h`](../include/qr2term.h).
+
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::qr::{EcLevel, Qr};
+use crate::render::{self, Density, Renderer};
+use crate::QUIET_ZONE_WIDTH;
+
+/// Success.
+pub const QR2TERM_OK: c_int = 0;
+
+/// A pointer was null, or an option was out of range.
+pub const QR2TERM_ERR_INVALID: c_int = 1;
+
+/// The data could not be encoded, for example because it is too long.
+pub const QR2TERM_ERR_QR: c_int = 2;
+
+/// Printing to the terminal failed.
+pub const QR2TERM_ERR_IO: c_int = 3;
+
+/// Render pixels using half blocks, two pixels per character.
+pub const QR2TERM_DENSITY_HALF_BLOCK: c_int = 0;
+
+/// Render pixels using two characters each, for terminals or fonts without block glyphs.
+pub const QR2TERM_DENSITY_FULL: c_int = 1;
+
+/// Render pixels using braille glyphs, eight pixels per character.
+pub const QR2TERM_DENSITY_BRAILLE: c_int = 2;
+
+/// Largest accepted scale.
+pub const QR2TERM_MAX_SCALE: u32 = 16;
+
+/// Largest accepted quiet zone, in pixels.
+pub const QR2TERM_MAX_QUIET_ZONE: u32 = 64;
+
+/// Rendering options, see [`qr2term_default_options`] for the defaults.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Qr2termOptions {
+    /// One of the `QR2TERM_DENSITY_*` constants.
+    pub density: c_int,
+
+    /// Whether to use ANSI colors.
+    pub colors: bool,
+
+    /// Whether to swap dark and light pixels.
+    pub invert: bool,
+
+    /// How many times each pixel is repeated, from 1 to [`QR2TERM_MAX_SCALE`].
+    pub scale: u32,
+
+    /// Width of the light border around the code, in pixels, up to
+    /// [`QR2TERM_MAX_QUIET_ZONE`].
+    pub quiet_zone: u32,
+
+    /// Error correction level, from 0 (`L`) to 3 (`H`).
+    pub ec_level: c_int,
+}
+
+impl Default for Qr2termOptions {
+    fn default() -> Self {
+        Self {
+            density: QR2TERM_DENSITY_HALF_BLOCK,
+            colors: true,
+            invert: false,
+            scale: 1,
+            quiet_zone: QUIET_ZONE_WIDTH as u32,
+            ec_level: 1,
+        }
+    }
+}
+
+/// Error of a C interface call, mapped onto one of the `QR2TERM_ERR_*` codes.
+enum Error {
+    Invalid,
+    Qr,
+    Io,
+}
+
+impl Error {
+    fn code(&self) -> c_int {
+        match self {
+            Error::Invalid => QR2TERM_ERR_INVALID,
+            Error::Qr => QR2TERM_ERR_QR,
+            Error::Io => QR2TERM_ERR_IO,
+        }
+    }
+}
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_void;
+}
+
+/// Run the body of a C interface call, returning `fallback` if it panics rather than
+/// unwinding into the caller.
+fn catch<T, F: FnOnce() -> T>(fallback: T, body: F) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+/// Get the default rendering options.
+#[no_mangle]
+pub extern "C" fn qr2term_default_options() -> Qr2termOptions {
+    catch(Qr2termOptions::default(), Qr2termOptions::default)
+}
+
+/// Print the NUL terminated `data` as QR code in the terminal.
+///
+/// Returns [`QR2TERM_OK`] on success, or one of the `QR2TERM_ERR_*` codes.
+///
+/// # Safety
+///
+/// `data` must be null or point to a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn qr2term_print(data: *const c_char) -> c_int {
+    catch(QR2TERM_ERR_INVALID, || {
+        qr2term_print_with(data, ptr::null())
+    })
+}
+
+/// Print the NUL terminated `data` as QR code in the terminal, using the given options.
+///
+/// Default options are used if `options` is null. Returns [`QR2TERM_OK`] on success, or
+/// one of the `QR2TERM_ERR_*` codes.
+///
+/// # Safety
+///
+/// `data` must be null or point to a NUL terminated string, and `options` must be null or
+/// point to valid options.
+#[no_mangle]
+pub unsafe extern "C" fn qr2term_print_with(
+    data: *const c_char,
+    options: *const Qr2termOptions,
+) -> c_int {
+    catch(QR2TERM_ERR_INVALID, || {
+        let result = prepare(data, options).and_then(|(renderer, matrix)| {
+            renderer
+                .for_console()
+                .render(&matrix, &mut io::stdout().lock())
+                .map_err(|_| Error::Io)
+        });
+        match result {
+            Ok(()) => QR2TERM_OK,
+            Err(err) => err.code(),
+        }
+    })
+}
+
+/// Generate the NUL terminated `data` as QR code string, using the given options.
+///
+/// Default options are used if `options` is null. Returns a string allocated with
+/// `malloc`, which the caller must release with `free`, or null on failure.
+///
+/// # Safety
+///
+/// `data` must be null or point to a NUL terminated string, and `options` must be null or
+/// point to valid options.
+#[no_mangle]
+pub unsafe extern "C" fn qr2term_generate(
+    data: *const c_char,
+    options: *const Qr2termOptions,
+) -> *mut c_char {
+    catch(ptr::null_mut(), || {
+        let mut buf = Vec::new();
+        let rendered = prepare(data, options).and_then(|(renderer, matrix)| {
+            renderer.render(&matrix, &mut buf).map_err(|_| Error::Io)
+        });
+        if rendered.is_err() {
+            return ptr::null_mut();
+        }
+
+        let string = malloc(buf.len() + 1) as *mut u8;
+        if string.is_null() {
+            return ptr::null_mut();
+        }
+        ptr::copy_nonoverlapping(buf.as_ptr(), string, buf.len());
+        *string.add(buf.len()) = 0;
+        string as *mut c_char
+    })
+}
+
+/// Build the renderer and QR code matrix for the given C arguments.
+unsafe fn prepare(
+    data: *const c_char,
+    options: *const Qr2termOptions,
+) -> Result<(Renderer, crate::Matrix<render::Color>), Error> {
+    if data.is_null() {
+        return Err(Error::Invalid);
+    }
+    let data = CStr::from_ptr(data).to_bytes();
+    let options = options.as_ref().copied().unwrap_or_default();
+
+    let density = match options.density {
+        QR2TERM_DENSITY_HALF_BLOCK => Density::HalfBlock,
+        QR2TERM_DENSITY_FULL => Density::Full,
+        QR2TERM_DENSITY_BRAILLE => Density::Braille,
+        _ => return Err(Error::Invalid),
+    };
+    let ec_level = match options.ec_level {
+        0 => EcLevel::L,
+        1 => EcLevel::M,
+        2 => EcLevel::Q,
+        3 => EcLevel::H,
+        _ => return Err(Error::Invalid),
+    };
+    if options.scale == 0
+        || options.scale > QR2TERM_MAX_SCALE
+        || options.quiet_zone > QR2TERM_MAX_QUIET_ZONE
+    {
+        return Err(Error::Invalid);
+    }
+
+    let mut matrix = Qr::with_error_correction_level(data, ec_level)
+        .map_err(|_| Error::Qr)?
+        .to_matrix();
+    matrix.surround(options.quiet_zone as usize, render::QrLight);
+    let renderer = Renderer::default()
+        .density(density)
+        .colors(options.colors)
+        .invert(options.invert)
+        .scale(options.scale as usize);
+    Ok((renderer, matrix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" {
+        fn free(ptr: *mut c_void);
+    }
+
+    unsafe fn generate(data: &CStr, options: Option<&Qr2termOptions>) -> Option<String> {
+        let string = qr2term_generate(data.as_ptr(), options.map_or(ptr::null(), |o| o));
+        if string.is_null() {
+            return None;
+        }
+        let text = CStr::from_ptr(string).to_str().unwrap().to_string();
+        free(string as *mut c_void);
+        Some(text)
+    }
+
+    #[test]
+    fn generate_strings() {
+        let data = CStr::from_bytes_with_nul(b"https://rust-lang.org/\0").unwrap();
+        unsafe {
+            assert_eq!(
+                generate(data, None),
+                crate::generate_qr_string("https://rust-lang.org/").ok()
+            );
+
+            let options = Qr2termOptions {
+                density: QR2TERM_DENSITY_FULL,
+                colors: false,
+                ..qr2term_default_options()
+            };
+            let text = generate(data, Some(&options)).unwrap();
+            assert!(text.contains("\n    ##############  "));
+            assert!(!text.contains('\x1b'));
+
+            for invalid in [
+                Qr2termOptions {
+                    scale: 0,
+                    ..qr2term_default_options()
+                },
+                Qr2termOptions {
+                    scale: u32::MAX,
+                    ..qr2term_default_options()
+                },
+                Qr2termOptions {
+                    quiet_zone: u32::MAX,
+                    ..qr2term_default_options()
+                },
+            ] {
+                assert_eq!(generate(data, Some(&invalid)), None);
+            }
+            assert!(qr2term_generate(ptr::null(), ptr::null()).is_null());
+            assert_eq!(qr2term_print(ptr::null()), QR2TERM_ERR_INVALID);
+        }
+    }
+}
@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_debug_implementations, missing_docs)]
 
 //! A stupidly simple QR code renderer, that prints text as QR code to the terminal,
@@ -10,6 +11,7 @@
 //! # Examples
 //! [`example.rs`](./example/example.rs):
 //! ```rust
+//! # #[cfg(feature = "std")]
 //! qr2term::print_qr("https://rust-lang.org/").unwrap();
 //! ```
 //!
@@ -22,43 +24,459 @@
 //!
 //! - [https://crates.io/crates/qair](https://crates.io/crates/qair)
 //! - [https://code.willemp.be/willem/qair/src/branch/master/src/console_barcode_renderer.rs](https://code.willemp.be/willem/qair/src/branch/master/src/console_barcode_renderer.rs)
+//!
+//! # Environment
+//! The printing functions of this crate use the [default options](default_options),
+//! which applications can configure once through [`set_default_options`]. On top of
+//! those, they follow these environment variables, so users can tune the output of any
+//! tool built on it:
+//!
+//! - `QR2TERM_STYLE`: `ansi`, `utf8`, `ascii` or `braille`.
+//! - `QR2TERM_THEME`: name of a built-in [`Theme`](render::Theme).
+//! - `QR2TERM_INVERT`: `1` to swap dark and light pixels.
+//! - `QR2TERM_QUIET_ZONE`: width of the light border, in pixels.
+//! - `QR2TERM_SCALE`: how many times each pixel is repeated.
+//! - `QR2TERM_ALT_TEXT`: `only` or `1` to print the payload as plain text instead of the
+//!   code, for screen readers, or `alongside` to print both.
+//!
+//! # `no_std`
+//! Without the default `std` feature, only [`matrix`] and the [`plain`] text renderer are
+//! available, requiring just `alloc`.
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod annotate;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod clipboard;
+#[cfg(feature = "std")]
+mod confirm;
+#[cfg(feature = "std")]
+pub mod datamatrix;
+#[cfg(feature = "std")]
+mod env;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod linear;
+#[cfg(feature = "std")]
+mod macros;
 pub mod matrix;
+#[cfg(feature = "std")]
+mod options;
+#[cfg(feature = "std")]
+pub mod parse;
+#[cfg(feature = "std")]
+pub mod payload;
+pub mod plain;
+#[cfg(feature = "std")]
 pub mod qr;
+#[cfg(feature = "std")]
 pub mod render;
+#[cfg(feature = "std")]
+pub mod split;
+#[cfg(feature = "std")]
+pub mod symbology;
+#[cfg(feature = "std")]
+pub mod term;
+#[cfg(feature = "std")]
+pub mod testing;
 pub(crate) mod util;
 
+#[cfg(feature = "std")]
+pub use crate::error::Error;
+#[cfg(feature = "std")]
+pub use crate::options::{default_options, set_default_options, AltText, Options};
+#[cfg(feature = "std")]
 pub use qrcode::types::QrError;
 
+#[cfg(feature = "std")]
+use std::io::Write;
+
+#[cfg(feature = "std")]
+use crate::annotate::{Annotations, Panel};
+#[cfg(feature = "std")]
 use crate::matrix::Matrix;
-use crate::render::Renderer;
+#[cfg(feature = "std")]
+use crate::payload::ToQrPayload;
+#[cfg(feature = "std")]
+use crate::render::{RenderInfo, Renderer};
+#[cfg(feature = "std")]
+use crate::symbology::Symbology;
 
 /// Quiet zone size in pixels around QR code.
 ///
 /// Should be 4, but using 2 for small terminals:
 /// https://qrworld.wordpress.com/2011/08/09/the-quiet-zone/
-const QUIET_ZONE_WIDTH: usize = 2;
+pub const QUIET_ZONE_WIDTH: usize = 2;
 
 /// Print the given `data` as QR code in the terminal.
 ///
-/// Returns an error if generating the QR code failed.
+/// Returns an error if generating the QR code failed, or [`Error::Io`] if printing it to
+/// the terminal failed, for example because stdout was closed.
 ///
 /// # Examples
 ///
 /// ```rust
 /// qr2term::print_qr("https://rust-lang.org/").unwrap();
 /// ```
+#[cfg(feature = "std")]
+pub fn print_qr<D: AsRef<[u8]>>(data: D) -> Result<(), Error> {
+    print_qr_with(data, &options())
+}
+
+/// Print the given `data` as QR code in the terminal, with the given options.
+///
+/// Same as [`print_qr`](fn@print_qr), but generates and renders the code with `options` instead of
+/// the default options. Environment overrides don't apply.
+///
+/// Returns an error if generating the QR code failed, or [`Error::Io`] if printing it to
+/// the terminal failed.
 ///
-/// # Panics
+/// # Examples
+///
+/// ```rust
+/// use qr2term::qr::EcLevel;
+/// use qr2term::render::{Density, Theme};
+/// use qr2term::Options;
 ///
-/// Panics if printing the QR code to the terminal failed.
-pub fn print_qr<D: AsRef<[u8]>>(data: D) -> Result<(), QrError> {
+/// let options = Options::default()
+///     .ec_level(EcLevel::H)
+///     .density(Density::Braille)
+///     .theme(Theme::named("nord").unwrap())
+///     .quiet_zone(4);
+/// qr2term::print_qr_with("https://rust-lang.org/", &options).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_with<D: AsRef<[u8]>>(data: D, options: &Options) -> Result<(), Error> {
     // Generate QR code pixel matrix
-    let mut matrix = qr::Qr::from(data)?.to_matrix();
-    matrix.surround(QUIET_ZONE_WIDTH, render::QrLight);
+    let matrix = options.matrix(&options.qr(&data)?);
+    if !print_alt_text(options, data.as_ref())? {
+        return Ok(());
+    }
 
     // Render QR code to stdout
-    Renderer::default().print_stdout(&matrix);
+    let renderer = options.renderer().for_console();
+    renderer.render(&matrix, &mut std::io::stdout().lock())?;
+    print_fingerprint(options, data.as_ref(), renderer.width(&matrix))?;
+    Ok(())
+}
+
+/// Print the given `data` as QR code centered in the terminal, with a `label` beneath it.
+///
+/// Centers within the terminal width, falling back to printing at the left if it is
+/// unknown. Returns [`Error::TerminalTooSmall`] if the code doesn't fit.
+///
+/// # Examples
+///
+/// ```rust
+/// qr2term::print_qr_centered_with_label(
+///     "https://example.com/pair?code=1234",
+///     "Scan to pair your device",
+/// )
+/// .unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_centered_with_label<D: AsRef<[u8]>>(data: D, label: &str) -> Result<(), Error> {
+    let options = options();
+    let matrix = options.matrix(&options.qr(&data)?);
+    if !print_alt_text(&options, data.as_ref())? {
+        return Ok(());
+    }
+    write_centered_with_label(
+        &options,
+        &options.renderer().for_console(),
+        &matrix,
+        data.as_ref(),
+        label,
+        term::columns(),
+        &mut std::io::stdout().lock(),
+    )
+}
+
+/// Write the `matrix` of `data` centered within `columns`, with a `label` beneath it.
+#[cfg(feature = "std")]
+fn write_centered_with_label<W: Write>(
+    options: &Options,
+    renderer: &Renderer,
+    matrix: &Matrix<render::Color>,
+    data: &[u8],
+    label: &str,
+    columns: Option<usize>,
+    target: &mut W,
+) -> Result<(), Error> {
+    let width = renderer.width(matrix);
+    let indent = match columns {
+        Some(columns) if width > columns => return Err(Error::TerminalTooSmall { width, columns }),
+        Some(columns) => (columns - width) / 2,
+        None => 0,
+    };
+
+    let mut annotations = Annotations::new().bottom(label);
+    if let Some(fingerprint) = options.fingerprint_line(data) {
+        annotations = annotations.bottom(fingerprint);
+    }
+    for line in annotations.lines(renderer, matrix)? {
+        writeln!(target, "{:indent$}{}", "", line, indent = indent)?;
+    }
+    Ok(())
+}
+
+/// Print the given `data` as QR code inside a framed `panel` in the terminal.
+///
+/// The panel is sized to the terminal width unless it has a maximum width already, and
+/// is drawn with ASCII characters on terminals without Unicode support. Returns
+/// [`Error::TerminalTooSmall`] if the code doesn't fit.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::annotate::Panel;
+///
+/// let panel = Panel::new("Scan me").text("Point your phone camera at the code.");
+/// qr2term::print_qr_panel("https://rust-lang.org/", &panel).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_panel<D: AsRef<[u8]>>(data: D, panel: &Panel) -> Result<(), Error> {
+    let options = options();
+    let matrix = options.matrix(&options.qr(&data)?);
+    if !print_alt_text(&options, data.as_ref())? {
+        return Ok(());
+    }
+    write_panel(
+        &options,
+        &options.renderer().for_console(),
+        &matrix,
+        data.as_ref(),
+        panel,
+        term::capabilities().unicode,
+        term::columns(),
+        &mut std::io::stdout().lock(),
+    )
+}
+
+/// Write the `matrix` of `data` inside a `panel` fitting within `columns`, framed with
+/// ASCII characters unless `unicode` is supported.
+#[cfg(feature = "std")]
+#[allow(clippy::too_many_arguments)]
+fn write_panel<W: Write>(
+    options: &Options,
+    renderer: &Renderer,
+    matrix: &Matrix<render::Color>,
+    data: &[u8],
+    panel: &Panel,
+    unicode: bool,
+    columns: Option<usize>,
+    target: &mut W,
+) -> Result<(), Error> {
+    let mut panel = panel.clone();
+    if !unicode {
+        panel = panel.ascii(true);
+    }
+    if let Some(columns) = columns {
+        if panel.max_width.is_none() {
+            panel = panel.max_width(columns);
+        }
+        let width = panel.width(renderer.width(matrix));
+        if width > columns {
+            return Err(Error::TerminalTooSmall { width, columns });
+        }
+    }
+    panel.render(renderer, matrix, target)?;
+    let width = panel.width(renderer.width(matrix));
+    options.write_fingerprint(data, width, target)?;
+    Ok(())
+}
+
+/// Print the given `data` as QR code in the terminal, with annotations around it.
+///
+/// Returns an error if generating the QR code failed, or [`Error::Io`] if printing it
+/// failed.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::annotate::Annotations;
+///
+/// let annotations = Annotations::new()
+///     .top("Rust")
+///     .bottom("https://rust-lang.org/");
+/// qr2term::print_qr_annotated("https://rust-lang.org/", &annotations).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_annotated<D: AsRef<[u8]>>(data: D, annotations: &Annotations) -> Result<(), Error> {
+    let options = options();
+    let matrix = options.matrix(&options.qr(&data)?);
+    if !print_alt_text(&options, data.as_ref())? {
+        return Ok(());
+    }
+    write_annotated(
+        &options,
+        &options.renderer().for_console(),
+        &matrix,
+        data.as_ref(),
+        annotations,
+        &mut std::io::stdout().lock(),
+    )
+}
+
+/// Write the `matrix` of `data` with `annotations` around it.
+#[cfg(feature = "std")]
+fn write_annotated<W: Write>(
+    options: &Options,
+    renderer: &Renderer,
+    matrix: &Matrix<render::Color>,
+    data: &[u8],
+    annotations: &Annotations,
+    target: &mut W,
+) -> Result<(), Error> {
+    annotations.render(renderer, matrix, target)?;
+    options.write_fingerprint(data, renderer.width(matrix), target)?;
+    Ok(())
+}
+
+/// Read all of stdin, and print it as QR code in the terminal.
+///
+/// Stops reading once more data is given than fits in a QR code, failing with
+/// [`QrError::DataTooLong`]. Returns [`Error::Io`] if reading stdin or printing failed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// // echo "https://rust-lang.org/" | my-wrapper
+/// fn main() -> Result<(), qr2term::Error> {
+///     qr2term::print_qr_stdin()
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_stdin() -> Result<(), Error> {
+    let data = qr::read_limited(std::io::stdin().lock(), qr::MAX_DATA_LEN)?;
+    print_qr(data)
+}
+
+/// Print the given `data` as QR code in the terminal, and ask the user whether they
+/// scanned it.
+///
+/// Prints the `prompt` beneath the code and waits for an answer. On a terminal, `y` or
+/// Enter confirm and `n` or `q` decline, otherwise a line is read from stdin. Returns
+/// whether the user confirmed, declining at the end of input.
+///
+/// Returns an error if generating the QR code failed, or [`Error::Io`] if printing it or
+/// reading the answer failed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// let secret = "otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP";
+/// if !qr2term::confirm_scanned(secret, "Did you scan the code?").unwrap() {
+///     eprintln!("Enrollment cancelled");
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn confirm_scanned<D: AsRef<[u8]>>(data: D, prompt: &str) -> Result<bool, Error> {
+    print_qr(data)?;
+    let mut stdout = std::io::stdout().lock();
+    write!(stdout, "{} [Y/n] ", prompt)?;
+    stdout.flush()?;
+    drop(stdout);
+
+    Ok(confirm::read()?)
+}
+
+/// Print formatted data as QR code to stdout or stderr, used by [`print_qr!`] and
+/// [`eprint_qr!`].
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub fn _print_qr(args: std::fmt::Arguments, stderr: bool) -> Result<(), Error> {
+    let options = options();
+
+    // Skip formatting into a new string if there is nothing to format
+    let data = match args.as_str() {
+        Some(data) => std::borrow::Cow::Borrowed(data),
+        None => std::borrow::Cow::Owned(args.to_string()),
+    };
+    let matrix = options.matrix(&options.qr(data.as_bytes())?);
+
+    let renderer = options.renderer().for_console();
+    if stderr {
+        let mut stderr = std::io::stderr().lock();
+        if options.write_alt_text(data.as_bytes(), &mut stderr)? {
+            renderer.render(&matrix, &mut stderr)?;
+            options.write_fingerprint(data.as_bytes(), renderer.width(&matrix), &mut stderr)?;
+        }
+    } else {
+        let mut stdout = std::io::stdout().lock();
+        if options.write_alt_text(data.as_bytes(), &mut stdout)? {
+            renderer.render(&matrix, &mut stdout)?;
+            options.write_fingerprint(data.as_bytes(), renderer.width(&matrix), &mut stdout)?;
+        }
+    }
+    Ok(())
+}
+
+/// Print the given `data` as QR code in the terminal, and report what was printed.
+///
+/// Same as [`print_qr`](fn@print_qr), but returns [`RenderInfo`] describing the printed code. The
+/// code is always printed, even if the [alt text](AltText) is configured to replace it.
+/// The info doesn't include the alt text and fingerprint lines.
+///
+/// Returns an error if generating the QR code or printing it to the terminal failed.
+///
+/// # Examples
+///
+/// ```rust
+/// let info = qr2term::print_qr_with_info("https://rust-lang.org/").unwrap();
+/// println!("QR code is {} columns wide", info.cols);
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_with_info<D: AsRef<[u8]>>(data: D) -> Result<RenderInfo, Error> {
+    // Generate QR code pixel matrix
+    let options = options();
+    let matrix = options.matrix(&options.qr(&data)?);
+
+    // Render QR code to stdout
+    let mut stdout = std::io::stdout().lock();
+    options.write_alt_text(data.as_ref(), &mut stdout)?;
+    let renderer = options.renderer().for_console();
+    let info = renderer.render_with_info(&matrix, &mut stdout)?;
+    options.write_fingerprint(data.as_ref(), renderer.width(&matrix), &mut stdout)?;
+    Ok(info)
+}
+
+/// Print the given `payload` as QR code in the terminal, using its recommended encoding.
+///
+/// Returns an error if the payload is invalid, if generating the QR code failed, or if
+/// printing it to the terminal failed.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Wifi;
+///
+/// qr2term::print_qr_payload(&Wifi::new("My network").password("secret")).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn print_qr_payload<T: ToQrPayload + ?Sized>(payload: &T) -> Result<(), Error> {
+    // Generate QR code pixel matrix
+    let options = options();
+    let payload = payload.to_qr_payload()?;
+    let matrix = options.matrix(&payload.to_qr()?);
+    if !print_alt_text(&options, &payload.data)? {
+        return Ok(());
+    }
+
+    // Render QR code to stdout
+    let renderer = options.renderer().for_console();
+    renderer.render(&matrix, &mut std::io::stdout().lock())?;
+    print_fingerprint(&options, &payload.data, renderer.width(&matrix))?;
     Ok(())
 }
 
@@ -72,19 +490,233 @@ pub fn print_qr<D: AsRef<[u8]>>(data: D) -> Result<(), QrError> {
 /// let qr_string = qr2term::generate_qr_string("https://rust-lang.org/").unwrap();
 /// print!("{}", qr_string);
 /// ```
+#[cfg(feature = "std")]
+pub fn generate_qr_string<D: AsRef<[u8]>>(data: D) -> Result<String, Error> {
+    generate_qr_string_with(data, &options())
+}
+
+/// Generate a `String` for each of the given `items` as QR code, in parallel.
+///
+/// Same as calling [`generate_qr_string`] for each item, but encodes and renders them on
+/// all available cores. Results are returned in the order of the items. See
+/// [`Batch`](batch::Batch) to cancel generating.
+///
+/// # Examples
+///
+/// ```rust
+/// let tickets = ["ticket-1", "ticket-2", "ticket-3"];
+/// let codes = qr2term::generate_qr_strings_par(tickets);
+/// assert_eq!(codes.len(), tickets.len());
+/// assert_eq!(
+///     codes[1].as_ref().unwrap(),
+///     &qr2term::generate_qr_string("ticket-2").unwrap(),
+/// );
+/// ```
+#[cfg(feature = "parallel")]
+pub fn generate_qr_strings_par<I>(items: I) -> Vec<Result<String, Error>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]> + Sync,
+{
+    batch::Batch::new().generate_strings(items)
+}
+
+/// Generate `String` from the given `data` as QR code, with the given options.
+///
+/// Same as [`generate_qr_string`], but generates and renders the code with `options`
+/// instead of the default options. Environment overrides don't apply.
+///
+/// Returns an error if generating the QR code failed.
+///
+/// # Examples
 ///
-/// # Panics
+/// ```rust
+/// use qr2term::render::Density;
+/// use qr2term::Options;
 ///
-/// Panics if generating the QR code string failed.
-pub fn generate_qr_string<D: AsRef<[u8]>>(data: D) -> Result<String, QrError> {
+/// let options = Options::default().density(Density::Full).colors(false);
+/// let qr_string = qr2term::generate_qr_string_with("https://rust-lang.org/", &options).unwrap();
+/// assert!(qr_string.contains("##"));
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_qr_string_with<D: AsRef<[u8]>>(
+    data: D,
+    options: &Options,
+) -> Result<String, Error> {
     // Generate QR code pixel matrix
-    let mut matrix = qr::Qr::from(data)?.to_matrix();
-    matrix.surround(QUIET_ZONE_WIDTH, render::QrLight);
+    let matrix = options.matrix(&options.qr(data)?);
 
     // Render QR code to a String
     let mut buf = Vec::new();
-    Renderer::default()
-        .render(&matrix, &mut buf)
-        .expect("failed to generate QR code string");
-    Ok(String::from_utf8(buf).unwrap())
+    options.renderer().render(&matrix, &mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+/// Print the given `data` as barcode of the given `symbology` in the terminal.
+///
+/// Uses the rendering options and quiet zone of the [default options](default_options),
+/// while the symbology decides how data is encoded.
+///
+/// Returns an error if encoding the data failed, or [`Error::Io`] if printing it to the
+/// terminal failed.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::symbology::DataMatrix;
+///
+/// qr2term::print_code(&DataMatrix, "https://rust-lang.org/").unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn print_code<S: Symbology + ?Sized, D: AsRef<[u8]>>(
+    symbology: &S,
+    data: D,
+) -> Result<(), Error> {
+    let options = options();
+    let matrix = options.surround(symbology.encode(data.as_ref())?);
+    if !print_alt_text(&options, data.as_ref())? {
+        return Ok(());
+    }
+
+    let renderer = options.renderer().for_console();
+    renderer.render(&matrix, &mut std::io::stdout().lock())?;
+    print_fingerprint(&options, data.as_ref(), renderer.width(&matrix))?;
+    Ok(())
+}
+
+/// Generate `String` from the given `data` as barcode of the given `symbology`.
+///
+/// Returns an error if encoding the data failed.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::symbology::MicroQr;
+///
+/// let code_string = qr2term::generate_code_string(&MicroQr::default(), "12345").unwrap();
+/// print!("{}", code_string);
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_code_string<S: Symbology + ?Sized, D: AsRef<[u8]>>(
+    symbology: &S,
+    data: D,
+) -> Result<String, Error> {
+    let options = options();
+    let matrix = options.surround(symbology.encode(data.as_ref())?);
+
+    let mut buf = Vec::new();
+    options.renderer().render(&matrix, &mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+/// Print the plain text alternative of `data` to stdout, if enabled in `options`.
+///
+/// Returns whether the code should still be printed.
+#[cfg(feature = "std")]
+fn print_alt_text(options: &Options, data: &[u8]) -> Result<bool, Error> {
+    Ok(options.write_alt_text(data, &mut std::io::stdout().lock())?)
+}
+
+/// Print the fingerprint line of `data` to stdout, if enabled in `options`.
+#[cfg(feature = "std")]
+fn print_fingerprint(options: &Options, data: &[u8], width: usize) -> Result<(), Error> {
+    Ok(options.write_fingerprint(data, width, &mut std::io::stdout().lock())?)
+}
+
+/// Get a short fingerprint of `data`, being the first 8 hexadecimal characters of its
+/// SHA-256 digest.
+///
+/// Printed under codes when enabled through [`Options::fingerprint`], so the receiving
+/// side can compare it against the fingerprint of what it scanned.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(qr2term::fingerprint("abc"), "ba7816bf");
+/// ```
+#[cfg(feature = "std")]
+pub fn fingerprint<D: AsRef<[u8]>>(data: D) -> String {
+    util::hex(&util::sha256(data.as_ref())[..4])
+}
+
+/// Get the options for the top-level functions, with the environment overrides applied.
+#[cfg(feature = "std")]
+pub(crate) fn options() -> Options {
+    env::Overrides::from_env().apply(default_options())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::render::Theme;
+    use crate::term::{Capabilities, ColorDepth};
+
+    /// The helpers must apply the console fallbacks, here to ASCII and 16 colors.
+    #[test]
+    fn helpers_render_for_console() {
+        let options = Options::default().theme(Theme::named("nord").unwrap());
+        let data = b"https://rust-lang.org/";
+        let matrix = options.matrix(&options.qr(data).unwrap());
+        let caps = Capabilities {
+            color: ColorDepth::Ansi16,
+            unicode: false,
+            ..term::capabilities()
+        };
+        let renderer = options.renderer().for_terminal(&caps, None, Some(false));
+        let check = |out: Vec<u8>| {
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.is_ascii(), "non-ASCII output: {}", out);
+            assert!(!out.contains(";2;"), "true color output: {}", out);
+        };
+
+        let mut out = Vec::new();
+        options.renderer().render(&matrix, &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(";2;"));
+
+        let mut out = Vec::new();
+        write_centered_with_label(
+            &options,
+            &renderer,
+            &matrix,
+            data,
+            "Rust",
+            Some(80),
+            &mut out,
+        )
+        .unwrap();
+        check(out);
+
+        let mut out = Vec::new();
+        let panel = Panel::new("Rust");
+        write_panel(
+            &options,
+            &renderer,
+            &matrix,
+            data,
+            &panel,
+            caps.unicode,
+            None,
+            &mut out,
+        )
+        .unwrap();
+        check(out);
+
+        let mut out = Vec::new();
+        let annotations = Annotations::new().top("Rust");
+        write_annotated(&options, &renderer, &matrix, data, &annotations, &mut out).unwrap();
+        check(out);
+    }
+
+    #[test]
+    fn strict_colors() {
+        let options = Options::default().invert(true).strict(true);
+        assert!(matches!(
+            print_qr_with("https://rust-lang.org/", &options),
+            Err(Error::InvalidOption(_))
+        ));
+        assert!(matches!(
+            generate_qr_string_with("https://rust-lang.org/", &options),
+            Err(Error::InvalidOption(_))
+        ));
+    }
 }
@@ -0,0 +1,195 @@
+//! Code 128 encoding.
+
+use super::{BarcodeError, Bars};
+
+/// Element widths of all symbols, alternating bar and space starting with a bar.
+#[rustfmt::skip]
+const PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE_C: u8 = 99;
+const CODE_B: u8 = 100;
+const CODE_A: u8 = 101;
+const START_A: u8 = 103;
+const STOP: u8 = 106;
+
+/// Code set, determining how characters map onto symbol values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Set {
+    /// Upper case and control characters.
+    A,
+
+    /// Upper and lower case characters.
+    B,
+
+    /// Pairs of digits.
+    C,
+}
+
+impl Set {
+    /// Symbol value of `byte` in this set, if it has one.
+    fn value(self, byte: u8) -> Option<u8> {
+        match (self, byte) {
+            (Set::A, 0..=31) => Some(byte + 64),
+            (Set::A, 32..=95) | (Set::B, 32..=127) => Some(byte - 32),
+            _ => None,
+        }
+    }
+
+    /// Symbol value switching to this set, or starting with it.
+    fn code(self, start: bool) -> u8 {
+        match (self, start) {
+            (set, true) => START_A + set as u8,
+            (Set::A, false) => CODE_A,
+            (Set::B, false) => CODE_B,
+            (Set::C, false) => CODE_C,
+        }
+    }
+}
+
+/// Code 128 barcode.
+///
+/// Encodes any ASCII data, switching between code sets to keep the barcode short.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Code128 {
+    values: Vec<u8>,
+}
+
+impl Code128 {
+    /// Construct a new Code 128 barcode.
+    ///
+    /// Fails with [`BarcodeError::InvalidCharacter`] if the data contains non-ASCII bytes.
+    pub fn from<D: AsRef<[u8]>>(data: D) -> Result<Self, BarcodeError> {
+        let data = data.as_ref();
+        if let Some(byte) = data.iter().find(|byte| !byte.is_ascii()) {
+            return Err(BarcodeError::InvalidCharacter(*byte as char));
+        }
+
+        let mut values = Vec::with_capacity(data.len() + 3);
+        let mut set: Option<Set> = None;
+        let mut i = 0;
+        while i < data.len() {
+            let digits = data[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if set == Some(Set::C) && digits >= 2 {
+                values.push((data[i] - b'0') * 10 + data[i + 1] - b'0');
+                i += 2;
+                continue;
+            }
+
+            // Pack digit runs into pairs, if that is shorter than switching back and forth
+            let end = i + digits == data.len();
+            let pack = match set {
+                None => digits >= 4 || (end && digits == 2),
+                Some(Set::C) => false,
+                Some(_) => digits >= 6 || (end && digits >= 4),
+            };
+            if pack && digits % 2 == 0 {
+                values.push(Set::C.code(set.is_none()));
+                set = Some(Set::C);
+                continue;
+            }
+
+            // Switch to a set that can encode the next character, unless the current can
+            let current = match set.filter(|set| set.value(data[i]).is_some()) {
+                Some(current) => current,
+                None => {
+                    let next = next_set(&data[i..]);
+                    values.push(next.code(set.is_none()));
+                    set = Some(next);
+                    next
+                }
+            };
+            values.push(current.value(data[i]).unwrap());
+            i += 1;
+        }
+        if values.is_empty() {
+            values.push(Set::B.code(true));
+        }
+
+        let checksum = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| i.max(1) * *value as usize)
+            .sum::<usize>()
+            % 103;
+        values.push(checksum as u8);
+        values.push(STOP);
+        Ok(Self { values })
+    }
+
+    /// Create bars from this barcode.
+    pub fn to_bars(&self) -> Bars {
+        Bars::from_widths(
+            self.values
+                .iter()
+                .flat_map(|value| PATTERNS[*value as usize].bytes())
+                .map(|width| width - b'0'),
+        )
+    }
+}
+
+/// Pick code set A or B to encode the start of `data` with, whichever fits longest.
+fn next_set(data: &[u8]) -> Set {
+    let first_a_only = data.iter().position(|b| Set::B.value(*b).is_none());
+    let first_b_only = data.iter().position(|b| Set::A.value(*b).is_none());
+    match (first_a_only, first_b_only) {
+        (Some(a), Some(b)) if a < b => Set::A,
+        (Some(_), None) => Set::A,
+        _ => Set::B,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Symbol values, excluding the checksum and stop symbol.
+    fn values(data: &str) -> Vec<u8> {
+        let code = Code128::from(data).unwrap();
+        code.values[..code.values.len() - 2].to_vec()
+    }
+
+    #[test]
+    fn patterns() {
+        for (value, pattern) in PATTERNS.iter().enumerate() {
+            let width: u32 = pattern.bytes().map(|b| (b - b'0') as u32).sum();
+            assert_eq!(width, if value == STOP as usize { 13 } else { 11 });
+        }
+    }
+
+    #[test]
+    fn code_sets() {
+        assert_eq!(values("Wiki"), [104, 55, 73, 75, 73]);
+        assert_eq!(values("123456"), [105, 12, 34, 56]);
+        assert_eq!(values("12345"), [104, 17, 99, 23, 45]);
+        assert_eq!(values("AB\n"), [103, 33, 34, 74]);
+        assert_eq!(values("a\nb"), [104, 65, 101, 74, 100, 66]);
+        assert_eq!(values("X1234567"), [104, 56, 17, 99, 23, 45, 67]);
+        assert_eq!(values("X12"), [104, 56, 17, 18]);
+        assert_eq!(values(""), [104]);
+        assert_eq!(
+            Code128::from("é").err(),
+            Some(BarcodeError::InvalidCharacter('Ã'))
+        );
+    }
+
+    #[test]
+    fn checksum_and_bars() {
+        let code = Code128::from("Wikipedia").unwrap();
+        assert_eq!(code.values[code.values.len() - 2], 88);
+        let bars = code.to_bars();
+        assert_eq!(bars.width(), 11 * 11 + 13);
+    }
+}
@@ -0,0 +1,138 @@
+//! Code 39 encoding.
+
+use super::{BarcodeError, Bars};
+
+/// Characters in order of their value, used for the check digit.
+const CHARACTERS: &[u8; 43] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+/// Wide elements of all characters, alternating bar and space starting with a bar.
+#[rustfmt::skip]
+const PATTERNS: [&str; 43] = [
+    "nnnwwnwnn", "wnnwnnnnw", "nnwwnnnnw", "wnwwnnnnn", "nnnwwnnnw", "wnnwwnnnn", "nnwwwnnnn",
+    "nnnwnnwnw", "wnnwnnwnn", "nnwwnnwnn", "wnnnnwnnw", "nnwnnwnnw", "wnwnnwnnn", "nnnnwwnnw",
+    "wnnnwwnnn", "nnwnwwnnn", "nnnnnwwnw", "wnnnnwwnn", "nnwnnwwnn", "nnnnwwwnn", "wnnnnnnww",
+    "nnwnnnnww", "wnwnnnnwn", "nnnnwnnww", "wnnnwnnwn", "nnwnwnnwn", "nnnnnnwww", "wnnnnnwwn",
+    "nnwnnnwwn", "nnnnwnwwn", "wwnnnnnnw", "nwwnnnnnw", "wwwnnnnnn", "nwnnwnnnw", "wwnnwnnnn",
+    "nwwnwnnnn", "nwnnnnwnw", "wwnnnnwnn", "nwwnnnwnn", "nwnwnwnnn", "nwnwnnnwn", "nwnnnwnwn",
+    "nnnwnwnwn",
+];
+
+/// Pattern of the start and stop character `*`.
+const START_STOP: &str = "nwnnwnwnn";
+
+/// Width of wide elements in modules, narrow ones being one module.
+const WIDE: u8 = 2;
+
+/// Width of a character including the gap after it in modules.
+const CHARACTER_WIDTH: usize = 3 * WIDE as usize + 6 + 1;
+
+/// Code 39 barcode.
+///
+/// Encodes digits, upper case letters and the characters `-. $/+%`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Code39 {
+    data: String,
+    values: Vec<u8>,
+}
+
+impl Code39 {
+    /// Construct a new Code 39 barcode.
+    ///
+    /// Fails with [`BarcodeError::InvalidCharacter`] if the data contains characters that
+    /// can't be encoded. Lower case letters must be converted to upper case first.
+    pub fn from<D: AsRef<str>>(data: D) -> Result<Self, BarcodeError> {
+        let data = data.as_ref();
+        let values = data
+            .chars()
+            .map(|c| {
+                CHARACTERS
+                    .iter()
+                    .position(|b| *b as char == c)
+                    .map(|value| value as u8)
+                    .ok_or(BarcodeError::InvalidCharacter(c))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            data: data.into(),
+            values,
+        })
+    }
+
+    /// Construct a new Code 39 barcode with a modulo 43 check digit appended.
+    pub fn with_check_digit<D: AsRef<str>>(data: D) -> Result<Self, BarcodeError> {
+        let mut code = Self::from(data)?;
+        let check = (code.values.iter().map(|v| *v as u32).sum::<u32>() % 43) as u8;
+        code.values.push(check);
+        code.data.push(CHARACTERS[check as usize] as char);
+        Ok(code)
+    }
+
+    /// Get the encoded data, including the check digit if any.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Create bars from this barcode, with the data as human-readable text.
+    pub fn to_bars(&self) -> Bars {
+        let patterns = std::iter::once(START_STOP)
+            .chain(self.values.iter().map(|value| PATTERNS[*value as usize]))
+            .chain(std::iter::once(START_STOP));
+
+        let mut widths = Vec::with_capacity((self.values.len() + 2) * 10);
+        for pattern in patterns {
+            widths.extend(pattern.bytes().map(|b| if b == b'w' { WIDE } else { 1 }));
+            // Narrow gap between characters
+            widths.push(1);
+        }
+        widths.pop();
+
+        let mut bars = Bars::from_widths(widths);
+        bars.set_text(
+            self.data
+                .chars()
+                .enumerate()
+                .map(|(i, c)| ((i + 1) * CHARACTER_WIDTH + CHARACTER_WIDTH / 2, c))
+                .collect(),
+        );
+        bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{QrDark, QrLight};
+
+    #[test]
+    fn patterns() {
+        for pattern in PATTERNS.iter().chain(std::iter::once(&START_STOP)) {
+            assert_eq!(pattern.len(), 9);
+            assert_eq!(pattern.bytes().filter(|b| *b == b'w').count(), 3);
+        }
+    }
+
+    #[test]
+    fn check_digit() {
+        assert_eq!(Code39::from("CODE39").unwrap().data(), "CODE39");
+        assert_eq!(
+            Code39::with_check_digit("CODE39").unwrap().data(),
+            "CODE39W"
+        );
+        assert_eq!(
+            Code39::from("code").err(),
+            Some(BarcodeError::InvalidCharacter('c'))
+        );
+    }
+
+    #[test]
+    fn bars() {
+        let bars = Code39::from("A").unwrap().to_bars();
+        assert_eq!(bars.width(), 3 * CHARACTER_WIDTH - 1);
+        let expected: Vec<_> = "1001011011010110101001011"
+            .chars()
+            .map(|c| if c == '1' { QrDark } else { QrLight })
+            .collect();
+        assert_eq!(bars.modules()[..25], expected[..]);
+        assert_eq!(bars.text(), [(CHARACTER_WIDTH + 6, 'A')]);
+    }
+}
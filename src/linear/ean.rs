@@ -0,0 +1,170 @@
+//! EAN-13 and UPC-A encoding.
+
+use super::{BarcodeError, Bars};
+
+/// Left hand digit patterns with odd parity, right hand patterns are their complement.
+#[rustfmt::skip]
+const PATTERNS: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011,
+    0b0110001, 0b0101111, 0b0111011, 0b0110111, 0b0001011,
+];
+
+/// Parity of the left hand digits encoding the first digit, set bits use even parity.
+#[rustfmt::skip]
+const PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011,
+    0b011001, 0b011100, 0b010101, 0b010110, 0b011010,
+];
+
+/// Width of the quiet zone on both sides in modules, holding the first digit on the left.
+const QUIET_ZONE: usize = 11;
+
+/// EAN-13 barcode, also used for UPC-A.
+///
+/// Bars include the standard quiet zone and the human-readable digits beneath them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ean13 {
+    digits: [u8; 13],
+}
+
+impl Ean13 {
+    /// Construct a new EAN-13 barcode from 12 digits, or 13 including the check digit.
+    ///
+    /// The check digit is computed if missing, and verified if given.
+    pub fn from<D: AsRef<str>>(data: D) -> Result<Self, BarcodeError> {
+        let data = parse_digits(data.as_ref(), 12)?;
+        let mut digits = [0; 13];
+        digits[..12].copy_from_slice(&data[..12]);
+        digits[12] = check_digit(&digits[..12]);
+        if data.len() == 13 && data[12] != digits[12] {
+            return Err(BarcodeError::InvalidCheckDigit);
+        }
+        Ok(Self { digits })
+    }
+
+    /// Construct a new barcode from a UPC-A number, of 11 digits or 12 including the check
+    /// digit.
+    ///
+    /// UPC-A is encoded as EAN-13 with a leading zero.
+    pub fn from_upc_a<D: AsRef<str>>(data: D) -> Result<Self, BarcodeError> {
+        let data = data.as_ref();
+        parse_digits(data, 11)?;
+        Self::from(format!("0{}", data))
+    }
+
+    /// Get all 13 digits, including the check digit.
+    pub fn digits(&self) -> &[u8; 13] {
+        &self.digits
+    }
+
+    /// Create bars from this barcode.
+    pub fn to_bars(&self) -> Bars {
+        let mut modules = String::with_capacity(95);
+        let parity = PARITY[self.digits[0] as usize];
+        modules.push_str("101");
+        for (i, digit) in self.digits[1..7].iter().enumerate() {
+            let pattern = PATTERNS[*digit as usize];
+            if parity & (0b100000 >> i) == 0 {
+                modules.push_str(&format!("{:07b}", pattern));
+            } else {
+                // Even parity patterns are the mirrored right hand pattern
+                modules.push_str(
+                    &format!("{:07b}", !pattern & 0x7F)
+                        .chars()
+                        .rev()
+                        .collect::<String>(),
+                );
+            }
+        }
+        modules.push_str("01010");
+        for digit in &self.digits[7..] {
+            modules.push_str(&format!("{:07b}", !PATTERNS[*digit as usize] & 0x7F));
+        }
+        modules.push_str("101");
+
+        let mut bars = Bars::from_modules(&modules);
+        bars.surround(QUIET_ZONE);
+
+        // Center digits beneath their patterns, the first one left of the bars
+        let mut text = vec![(QUIET_ZONE - 4, (b'0' + self.digits[0]) as char)];
+        for (i, digit) in self.digits[1..].iter().enumerate() {
+            let guard = if i < 6 { 3 } else { 8 };
+            text.push((QUIET_ZONE + guard + i * 7 + 3, (b'0' + digit) as char));
+        }
+        bars.set_text(text);
+        bars
+    }
+}
+
+/// Parse a string of `len` digits, or one more including a check digit.
+fn parse_digits(data: &str, len: usize) -> Result<Vec<u8>, BarcodeError> {
+    if let Some(c) = data.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(BarcodeError::InvalidCharacter(c));
+    }
+    if data.len() != len && data.len() != len + 1 {
+        return Err(BarcodeError::InvalidLength(data.len()));
+    }
+    Ok(data.bytes().map(|b| b - b'0').collect())
+}
+
+/// Compute the check digit, weighting digits alternately by 1 and 3.
+fn check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .enumerate()
+        .map(|(i, digit)| *digit as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{QrDark, QrLight};
+
+    #[test]
+    fn check_digits() {
+        assert_eq!(Ean13::from("400638133393").unwrap().digits()[12], 1);
+        assert!(Ean13::from("4006381333931").is_ok());
+        assert_eq!(
+            Ean13::from("4006381333932").err(),
+            Some(BarcodeError::InvalidCheckDigit)
+        );
+        assert_eq!(
+            Ean13::from("40063813339").err(),
+            Some(BarcodeError::InvalidLength(11))
+        );
+        assert_eq!(
+            Ean13::from("40063813339a").err(),
+            Some(BarcodeError::InvalidCharacter('a'))
+        );
+        assert_eq!(
+            Ean13::from_upc_a("03600029145").unwrap().digits(),
+            &[0, 0, 3, 6, 0, 0, 0, 2, 9, 1, 4, 5, 2]
+        );
+        assert_eq!(
+            Ean13::from_upc_a("0360002914").err(),
+            Some(BarcodeError::InvalidLength(10))
+        );
+    }
+
+    #[test]
+    fn bars() {
+        let bars = Ean13::from("4006381333931").unwrap().to_bars();
+        assert_eq!(bars.width(), 95 + 2 * QUIET_ZONE);
+        assert_eq!(bars.quiet_zone(), QUIET_ZONE);
+
+        // Start guard, then 0 with odd and 0 with even parity
+        let modules = &bars.modules()[QUIET_ZONE..QUIET_ZONE + 17];
+        let expected: Vec<_> = "10100011010100111"
+            .chars()
+            .map(|c| if c == '1' { QrDark } else { QrLight })
+            .collect();
+        assert_eq!(modules, &expected[..]);
+
+        let text: String = bars.text().iter().map(|(_, c)| c).collect();
+        assert_eq!(text, "4006381333931");
+        assert_eq!(bars.text()[1].0, QUIET_ZONE + 6);
+        assert_eq!(bars.text()[7].0, QUIET_ZONE + 53);
+    }
+}
@@ -0,0 +1,32 @@
+//! Barcode encoding error.
+
+use std::{error, fmt};
+
+/// Error returned when data can't be encoded as one-dimensional barcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BarcodeError {
+    /// The data contains a character the symbology can't encode.
+    InvalidCharacter(char),
+
+    /// The data has a length the symbology can't encode.
+    InvalidLength(usize),
+
+    /// The given check digit doesn't match the data.
+    InvalidCheckDigit,
+}
+
+impl fmt::Display for BarcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BarcodeError::InvalidCharacter(character) => {
+                write!(f, "barcode data contains invalid character {:?}", character)
+            }
+            BarcodeError::InvalidLength(len) => {
+                write!(f, "barcode data has invalid length of {}", len)
+            }
+            BarcodeError::InvalidCheckDigit => f.write_str("barcode check digit is invalid"),
+        }
+    }
+}
+
+impl error::Error for BarcodeError {}
@@ -0,0 +1,124 @@
+//! One-dimensional barcodes.
+//!
+//! Encoders produce [`Bars`], which are printed with
+//! [`Renderer::render_bars`](crate::render::Renderer::render_bars) using the same styling
+//! options as 2D barcodes.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::linear::Code128;
+//! use qr2term::render::Renderer;
+//!
+//! let mut bars = Code128::from("SKU-12345").unwrap().to_bars();
+//! bars.surround(10);
+//! Renderer::default().print_bars_stdout(&bars).unwrap();
+//! ```
+
+mod code128;
+mod code39;
+mod ean;
+mod error;
+
+pub use code128::Code128;
+pub use code39::Code39;
+pub use ean::Ean13;
+pub use error::BarcodeError;
+
+use crate::render::{Color, QrDark, QrLight};
+
+/// Row of bars representing a one-dimensional barcode, dark or light per module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bars {
+    modules: Vec<Color>,
+    quiet_zone: usize,
+    text: Vec<(usize, char)>,
+}
+
+impl Bars {
+    /// Construct bars from the given modules.
+    pub fn new(modules: Vec<Color>) -> Self {
+        Self {
+            modules,
+            quiet_zone: 0,
+            text: Vec::new(),
+        }
+    }
+
+    /// Construct bars from alternating dark and light element widths, starting with dark.
+    pub(crate) fn from_widths<I: IntoIterator<Item = u8>>(widths: I) -> Self {
+        let mut modules = Vec::new();
+        for (i, width) in widths.into_iter().enumerate() {
+            let color = if i % 2 == 0 { QrDark } else { QrLight };
+            modules.extend(std::iter::repeat(color).take(width as usize));
+        }
+        Self::new(modules)
+    }
+
+    /// Construct bars from a string of `1` for dark and `0` for light modules.
+    pub(crate) fn from_modules(modules: &str) -> Self {
+        Self::new(
+            modules
+                .bytes()
+                .map(|b| if b == b'1' { QrDark } else { QrLight })
+                .collect(),
+        )
+    }
+
+    /// Get the width of the barcode in modules.
+    pub fn width(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Get the modules, from left to right.
+    pub fn modules(&self) -> &[Color] {
+        &self.modules
+    }
+
+    /// Get the width of the quiet zone on both sides in modules.
+    pub fn quiet_zone(&self) -> usize {
+        self.quiet_zone
+    }
+
+    /// Get the human-readable text printed beneath the bars, as characters and the module
+    /// they are centered on.
+    pub fn text(&self) -> &[(usize, char)] {
+        &self.text
+    }
+
+    /// Set the human-readable text printed beneath the bars, as characters and the module
+    /// they are centered on.
+    pub fn set_text(&mut self, text: Vec<(usize, char)>) {
+        self.text = text;
+    }
+
+    /// Surround the bars with light modules with the given `thickness` on both sides.
+    ///
+    /// Scanners need a quiet zone of at least 10 modules for most symbologies.
+    pub fn surround(&mut self, thickness: usize) {
+        let mut modules = vec![QrLight; self.modules.len() + thickness * 2];
+        modules[thickness..thickness + self.modules.len()].copy_from_slice(&self.modules);
+        self.modules = modules;
+        self.quiet_zone += thickness;
+        for (module, _) in &mut self.text {
+            *module += thickness;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_and_surround() {
+        let mut bars = Bars::from_widths([2, 1, 1]);
+        assert_eq!(bars.modules(), [QrDark, QrDark, QrLight, QrDark]);
+        bars.set_text(vec![(1, 'x')]);
+        bars.surround(2);
+        assert_eq!(bars.width(), 8);
+        assert_eq!(bars.text(), [(3, 'x')]);
+        assert_eq!(bars.quiet_zone(), 2);
+        assert_eq!(bars.modules()[..3], [QrLight, QrLight, QrDark]);
+    }
+}
@@ -0,0 +1,36 @@
+//! Printing macros.
+
+/// Print the formatted data as QR code in the terminal.
+///
+/// Takes the same arguments as [`format!`], and returns the same as
+/// [`print_qr`](fn@crate::print_qr).
+///
+/// # Examples
+///
+/// ```rust
+/// let id = 42;
+/// qr2term::print_qr!("https://example.com/{}", id).unwrap();
+/// ```
+#[macro_export]
+macro_rules! print_qr {
+    ($($arg:tt)*) => {
+        $crate::_print_qr(::std::format_args!($($arg)*), false)
+    };
+}
+
+/// Print the formatted data as QR code to stderr.
+///
+/// Same as [`print_qr!`](crate::print_qr!), but prints to stderr instead of stdout.
+///
+/// # Examples
+///
+/// ```rust
+/// let id = 42;
+/// qr2term::eprint_qr!("https://example.com/{}", id).unwrap();
+/// ```
+#[macro_export]
+macro_rules! eprint_qr {
+    ($($arg:tt)*) => {
+        $crate::_print_qr(::std::format_args!($($arg)*), true)
+    };
+}
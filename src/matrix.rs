@@ -1,11 +1,16 @@
 //! Matrix types representing 2D barcode.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 use crate::util;
 
 /// A square 2D matrix representing a barcode.
 #[derive(Debug)]
 pub struct Matrix<T> {
     pixels: Vec<T>,
+    size: usize,
+    quiet_zone: usize,
 }
 
 impl<T> Matrix<T> {
@@ -16,14 +21,18 @@ impl<T> Matrix<T> {
     /// Panics if given pixel map does not have a length that is a multiple of 2.
     pub fn new(pixels: Vec<T>) -> Self {
         // Assert pixels being multiple of 2
-        util::usize_sqrt(pixels.len());
+        let size = util::usize_sqrt(pixels.len());
 
-        Self { pixels }
+        Self {
+            pixels,
+            size,
+            quiet_zone: 0,
+        }
     }
 
     /// Get the width and height of the QR code in pixels.
     pub fn size(&self) -> usize {
-        util::usize_sqrt(self.pixels.len())
+        self.size
     }
 
     /// Get the pixel matrix.
@@ -31,6 +40,13 @@ impl<T> Matrix<T> {
         &self.pixels
     }
 
+    /// Get the width of the quiet zone around the barcode in pixels.
+    ///
+    /// This is the sum of all thicknesses this matrix was surrounded with.
+    pub fn quiet_zone(&self) -> usize {
+        self.quiet_zone
+    }
+
     /// Surround this matrix with `quiet` pixels having the specified `thickness`.
     pub fn surround(&mut self, thickness: usize, quiet: T)
     where
@@ -53,10 +69,191 @@ impl<T> Matrix<T> {
         }
 
         self.pixels = out;
+        self.size = out_width;
+        self.quiet_zone += thickness;
+    }
+
+    /// Get the pixel at column `x` and row `y`, or `None` if it is outside the matrix.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        let size = self.size();
+        if x < size && y < size {
+            self.pixels.get(y * size + x)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over views of `rows` full rows each, from top to bottom.
+    ///
+    /// The last view has fewer rows if the size isn't a multiple of `rows`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` is zero.
+    pub fn chunks(&self, rows: usize) -> Chunks<'_, T> {
+        assert!(rows > 0, "chunk rows must be at least 1");
+        Chunks {
+            matrix: self,
+            rows,
+            y: 0,
+        }
+    }
+
+    /// Iterate over views of `cols` by `rows` pixels each, row by row from the top left.
+    ///
+    /// Views at the right and bottom edge are smaller if the size isn't a multiple of
+    /// `cols` or `rows`. Meant for renderers mapping a grid of pixels onto each character,
+    /// such as two by four for braille.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cols` or `rows` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use qr2term::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new((0..9).collect());
+    /// let tiles: Vec<_> = matrix.tiles(2, 2).map(|tile| tile.get(0, 0).copied()).collect();
+    /// assert_eq!(tiles, [Some(0), Some(2), Some(6), Some(8)]);
+    /// ```
+    pub fn tiles(&self, cols: usize, rows: usize) -> Tiles<'_, T> {
+        assert!(cols > 0 && rows > 0, "tile size must be at least 1 by 1");
+        Tiles {
+            matrix: self,
+            cols,
+            rows,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    /// Get a view of the given area, clipped to the matrix.
+    fn view(&self, x: usize, y: usize, width: usize, height: usize) -> View<'_, T> {
+        let size = self.size();
+        View {
+            matrix: self,
+            x,
+            y,
+            width: width.min(size.saturating_sub(x)),
+            height: height.min(size.saturating_sub(y)),
+        }
     }
 }
 
-#[cfg(test)]
+/// Rectangular view of the pixels in part of a [`Matrix`].
+///
+/// Positions are relative to the top left of the view.
+#[derive(Debug)]
+pub struct View<'a, T> {
+    matrix: &'a Matrix<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<'a, T> View<'a, T> {
+    /// Get the column of the left edge of this view in the matrix.
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    /// Get the row of the top edge of this view in the matrix.
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    /// Get the width of this view in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of this view in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the pixel at column `x` and row `y` of this view, or `None` if it is outside
+    /// the view.
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x < self.width && y < self.height {
+            self.matrix.get(self.x + x, self.y + y)
+        } else {
+            None
+        }
+    }
+
+    /// Get the pixels of row `y` of this view, or `None` if it is outside the view.
+    pub fn row(&self, y: usize) -> Option<&'a [T]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = (self.y + y) * self.matrix.size() + self.x;
+        Some(&self.matrix.pixels[start..start + self.width])
+    }
+}
+
+/// Iterator over views of full rows of a [`Matrix`], see [`Matrix::chunks`].
+#[derive(Debug)]
+pub struct Chunks<'a, T> {
+    matrix: &'a Matrix<T>,
+    rows: usize,
+    y: usize,
+}
+
+impl<'a, T> Iterator for Chunks<'a, T> {
+    type Item = View<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.matrix.size();
+        if self.y >= size {
+            return None;
+        }
+        let view = self.matrix.view(0, self.y, size, self.rows);
+        self.y += self.rows;
+        Some(view)
+    }
+}
+
+/// Iterator over rectangular views of a [`Matrix`], see [`Matrix::tiles`].
+#[derive(Debug)]
+pub struct Tiles<'a, T> {
+    matrix: &'a Matrix<T>,
+    cols: usize,
+    rows: usize,
+    x: usize,
+    y: usize,
+}
+
+impl<'a, T> Iterator for Tiles<'a, T> {
+    type Item = View<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.matrix.size();
+        if self.y >= size {
+            return None;
+        }
+        let view = self.matrix.view(self.x, self.y, self.cols, self.rows);
+        self.x += self.cols;
+        if self.x >= size {
+            self.x = 0;
+            self.y += self.rows;
+        }
+        Some(view)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use qrcode::types::Color::{Dark as QrDark, Light as QrLight};
 
@@ -101,4 +298,38 @@ mod tests {
         let expected = vec![7; (3 * 2) * (3 * 2)];
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn chunks_and_tiles() {
+        let matrix = Matrix::new((0..9).collect::<Vec<u8>>());
+        let chunks: Vec<_> = matrix.chunks(2).collect();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].row(1), Some(&[3, 4, 5][..]));
+        assert_eq!((chunks[1].y(), chunks[1].height()), (2, 1));
+        assert_eq!(chunks[1].get(2, 0), Some(&8));
+        assert_eq!(chunks[1].get(0, 1), None);
+
+        let tiles: Vec<_> = matrix.tiles(2, 2).collect();
+        assert_eq!(tiles.len(), 4);
+        assert_eq!(
+            (tiles[1].x(), tiles[1].width(), tiles[1].height()),
+            (2, 1, 2)
+        );
+        assert_eq!(tiles[1].get(0, 1), Some(&5));
+        assert_eq!(tiles[1].get(1, 0), None);
+        assert_eq!(tiles[3].row(0), Some(&[8][..]));
+        assert_eq!(matrix.get(3, 0), None);
+
+        assert_eq!(Matrix::<u8>::new(vec![]).tiles(1, 1).count(), 0);
+    }
+
+    #[test]
+    fn surround_quiet_zone_accumulates() {
+        let mut matrix = Matrix::new(vec![0]);
+        assert_eq!(matrix.quiet_zone(), 0);
+        matrix.surround(2, 1);
+        matrix.surround(1, 1);
+        assert_eq!(matrix.quiet_zone(), 3);
+        assert_eq!(matrix.size(), 7);
+    }
 }
@@ -0,0 +1,382 @@
+//! Options for the top-level functions.
+
+use std::io::{Result as IoResult, Write};
+use std::sync::RwLock;
+
+use crate::matrix::Matrix;
+use crate::qr::{EcLevel, Qr};
+use crate::render::{self, Color, Density, Renderer, Theme};
+use crate::{QrError, QUIET_ZONE_WIDTH};
+
+/// Options set through [`set_default_options`], if any.
+static DEFAULT_OPTIONS: RwLock<Option<Options>> = RwLock::new(None);
+
+/// Whether to print codes as plain text alternative, for screen reader users.
+///
+/// Block graphics read out as hundreds of meaningless characters, so the printing
+/// functions can print the payload, or a description given through
+/// [`Options::alt_text_description`], as plain text line instead of or alongside them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AltText {
+    /// Only print the code.
+    #[default]
+    Off,
+
+    /// Print the alt text above the code.
+    Alongside,
+
+    /// Only print the alt text, instead of the code.
+    Only,
+}
+
+/// Options for generating and rendering codes with the top-level functions, such as
+/// [`print_qr`](fn@crate::print_qr).
+///
+/// Pass them to [`print_qr_with`](crate::print_qr_with) and
+/// [`generate_qr_string_with`](crate::generate_qr_string_with) for a single code, or set
+/// them as [default](set_default_options) for the whole process.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::render::{Density, Theme};
+/// use qr2term::Options;
+///
+/// let options = Options::default()
+///     .density(Density::Braille)
+///     .theme(Theme::named("nord").unwrap());
+/// qr2term::print_qr_with("https://rust-lang.org/", &options).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    ec_level: EcLevel,
+    version: Option<i16>,
+    quiet_zone: usize,
+    density: Density,
+    colors: bool,
+    theme: Theme,
+    invert: bool,
+    strict: bool,
+    scale: usize,
+    even_height: bool,
+    alt_text: AltText,
+    alt_text_description: Option<String>,
+    fingerprint: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            ec_level: EcLevel::M,
+            version: None,
+            quiet_zone: QUIET_ZONE_WIDTH,
+            density: Density::default(),
+            colors: true,
+            theme: Theme::default(),
+            invert: false,
+            strict: false,
+            scale: 1,
+            even_height: false,
+            alt_text: AltText::Off,
+            alt_text_description: None,
+            fingerprint: false,
+        }
+    }
+}
+
+impl Options {
+    /// Set the error correction level, defaults to [`EcLevel::M`].
+    pub fn ec_level(mut self, ec_level: EcLevel) -> Self {
+        self.ec_level = ec_level;
+        self
+    }
+
+    /// Set the QR code version from 1 to 40, picked automatically by default.
+    ///
+    /// `None` picks the smallest version the data fits in.
+    pub fn version(mut self, version: Option<i16>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Set the width of the light border around the code in pixels, defaults to
+    /// [`QUIET_ZONE_WIDTH`].
+    pub fn quiet_zone(mut self, quiet_zone: usize) -> Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Set how pixels are mapped onto terminal characters, defaults to half blocks.
+    pub fn density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Set whether to use ANSI colors, enabled by default.
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Set the colors to draw pixels with, defaults to [`Theme::CLASSIC`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set whether to swap dark and light pixels.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Set whether to refuse printing with colors that are hard to scan, see
+    /// [`Renderer::strict`].
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set how many times each pixel is repeated horizontally and vertically, defaults
+    /// to 1.
+    ///
+    /// Printing fails with [`Error::InvalidOption`](crate::Error::InvalidOption) if `scale`
+    /// is zero.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set whether to pad codes with an odd pixel height with a light pixel row, see
+    /// [`Renderer::even_height`].
+    pub fn even_height(mut self, even_height: bool) -> Self {
+        self.even_height = even_height;
+        self
+    }
+
+    /// Set whether the printing functions print a plain text alternative of codes, off by
+    /// default.
+    pub fn alt_text(mut self, alt_text: AltText) -> Self {
+        self.alt_text = alt_text;
+        self
+    }
+
+    /// Set the plain text alternative to print, instead of the payload.
+    ///
+    /// Useful for payloads that don't read well, such as Wi-Fi credentials. Only printed
+    /// if enabled through [`alt_text`](Self::alt_text).
+    pub fn alt_text_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.alt_text_description = Some(description.into());
+        self
+    }
+
+    /// Write the plain text alternative of `data` to `target`, if enabled.
+    ///
+    /// Control characters are escaped, so payloads can't move the cursor or send escape
+    /// sequences to the terminal. Returns whether the code should still be printed.
+    pub(crate) fn write_alt_text<W: Write>(&self, data: &[u8], target: &mut W) -> IoResult<bool> {
+        if self.alt_text == AltText::Off {
+            return Ok(true);
+        }
+        let text = match &self.alt_text_description {
+            Some(description) => escape_controls(description),
+            None => escape_controls(&String::from_utf8_lossy(data)),
+        };
+        writeln!(target, "QR code: {}", text)?;
+        Ok(self.alt_text == AltText::Alongside)
+    }
+
+    /// Set whether the printing functions print a [fingerprint](crate::fingerprint) of
+    /// the payload under codes, disabled by default.
+    ///
+    /// Lets users verify that what their phone scanned matches what was printed, such as
+    /// in key exchange workflows.
+    pub fn fingerprint(mut self, fingerprint: bool) -> Self {
+        self.fingerprint = fingerprint;
+        self
+    }
+
+    /// Get the fingerprint line to print under the code for `data`, if enabled.
+    pub(crate) fn fingerprint_line(&self, data: &[u8]) -> Option<String> {
+        if self.fingerprint {
+            Some(format!("SHA-256: {}", crate::fingerprint(data)))
+        } else {
+            None
+        }
+    }
+
+    /// Write the fingerprint line of `data` to `target` if enabled, centered within
+    /// `width` columns.
+    pub(crate) fn write_fingerprint<W: Write>(
+        &self,
+        data: &[u8],
+        width: usize,
+        target: &mut W,
+    ) -> IoResult<()> {
+        if let Some(line) = self.fingerprint_line(data) {
+            let indent = width.saturating_sub(line.len()) / 2;
+            writeln!(target, "{:indent$}{}", "", line, indent = indent)?;
+        }
+        Ok(())
+    }
+
+    /// How many columns and rows in the terminal it takes to print `qr` with these
+    /// options, including the quiet zone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use qr2term::qr::Qr;
+    /// use qr2term::Options;
+    ///
+    /// let qr = Qr::from("https://rust-lang.org/").unwrap();
+    /// assert_eq!(Options::default().quiet_zone(4).dimensions(&qr), (33, 17));
+    /// ```
+    pub fn dimensions(&self, qr: &Qr) -> (usize, usize) {
+        self.renderer().dimensions(&self.matrix(qr))
+    }
+
+    /// Generate the QR code for `data`, using the generation options.
+    pub(crate) fn qr<D: AsRef<[u8]>>(&self, data: D) -> Result<Qr, QrError> {
+        match self.version {
+            Some(version) => Qr::with_version(data, version, self.ec_level),
+            None => Qr::with_error_correction_level(data, self.ec_level),
+        }
+    }
+
+    /// Surround the pixel matrix of `qr` with the configured quiet zone.
+    pub(crate) fn matrix(&self, qr: &Qr) -> Matrix<Color> {
+        self.surround(qr.to_matrix())
+    }
+
+    /// Surround the given pixel matrix with the configured quiet zone.
+    pub(crate) fn surround(&self, mut matrix: Matrix<Color>) -> Matrix<Color> {
+        matrix.surround(self.quiet_zone, render::QrLight);
+        matrix
+    }
+
+    /// Get a renderer using the rendering options.
+    pub(crate) fn renderer(&self) -> Renderer {
+        Renderer::default()
+            .density(self.density)
+            .colors(self.colors)
+            .theme(self.theme)
+            .invert(self.invert)
+            .strict(self.strict)
+            .scale(self.scale)
+            .even_height(self.even_height)
+    }
+}
+
+/// Escape the control characters in `text`, such as `\u{1b}` for escape.
+fn escape_controls(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_control() {
+                c.escape_default().to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Set the options used by the top-level functions, such as [`print_qr`](fn@crate::print_qr)
+/// and [`generate_qr_string`](crate::generate_qr_string).
+///
+/// Meant to be called once at startup, to configure rendering for the whole process
+/// instead of at every call site. Environment variables set by the user still take
+/// precedence.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::render::Density;
+/// use qr2term::Options;
+///
+/// qr2term::set_default_options(Options::default().density(Density::Full));
+/// qr2term::print_qr("https://rust-lang.org/").unwrap();
+/// ```
+pub fn set_default_options(options: Options) {
+    *DEFAULT_OPTIONS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(options);
+}
+
+/// Get the options used by the top-level functions.
+///
+/// These are the options given to [`set_default_options`], or [`Options::default`] if
+/// never set.
+pub fn default_options() -> Options {
+    DEFAULT_OPTIONS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_with_options() {
+        let options = Options::default().version(Some(3)).quiet_zone(1);
+        let qr = options.qr("a").unwrap();
+        assert_eq!(qr.version(), 3);
+        assert_eq!(options.matrix(&qr).size(), 17 + 3 * 4 + 2);
+        assert_eq!(
+            Options::default().version(Some(41)).qr("a").err(),
+            Some(QrError::InvalidVersion)
+        );
+
+        let matrix = Options::default().matrix(&Qr::from("a").unwrap());
+        let mut expected = Vec::new();
+        Renderer::default().render(&matrix, &mut expected).unwrap();
+        let mut rendered = Vec::new();
+        Options::default()
+            .renderer()
+            .render(&matrix, &mut rendered)
+            .unwrap();
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn alt_text() {
+        let mut out = Vec::new();
+        assert!(Options::default().write_alt_text(b"a", &mut out).unwrap());
+        assert!(out.is_empty());
+
+        let options = Options::default().alt_text(AltText::Alongside);
+        assert!(options.write_alt_text(b"https://a", &mut out).unwrap());
+        let options = options
+            .alt_text(AltText::Only)
+            .alt_text_description("Wi-Fi");
+        assert!(!options.write_alt_text(b"WIFI:S:a;;", &mut out).unwrap());
+        assert_eq!(out, b"QR code: https://a\nQR code: Wi-Fi\n");
+    }
+
+    #[test]
+    fn alt_text_controls() {
+        let mut out = Vec::new();
+        Options::default()
+            .alt_text(AltText::Only)
+            .write_alt_text("a\x1b[2Jb\r\x07é".as_bytes(), &mut out)
+            .unwrap();
+        assert_eq!(out, "QR code: a\\u{1b}[2Jb\\r\\u{7}é\n".as_bytes());
+    }
+
+    #[test]
+    fn fingerprint() {
+        let mut out = Vec::new();
+        Options::default()
+            .write_fingerprint(b"abc", 29, &mut out)
+            .unwrap();
+        assert!(out.is_empty());
+        Options::default()
+            .fingerprint(true)
+            .write_fingerprint(b"abc", 29, &mut out)
+            .unwrap();
+        assert_eq!(out, b"      SHA-256: ba7816bf\n");
+    }
+}
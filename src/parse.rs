@@ -0,0 +1,324 @@
+//! Parse rendered terminal output back into a barcode matrix.
+//!
+//! Understands the output of a [`Renderer`](crate::render::Renderer) for every
+//! [`Density`], with or without colors, for round-trip tests and for inspecting codes
+//! captured from terminal logs. Scaled output parses into a scaled matrix, and inverted
+//! output can't be told apart from regular output without colors.
+
+use std::{error, fmt};
+
+use crate::matrix::Matrix;
+use crate::render::{Color, Density, QrDark, QrLight, TermColor};
+
+/// Error returned when text can't be parsed as rendered barcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The text doesn't contain any barcode lines.
+    Empty,
+
+    /// A line is wider or narrower than the first line.
+    Ragged {
+        /// Line number, starting at 1.
+        line: usize,
+    },
+
+    /// A character isn't used by the renderer for the given density.
+    InvalidGlyph {
+        /// Line number, starting at 1.
+        line: usize,
+
+        /// The offending character.
+        glyph: char,
+    },
+
+    /// The number of lines doesn't match the width of a square barcode.
+    NotSquare,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no barcode found in text"),
+            ParseError::Ragged { line } => {
+                write!(f, "line {} has a different width than the first line", line)
+            }
+            ParseError::InvalidGlyph { line, glyph } => {
+                write!(f, "line {} contains unexpected character {:?}", line, glyph)
+            }
+            ParseError::NotSquare => write!(f, "barcode is not square"),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Pixel as read from the text, before knowing which color is dark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ink {
+    /// Plain glyph, either drawing a dark pixel or not.
+    Plain(bool),
+
+    /// Pixel drawn in the given color.
+    Color(TermColor),
+}
+
+/// Character cell of the text, with the colors it was drawn with.
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    glyph: char,
+    fg: Option<TermColor>,
+    bg: Option<TermColor>,
+}
+
+impl Cell {
+    /// How to draw a glyph pixel, being part of the glyph or not.
+    fn ink(&self, glyph: bool) -> Ink {
+        match (glyph, self.fg, self.bg) {
+            (true, Some(fg), _) => Ink::Color(fg),
+            (false, _, Some(bg)) => Ink::Color(bg),
+            _ => Ink::Plain(glyph),
+        }
+    }
+}
+
+/// Parse text rendered with the given density back into a barcode matrix.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::matrix::Matrix;
+/// use qr2term::render::{Density, QrDark, QrLight};
+///
+/// let matrix = qr2term::parse::parse("▀▄\n", Density::HalfBlock).unwrap();
+/// assert_eq!(matrix.pixels(), [QrDark, QrLight, QrLight, QrDark]);
+/// ```
+pub fn parse(text: &str, density: Density) -> Result<Matrix<Color>, ParseError> {
+    let lines = cells(text);
+    let width = lines.first().map(Vec::len).ok_or(ParseError::Empty)?;
+    if let Some(line) = lines.iter().position(|line| line.len() != width) {
+        return Err(ParseError::Ragged { line: line + 1 });
+    }
+    let invalid = |line: usize, cell: &Cell| ParseError::InvalidGlyph {
+        line: line + 1,
+        glyph: cell.glyph,
+    };
+
+    // Read pixels as rows of ink, possibly including padding beyond the barcode size
+    let (size, rows) = match density {
+        Density::HalfBlock => {
+            let mut rows = Vec::with_capacity(lines.len() * 2);
+            for (line, cells) in lines.iter().enumerate() {
+                let (mut top, mut bottom) = (Vec::new(), Vec::new());
+                for cell in cells {
+                    let (upper, lower) = match cell.glyph {
+                        '█' => (cell.ink(true), cell.ink(true)),
+                        '▀' => (cell.ink(true), cell.ink(false)),
+                        '▄' => (cell.ink(false), cell.ink(true)),
+                        ' ' => (cell.ink(false), cell.ink(false)),
+                        _ => return Err(invalid(line, cell)),
+                    };
+                    top.push(upper);
+                    bottom.push(lower);
+                }
+                rows.push(top);
+                rows.push(bottom);
+            }
+            (width, rows)
+        }
+        Density::Full => {
+            let mut rows = Vec::with_capacity(lines.len());
+            for (line, cells) in lines.iter().enumerate() {
+                let mut row = Vec::new();
+                for pair in cells.chunks(2) {
+                    let ink = match pair.iter().map(|cell| cell.glyph).collect::<String>() {
+                        glyphs if glyphs == "##" => pair[0].ink(true),
+                        glyphs if glyphs == "  " => pair[0].ink(false),
+                        _ => return Err(invalid(line, &pair[0])),
+                    };
+                    row.push(ink);
+                }
+                rows.push(row);
+            }
+            (width / 2, rows)
+        }
+        Density::Braille => {
+            // Braille dot bits, indexed by row and column within the character
+            const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+            let mut rows = Vec::with_capacity(lines.len() * 4);
+            for (line, cells) in lines.iter().enumerate() {
+                let mut block = vec![Vec::new(); 4];
+                for cell in cells {
+                    let bits = match cell.glyph as u32 {
+                        bits @ 0x2800..=0x28FF => bits - 0x2800,
+                        _ => return Err(invalid(line, cell)),
+                    };
+                    for (row, dots) in block.iter_mut().zip(DOTS.iter()) {
+                        row.extend(dots.iter().map(|dot| cell.ink(bits & dot != 0)));
+                    }
+                }
+                rows.extend(block);
+            }
+
+            // Padding can't be told apart from light pixels, but barcodes are odd sized
+            let size = [width * 2 - 1, width * 2]
+                .into_iter()
+                .find(|size| (size + 3) / 4 == lines.len())
+                .unwrap_or(width * 2);
+            (size, rows)
+        }
+    };
+    if rows.len() < size || rows.len() - size >= rows.len() / lines.len() {
+        return Err(ParseError::NotSquare);
+    }
+
+    // Classify colors by luminance, the darker ones being dark pixels
+    let luminance = |color: TermColor| {
+        let (r, g, b) = color.rgb();
+        r as u32 * 299 + g as u32 * 587 + b as u32 * 114
+    };
+    let colors = rows.iter().flatten().filter_map(|ink| match ink {
+        Ink::Color(color) => Some(luminance(*color)),
+        Ink::Plain(_) => None,
+    });
+    let (min, max) = colors.fold((u32::MAX, 0), |(min, max), l| (min.min(l), max.max(l)));
+    let threshold = (min / 2 + max / 2).max(min.saturating_add(1));
+
+    let pixels = rows
+        .iter()
+        .take(size)
+        .flat_map(|row| row.iter().take(size))
+        .map(|ink| match ink {
+            Ink::Plain(true) => QrDark,
+            Ink::Plain(false) => QrLight,
+            Ink::Color(color) if luminance(*color) < threshold => QrDark,
+            Ink::Color(_) => QrLight,
+        })
+        .collect();
+    Ok(Matrix::new(pixels))
+}
+
+/// Split text into lines of character cells, resolving ANSI color escape sequences.
+///
+/// Empty lines are skipped.
+fn cells(text: &str) -> Vec<Vec<Cell>> {
+    let (mut fg, mut bg) = (None, None);
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(glyph) = chars.next() {
+        match glyph {
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+                let mut sequence = String::new();
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        if c == 'm' {
+                            apply_sgr(&sequence, &mut fg, &mut bg);
+                        }
+                        break;
+                    }
+                    sequence.push(c);
+                }
+            }
+            '\n' => {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+            }
+            '\r' => {}
+            glyph => line.push(Cell { glyph, fg, bg }),
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Apply the parameters of an SGR escape sequence to the current colors.
+fn apply_sgr(params: &str, fg: &mut Option<TermColor>, bg: &mut Option<TermColor>) {
+    let mut params = params
+        .split(';')
+        .map(|param| param.parse::<u8>().unwrap_or(0));
+    while let Some(param) = params.next() {
+        match param {
+            0 => (*fg, *bg) = (None, None),
+            39 => *fg = None,
+            49 => *bg = None,
+            38 | 48 => {
+                let color = match params.next() {
+                    Some(5) => params.next().map(TermColor::Ansi),
+                    Some(2) => match (params.next(), params.next(), params.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(TermColor::Rgb(r, g, b)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if param == 38 {
+                    *fg = color;
+                } else {
+                    *bg = color;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qr::Qr;
+    use crate::render::{Renderer, Theme};
+    use crate::QUIET_ZONE_WIDTH;
+
+    #[test]
+    fn round_trip() {
+        let mut matrix = Qr::from("https://rust-lang.org/").unwrap().to_matrix();
+        matrix.surround(QUIET_ZONE_WIDTH, QrLight);
+
+        for density in [Density::HalfBlock, Density::Full, Density::Braille] {
+            for colors in [false, true] {
+                for theme in [Theme::CLASSIC, Theme::named("gruvbox").unwrap()] {
+                    let renderer = Renderer::default()
+                        .density(density)
+                        .colors(colors)
+                        .theme(theme);
+                    let mut out = Vec::new();
+                    renderer.render(&matrix, &mut out).unwrap();
+                    let text = String::from_utf8(out).unwrap();
+                    let parsed = parse(&text, density).unwrap();
+                    assert_eq!(parsed.pixels(), matrix.pixels(), "{:?}", renderer);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_text() {
+        assert_eq!(
+            parse("\n\n", Density::HalfBlock).err(),
+            Some(ParseError::Empty)
+        );
+        assert_eq!(
+            parse("▀▄\n▀\n", Density::HalfBlock).err(),
+            Some(ParseError::Ragged { line: 2 })
+        );
+        assert_eq!(
+            parse("▀x\n", Density::HalfBlock).err(),
+            Some(ParseError::InvalidGlyph {
+                line: 1,
+                glyph: 'x'
+            })
+        );
+        assert_eq!(
+            parse("▀▄▀\n", Density::HalfBlock).err(),
+            Some(ParseError::NotSquare)
+        );
+        assert_eq!(
+            parse("####\n", Density::Full).err(),
+            Some(ParseError::NotSquare)
+        );
+    }
+}
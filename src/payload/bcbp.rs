@@ -0,0 +1,282 @@
+//! IATA bar coded boarding pass payload.
+
+use super::PayloadError;
+
+/// Flight leg of a boarding pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leg {
+    /// Booking reference (PNR code) of the operating carrier, at most 7 characters.
+    pub pnr: String,
+
+    /// IATA code of the departure airport, such as `AMS`.
+    pub from: String,
+
+    /// IATA code of the arrival airport, such as `JFK`.
+    pub to: String,
+
+    /// IATA designator of the operating carrier, such as `KL`.
+    pub carrier: String,
+
+    /// Flight number, up to 4 digits with an optional letter suffix, such as `641` or `12A`.
+    pub flight_number: String,
+
+    /// Day of the year of the flight, from 1 to 366.
+    pub day_of_year: u16,
+
+    /// Compartment code, such as `Y` for economy.
+    pub compartment: char,
+
+    /// Seat, up to 3 digits followed by a letter, such as `12A`.
+    pub seat: String,
+
+    /// Check-in sequence number, up to 9999.
+    pub sequence: u16,
+
+    /// Passenger status code, such as `1` for checked in.
+    pub status: char,
+}
+
+/// Boarding pass, as used for airport security and boarding gates.
+///
+/// Produces the mandatory items of the IATA Bar Coded Boarding Pass (BCBP) `M` format, with
+/// all fields padded to their fixed size. Up to four legs are supported. Use
+/// [`build`](Self::build) to validate all fields and obtain the payload.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{BoardingPass, Leg};
+///
+/// let pass = BoardingPass::new("DESMARAIS/LUC").leg(Leg {
+///     pnr: "ABC123".into(),
+///     from: "YUL".into(),
+///     to: "FRA".into(),
+///     carrier: "AC".into(),
+///     flight_number: "834".into(),
+///     day_of_year: 226,
+///     compartment: 'F',
+///     seat: "1A".into(),
+///     sequence: 25,
+///     status: '1',
+/// });
+/// assert_eq!(
+///     pass.build().unwrap(),
+///     "M1DESMARAIS/LUC       EABC123 YULFRAAC 0834 226F001A0025 100",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardingPass {
+    name: String,
+    electronic_ticket: bool,
+    legs: Vec<Leg>,
+}
+
+impl BoardingPass {
+    /// Construct a boarding pass for the passenger with the given `name`, formatted as
+    /// `SURNAME/GIVEN NAME`.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            electronic_ticket: true,
+            legs: Vec::new(),
+        }
+    }
+
+    /// Set whether the ticket is an electronic ticket, defaults to `true`.
+    pub fn electronic_ticket(mut self, electronic_ticket: bool) -> Self {
+        self.electronic_ticket = electronic_ticket;
+        self
+    }
+
+    /// Add a flight leg.
+    pub fn leg(mut self, leg: Leg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Validate all fields, and build the payload.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        if self.legs.is_empty() {
+            return Err(PayloadError::Missing { field: "leg" });
+        }
+        if self.legs.len() > 4 {
+            return Err(PayloadError::Invalid {
+                field: "leg",
+                reason: "at most 4 legs can be encoded",
+            });
+        }
+
+        let mut out = format!("M{}", self.legs.len());
+        push_padded(&mut out, &self.name, 20, "name")?;
+        out.push(if self.electronic_ticket { 'E' } else { ' ' });
+
+        for leg in &self.legs {
+            push_padded(&mut out, &leg.pnr, 7, "pnr")?;
+            push_airport(&mut out, &leg.from, "from")?;
+            push_airport(&mut out, &leg.to, "to")?;
+            if !(2..=3).contains(&leg.carrier.len()) {
+                return Err(PayloadError::Invalid {
+                    field: "carrier",
+                    reason: "carrier designator must be 2 or 3 characters",
+                });
+            }
+            push_padded(&mut out, &leg.carrier, 3, "carrier")?;
+            push_numbered(&mut out, &leg.flight_number, 4, "flight_number")?;
+            if !(1..=366).contains(&leg.day_of_year) {
+                return Err(PayloadError::Invalid {
+                    field: "day_of_year",
+                    reason: "day of year must be between 1 and 366",
+                });
+            }
+            out.push_str(&format!("{:03}", leg.day_of_year));
+            push_padded(&mut out, &leg.compartment.to_string(), 1, "compartment")?;
+            let mut seat = String::new();
+            push_numbered(&mut seat, &leg.seat, 3, "seat")?;
+            if seat.ends_with(' ') {
+                return Err(PayloadError::Invalid {
+                    field: "seat",
+                    reason: "seat must end with a letter",
+                });
+            }
+            out.push_str(&seat);
+            if leg.sequence > 9999 {
+                return Err(PayloadError::Invalid {
+                    field: "sequence",
+                    reason: "sequence number must not exceed 9999",
+                });
+            }
+            out.push_str(&format!("{:04} ", leg.sequence));
+            push_padded(&mut out, &leg.status.to_string(), 1, "status")?;
+
+            // Size of the conditional items, which are not supported
+            out.push_str("00");
+        }
+
+        Ok(out)
+    }
+}
+
+/// Push a field, padded with spaces to its fixed `size`.
+fn push_padded(
+    out: &mut String,
+    value: &str,
+    size: usize,
+    field: &'static str,
+) -> Result<(), PayloadError> {
+    if let Some(character) = value
+        .chars()
+        .find(|c| !c.is_ascii() || c.is_ascii_control())
+    {
+        return Err(PayloadError::InvalidCharacter { field, character });
+    }
+    if value.len() > size {
+        return Err(PayloadError::TooLong { field, max: size });
+    }
+    out.push_str(&format!(
+        "{:<size$}",
+        value.to_ascii_uppercase(),
+        size = size
+    ));
+    Ok(())
+}
+
+/// Push a three letter airport code.
+fn push_airport(out: &mut String, value: &str, field: &'static str) -> Result<(), PayloadError> {
+    if value.len() != 3 || !value.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(PayloadError::Invalid {
+            field,
+            reason: "airport must be a three letter IATA code",
+        });
+    }
+    out.push_str(&value.to_ascii_uppercase());
+    Ok(())
+}
+
+/// Push a number zero padded to `digits`, followed by an optional letter or a space.
+fn push_numbered(
+    out: &mut String,
+    value: &str,
+    digits: usize,
+    field: &'static str,
+) -> Result<(), PayloadError> {
+    let (number, suffix) = match value.chars().last() {
+        Some(c) if c.is_ascii_alphabetic() => (&value[..value.len() - 1], c.to_ascii_uppercase()),
+        _ => (value, ' '),
+    };
+    if number.is_empty() || number.len() > digits || !number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PayloadError::Invalid {
+            field,
+            reason: "value must be a number with an optional letter suffix",
+        });
+    }
+    out.push_str(&format!("{:0>digits$}{}", number, suffix, digits = digits));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg() -> Leg {
+        Leg {
+            pnr: "xyz789".into(),
+            from: "ams".into(),
+            to: "jfk".into(),
+            carrier: "KLM".into(),
+            flight_number: "12a".into(),
+            day_of_year: 5,
+            compartment: 'y',
+            seat: "33C".into(),
+            sequence: 1234,
+            status: '1',
+        }
+    }
+
+    #[test]
+    fn bcbp_multiple_legs() {
+        let pass = BoardingPass::new("Doe/Jane")
+            .electronic_ticket(false)
+            .leg(leg())
+            .leg(leg());
+        let leg = "XYZ789 AMSJFKKLM0012A005Y033C1234 100";
+        assert_eq!(
+            pass.build().unwrap(),
+            format!("M2DOE/JANE             {}{}", leg, leg)
+        );
+        assert_eq!(leg.len(), 37);
+    }
+
+    #[test]
+    fn bcbp_invalid() {
+        let pass = |leg| BoardingPass::new("DOE/JANE").leg(leg);
+        assert!(BoardingPass::new("DOE/JANE").build().is_err());
+        assert!(BoardingPass::new("A".repeat(21))
+            .leg(leg())
+            .build()
+            .is_err());
+        assert!(pass(Leg {
+            from: "AM".into(),
+            ..leg()
+        })
+        .build()
+        .is_err());
+        assert!(pass(Leg {
+            flight_number: "12345".into(),
+            ..leg()
+        })
+        .build()
+        .is_err());
+        assert!(pass(Leg {
+            seat: "12".into(),
+            ..leg()
+        })
+        .build()
+        .is_err());
+        assert!(pass(Leg {
+            day_of_year: 367,
+            ..leg()
+        })
+        .build()
+        .is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Binary data payload, encoded as text.
+
+use std::fmt;
+
+use super::{base64, PayloadError};
+use crate::qr::Mode;
+
+/// Base45 alphabet, as specified in RFC 9285.
+///
+/// This matches the QR alphanumeric mode character set.
+const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Text encoding for binary data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// Base45, encoded in the QR alphanumeric mode.
+    ///
+    /// Results in the smallest code for binary data that must survive as text, such as
+    /// EU digital COVID certificates.
+    #[default]
+    Base45,
+
+    /// Base64 with the standard alphabet, encoded in the QR byte mode.
+    ///
+    /// Results in a larger code, but is understood by more decoders.
+    Base64,
+}
+
+/// Arbitrary binary data, such as keys or CBOR, wrapped in a text encoding.
+///
+/// Many scanners only handle text, so binary data is encoded as Base45 or Base64 first.
+/// The matching QR encoding mode is used when printing through
+/// [`print_qr_payload`](crate::print_qr_payload).
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{Binary, BinaryEncoding};
+///
+/// let binary = Binary::new(b"Hello!!".to_vec());
+/// assert_eq!(binary.to_string(), "%69 VD92EX0");
+///
+/// let binary = binary.encoding(BinaryEncoding::Base64);
+/// assert_eq!(binary.to_string(), "SGVsbG8hIQ==");
+///
+/// qr2term::print_qr_payload(&binary).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binary {
+    data: Vec<u8>,
+    encoding: BinaryEncoding,
+}
+
+impl Binary {
+    /// Construct from the given binary `data`, using Base45 by default.
+    pub fn new<D: Into<Vec<u8>>>(data: D) -> Self {
+        Self {
+            data: data.into(),
+            encoding: BinaryEncoding::default(),
+        }
+    }
+
+    /// Set the text encoding.
+    pub fn encoding(mut self, encoding: BinaryEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Decode binary data from the given text in the given `encoding`.
+    pub fn decode(text: &str, encoding: BinaryEncoding) -> Result<Self, PayloadError> {
+        let data = match encoding {
+            BinaryEncoding::Base45 => base45_decode(text),
+            BinaryEncoding::Base64 => base64::decode(text),
+        };
+        data.map(|data| Self { data, encoding })
+            .ok_or(PayloadError::Invalid {
+                field: "data",
+                reason: "data is not correctly encoded",
+            })
+    }
+
+    /// Get the binary data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The QR encoding mode matching the text encoding.
+    pub(super) fn mode(&self) -> Mode {
+        match self.encoding {
+            BinaryEncoding::Base45 => Mode::Alphanumeric,
+            BinaryEncoding::Base64 => Mode::Byte,
+        }
+    }
+}
+
+impl fmt::Display for Binary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.encoding {
+            BinaryEncoding::Base45 => write!(f, "{}", base45_encode(&self.data)),
+            BinaryEncoding::Base64 => write!(f, "{}", base64::encode(&self.data)),
+        }
+    }
+}
+
+/// Encode bytes as Base45.
+fn base45_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() + 1) / 2 * 3);
+    for chunk in data.chunks(2) {
+        let (mut value, digits) = match chunk {
+            [a, b] => ((*a as usize) << 8 | *b as usize, 3),
+            _ => (chunk[0] as usize, 2),
+        };
+        for _ in 0..digits {
+            out.push(BASE45_ALPHABET[value % 45] as char);
+            value /= 45;
+        }
+    }
+    out
+}
+
+/// Decode Base45.
+///
+/// Returns `None` if the input contains characters outside of the alphabet, or is malformed.
+fn base45_decode(data: &str) -> Option<Vec<u8>> {
+    let digits: Option<Vec<usize>> = data
+        .bytes()
+        .map(|c| BASE45_ALPHABET.iter().position(|&a| a == c))
+        .collect();
+    let mut out = Vec::with_capacity(data.len() / 3 * 2 + 1);
+    for chunk in digits?.chunks(3) {
+        let value = chunk.iter().rev().fold(0, |acc, d| acc * 45 + d);
+        match chunk.len() {
+            3 if value <= 0xFFFF => out.extend_from_slice(&(value as u16).to_be_bytes()),
+            2 if value <= 0xFF => out.push(value as u8),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::{QrPayload, ToQrPayload};
+
+    #[test]
+    fn base45_vectors() {
+        for (data, encoded) in [
+            (&b"AB"[..], "BB8"),
+            (b"Hello!!", "%69 VD92EX0"),
+            (b"base-45", "UJCLQE7W581"),
+            (b"ietf!", "QED8WEX0"),
+            (b"", ""),
+        ] {
+            assert_eq!(base45_encode(data), encoded);
+            assert_eq!(base45_decode(encoded).unwrap(), data);
+        }
+        assert_eq!(base45_decode("GGW"), None);
+        assert_eq!(base45_decode("a"), None);
+        assert_eq!(base45_decode("A"), None);
+    }
+
+    #[test]
+    fn binary_modes() {
+        let data: Vec<u8> = (0..=255).collect();
+        let base45 = Binary::new(data.clone()).to_qr_payload().unwrap();
+        assert_eq!(base45.mode, Some(Mode::Alphanumeric));
+        let base64 = Binary::new(data.clone())
+            .encoding(BinaryEncoding::Base64)
+            .to_qr_payload()
+            .unwrap();
+        assert_eq!(base64.mode, Some(Mode::Byte));
+
+        // Base45 in alphanumeric mode gives a smaller code than Base64 in byte mode
+        let size = |payload: QrPayload| payload.to_qr().unwrap().to_matrix().size();
+        assert!(size(base45) < size(base64));
+
+        let text = Binary::new(data.clone()).to_string();
+        let decoded = Binary::decode(&text, BinaryEncoding::Base45).unwrap();
+        assert_eq!(decoded.data(), &data[..]);
+    }
+}
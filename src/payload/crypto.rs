@@ -0,0 +1,173 @@
+//! BIP-21 style cryptocurrency payment URI payload.
+
+use std::fmt;
+
+use super::percent::{self, COMPONENT};
+use super::PayloadError;
+
+/// Most decimals an amount may have, as a `u64` has at most 20 digits.
+const MAX_DECIMALS: u32 = 19;
+
+/// Cryptocurrency payment request, for paying from a wallet app by scanning.
+///
+/// Produces a BIP-21 style URI, such as `bitcoin:<address>?amount=...`. Other currencies
+/// using the same URI structure are supported through [`new`](Self::new) with a custom
+/// scheme.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::CryptoUri;
+///
+/// let uri = CryptoUri::bitcoin("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq")
+///     .amount(150_000, 8)
+///     .unwrap()
+///     .label("Luke Jr");
+/// assert_eq!(
+///     uri.to_string(),
+///     "bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=0.0015&label=Luke%20Jr",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoUri {
+    scheme: String,
+    address: String,
+    amount: Option<(u64, u32)>,
+    label: Option<String>,
+    message: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl CryptoUri {
+    /// Construct a payment request using the given URI `scheme`, such as `litecoin`.
+    pub fn new<S: Into<String>, A: Into<String>>(scheme: S, address: A) -> Self {
+        Self {
+            scheme: scheme.into().to_ascii_lowercase(),
+            address: address.into(),
+            amount: None,
+            label: None,
+            message: None,
+            params: Vec::new(),
+        }
+    }
+
+    /// Construct a Bitcoin payment request to the given `address`.
+    pub fn bitcoin<A: Into<String>>(address: A) -> Self {
+        Self::new("bitcoin", address)
+    }
+
+    /// Set the amount, as a number of the smallest `units` with the given number of `decimals`.
+    ///
+    /// For example, satoshis use 8 decimals to get an amount in bitcoin. Returns an error
+    /// if there are more than 19 decimals.
+    pub fn amount(mut self, units: u64, decimals: u32) -> Result<Self, PayloadError> {
+        if decimals > MAX_DECIMALS {
+            return Err(PayloadError::Invalid {
+                field: "amount",
+                reason: "amount must have at most 19 decimals",
+            });
+        }
+        self.amount = Some((units, decimals));
+        Ok(self)
+    }
+
+    /// Set the label for the recipient.
+    pub fn label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the message describing the payment.
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Add a custom parameter, such as `lightning` or `req-...` parameters.
+    pub fn param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl fmt::Display for CryptoUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            self.scheme,
+            percent::encode(&self.address, COMPONENT)
+        )?;
+
+        let mut params = Vec::new();
+        if let Some((units, decimals)) = self.amount {
+            params.push(format!("amount={}", format_amount(units, decimals)));
+        }
+        if let Some(label) = &self.label {
+            params.push(format!("label={}", percent::encode(label, COMPONENT)));
+        }
+        if let Some(message) = &self.message {
+            params.push(format!("message={}", percent::encode(message, COMPONENT)));
+        }
+        for (key, value) in &self.params {
+            params.push(format!(
+                "{}={}",
+                percent::encode(key, COMPONENT),
+                percent::encode(value, COMPONENT)
+            ));
+        }
+
+        if !params.is_empty() {
+            write!(f, "?{}", params.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Format an amount of `units` with `decimals` as decimal number, without trailing zeros.
+fn format_amount(units: u64, decimals: u32) -> String {
+    let digits = format!("{:0width$}", units, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    match fraction.trim_end_matches('0') {
+        "" => whole.to_string(),
+        fraction => format!("{}.{}", whole, fraction),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_formatting() {
+        assert_eq!(format_amount(0, 8), "0");
+        assert_eq!(format_amount(1, 8), "0.00000001");
+        assert_eq!(format_amount(100_000_000, 8), "1");
+        assert_eq!(format_amount(123_450_000, 8), "1.2345");
+        assert_eq!(format_amount(42, 0), "42");
+        assert_eq!(format_amount(u64::MAX, 19), "1.8446744073709551615");
+
+        assert!(CryptoUri::bitcoin("1abc").amount(1, 19).is_ok());
+        assert!(matches!(
+            CryptoUri::bitcoin("1abc").amount(1, u32::MAX),
+            Err(PayloadError::Invalid {
+                field: "amount",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn custom_scheme_and_params() {
+        let uri = CryptoUri::new("Litecoin", "LQ4i")
+            .amount(5, 1)
+            .unwrap()
+            .message("Pizza & beer")
+            .param("req-note", "a b");
+        assert_eq!(
+            uri.to_string(),
+            "litecoin:LQ4i?amount=0.5&message=Pizza%20%26%20beer&req-note=a%20b"
+        );
+        assert_eq!(CryptoUri::bitcoin("1abc").to_string(), "bitcoin:1abc");
+    }
+}
@@ -0,0 +1,124 @@
+//! Email `mailto:` URI payload.
+
+use std::fmt;
+
+use super::percent::{self, COMPONENT};
+
+/// Email message draft, for composing a message by scanning.
+///
+/// Produces a percent-encoded `mailto:` URI as specified in RFC 6068.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Email;
+///
+/// let email = Email::new("feedback@example.com").subject("Hello there");
+/// assert_eq!(email.to_string(), "mailto:feedback@example.com?subject=Hello%20there");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    to: Vec<String>,
+    cc: Vec<String>,
+    subject: Option<String>,
+    body: Option<String>,
+}
+
+impl Email {
+    /// Construct an email draft addressed to `to`.
+    pub fn new<S: Into<String>>(to: S) -> Self {
+        Self {
+            to: vec![to.into()],
+            cc: Vec::new(),
+            subject: None,
+            body: None,
+        }
+    }
+
+    /// Add another recipient.
+    pub fn to<S: Into<String>>(mut self, to: S) -> Self {
+        self.to.push(to.into());
+        self
+    }
+
+    /// Add a carbon copy recipient.
+    pub fn cc<S: Into<String>>(mut self, cc: S) -> Self {
+        self.cc.push(cc.into());
+        self
+    }
+
+    /// Set the message subject.
+    pub fn subject<S: Into<String>>(mut self, subject: S) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Set the message body.
+    ///
+    /// Line breaks are normalized to CRLF as required by the specification.
+    pub fn body<S: Into<String>>(mut self, body: S) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "mailto:{}", join_addresses(&self.to))?;
+
+        let mut fields = Vec::new();
+        if !self.cc.is_empty() {
+            fields.push(format!("cc={}", join_addresses(&self.cc)));
+        }
+        if let Some(subject) = &self.subject {
+            fields.push(format!("subject={}", percent::encode(subject, COMPONENT)));
+        }
+        if let Some(body) = &self.body {
+            let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+            fields.push(format!("body={}", percent::encode(&body, COMPONENT)));
+        }
+
+        if !fields.is_empty() {
+            write!(f, "?{}", fields.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Encode and join a list of addresses with commas.
+fn join_addresses(addresses: &[String]) -> String {
+    addresses
+        .iter()
+        .map(|address| percent::encode(address, b"@"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn email_all_fields() {
+        let email = Email::new("a@example.com")
+            .to("b@example.com")
+            .cc("c+d@example.com")
+            .subject("Q&A?")
+            .body("Line one\nLine two");
+        assert_eq!(
+            email.to_string(),
+            "mailto:a@example.com,b@example.com\
+            ?cc=c%2Bd@example.com\
+            &subject=Q%26A%3F\
+            &body=Line%20one%0D%0ALine%20two"
+        );
+    }
+
+    #[test]
+    fn email_only_address() {
+        assert_eq!(
+            Email::new("a@example.com").to_string(),
+            "mailto:a@example.com"
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! EMVCo merchant-presented QR code payload, including PIX.
+
+use super::PayloadError;
+
+/// Globally unique identifier of the PIX merchant account template.
+const PIX_GUI: &str = "br.gov.bcb.pix";
+
+/// EMVCo merchant-presented payment code, for paying a merchant from a banking app.
+///
+/// Produces the EMV QR Code Specification for Payment Systems (EMV QRCPS) format: a list of
+/// tag-length-value fields, closed by a CRC16 checksum. Use [`pix`](Self::pix) for Brazil's
+/// PIX BR Code, or [`new`](Self::new) with
+/// [`merchant_account`](Self::merchant_account) for other schemes. Use
+/// [`build`](Self::build) to validate all fields and obtain the payload.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::EmvMerchant;
+///
+/// let pix = EmvMerchant::pix("123e4567-e12b-12d1-a456-426655440000", "Fulano de Tal", "BRASILIA");
+/// assert_eq!(
+///     pix.build().unwrap(),
+///     "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000\
+///     5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmvMerchant {
+    name: String,
+    city: String,
+    country: String,
+    currency: String,
+    category_code: String,
+    dynamic: Option<bool>,
+    accounts: Vec<(u8, Vec<(u8, String)>)>,
+    amount: Option<String>,
+    postal_code: Option<String>,
+    additional_data: Vec<(u8, String)>,
+}
+
+impl EmvMerchant {
+    /// Construct a merchant payment code.
+    ///
+    /// `country` is a two letter ISO 3166-1 code, and `currency` a three digit numeric
+    /// ISO 4217 code, such as `978` for euro.
+    pub fn new<N, C, O, U>(name: N, city: C, country: O, currency: U) -> Self
+    where
+        N: Into<String>,
+        C: Into<String>,
+        O: Into<String>,
+        U: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            city: city.into(),
+            country: country.into(),
+            currency: currency.into(),
+            category_code: "0000".into(),
+            dynamic: None,
+            accounts: Vec::new(),
+            amount: None,
+            postal_code: None,
+            additional_data: Vec::new(),
+        }
+    }
+
+    /// Construct a static PIX BR Code paying to the given PIX `key`.
+    ///
+    /// The key may be a CPF/CNPJ number, phone number, email address or random key.
+    pub fn pix<K, N, C>(key: K, name: N, city: C) -> Self
+    where
+        K: Into<String>,
+        N: Into<String>,
+        C: Into<String>,
+    {
+        Self::new(name, city, "BR", "986")
+            .merchant_account(26, vec![(0, PIX_GUI.into()), (1, key.into())])
+            .reference_label("***")
+    }
+
+    /// Add a merchant account information template with the given `tag` from 26 to 51,
+    /// holding the given sub-fields.
+    pub fn merchant_account(mut self, tag: u8, fields: Vec<(u8, String)>) -> Self {
+        self.accounts.push((tag, fields));
+        self
+    }
+
+    /// Set the four digit ISO 18245 merchant category code, defaults to `0000`.
+    pub fn category_code<S: Into<String>>(mut self, category_code: S) -> Self {
+        self.category_code = category_code.into();
+        self
+    }
+
+    /// Set whether this code is static for many transactions, or dynamic for a single one.
+    ///
+    /// If not set, the point of initiation field is omitted, which implies a static code.
+    pub fn dynamic(mut self, dynamic: bool) -> Self {
+        self.dynamic = Some(dynamic);
+        self
+    }
+
+    /// Set the transaction amount, as a decimal number such as `12.50`.
+    pub fn amount<S: Into<String>>(mut self, amount: S) -> Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Set the postal code of the merchant.
+    pub fn postal_code<S: Into<String>>(mut self, postal_code: S) -> Self {
+        self.postal_code = Some(postal_code.into());
+        self
+    }
+
+    /// Set the reference label, such as a PIX transaction ID.
+    pub fn reference_label<S: Into<String>>(self, reference_label: S) -> Self {
+        self.additional_data(5, reference_label)
+    }
+
+    /// Set an additional data field with the given `tag`, replacing any previous value.
+    pub fn additional_data<S: Into<String>>(mut self, tag: u8, value: S) -> Self {
+        self.additional_data.retain(|(t, _)| *t != tag);
+        self.additional_data.push((tag, value.into()));
+        self.additional_data.sort_by_key(|(t, _)| *t);
+        self
+    }
+
+    /// Validate all fields, and build the payload.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        let mut out = String::new();
+        push_field(&mut out, 0, "01", "payload_format")?;
+        if let Some(dynamic) = self.dynamic {
+            let initiation = if dynamic { "12" } else { "11" };
+            push_field(&mut out, 1, initiation, "initiation")?;
+        }
+
+        if self.accounts.is_empty() {
+            return Err(PayloadError::Missing {
+                field: "merchant_account",
+            });
+        }
+        for (tag, fields) in &self.accounts {
+            if !(26..=51).contains(tag) {
+                return Err(PayloadError::Invalid {
+                    field: "merchant_account",
+                    reason: "merchant account tag must be between 26 and 51",
+                });
+            }
+            push_field(
+                &mut out,
+                *tag,
+                &template(fields, "merchant_account")?,
+                "merchant_account",
+            )?;
+        }
+
+        check_digits(&self.category_code, 4, "category_code")?;
+        push_field(&mut out, 52, &self.category_code, "category_code")?;
+        check_digits(&self.currency, 3, "currency")?;
+        push_field(&mut out, 53, &self.currency, "currency")?;
+        if let Some(amount) = &self.amount {
+            let valid = !amount.is_empty()
+                && amount.len() <= 13
+                && amount.chars().all(|c| c.is_ascii_digit() || c == '.')
+                && amount.matches('.').count() <= 1;
+            if !valid {
+                return Err(PayloadError::Invalid {
+                    field: "amount",
+                    reason: "amount must be a decimal number of at most 13 characters",
+                });
+            }
+            push_field(&mut out, 54, amount, "amount")?;
+        }
+        if self.country.len() != 2 || !self.country.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(PayloadError::Invalid {
+                field: "country",
+                reason: "country must be a two letter uppercase ISO 3166-1 code",
+            });
+        }
+        push_field(&mut out, 58, &self.country, "country")?;
+        check_length(&self.name, 25, "name")?;
+        push_field(&mut out, 59, &self.name, "name")?;
+        check_length(&self.city, 15, "city")?;
+        push_field(&mut out, 60, &self.city, "city")?;
+        if let Some(postal_code) = &self.postal_code {
+            push_field(&mut out, 61, postal_code, "postal_code")?;
+        }
+        if !self.additional_data.is_empty() {
+            let data = template(&self.additional_data, "additional_data")?;
+            push_field(&mut out, 62, &data, "additional_data")?;
+        }
+
+        // The checksum covers all fields, including the checksum tag and length itself
+        out.push_str("6304");
+        let crc = crc16(out.as_bytes());
+        out.push_str(&format!("{:04X}", crc));
+        Ok(out)
+    }
+}
+
+/// Validate and push a tag-length-value field.
+fn push_field(
+    out: &mut String,
+    tag: u8,
+    value: &str,
+    field: &'static str,
+) -> Result<(), PayloadError> {
+    if let Some(character) = value
+        .chars()
+        .find(|c| !c.is_ascii() || c.is_ascii_control())
+    {
+        return Err(PayloadError::InvalidCharacter { field, character });
+    }
+    if value.is_empty() {
+        return Err(PayloadError::Missing { field });
+    }
+    if value.len() > 99 {
+        return Err(PayloadError::TooLong { field, max: 99 });
+    }
+    out.push_str(&format!("{:02}{:02}{}", tag, value.len(), value));
+    Ok(())
+}
+
+/// Encode a list of tag-length-value sub-fields.
+fn template(fields: &[(u8, String)], field: &'static str) -> Result<String, PayloadError> {
+    let mut out = String::new();
+    for (tag, value) in fields {
+        if *tag > 99 {
+            return Err(PayloadError::Invalid {
+                field,
+                reason: "tag must be between 0 and 99",
+            });
+        }
+        push_field(&mut out, *tag, value, field)?;
+    }
+    Ok(out)
+}
+
+/// Check a field consists of exactly `len` digits.
+fn check_digits(value: &str, len: usize, field: &'static str) -> Result<(), PayloadError> {
+    if value.len() != len || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PayloadError::Invalid {
+            field,
+            reason: "value must be a numeric code of the required length",
+        });
+    }
+    Ok(())
+}
+
+/// Check a field is not longer than `max` characters.
+fn check_length(value: &str, max: usize, field: &'static str) -> Result<(), PayloadError> {
+    if value.chars().count() > max {
+        return Err(PayloadError::TooLong { field, max });
+    }
+    Ok(())
+}
+
+/// Compute the CRC16 checksum as specified by EMV QRCPS, being CRC-16/CCITT-FALSE.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn emv_generic() {
+        let code = EmvMerchant::new("Shop", "Berlin", "DE", "978")
+            .merchant_account(29, vec![(0, "com.example".into()), (1, "id-1".into())])
+            .category_code("5812")
+            .dynamic(true)
+            .amount("9.99")
+            .postal_code("10115")
+            .additional_data(1, "INV-1");
+        let payload = code.build().unwrap();
+        let (fields, crc) = payload.split_at(payload.len() - 4);
+        assert_eq!(
+            fields,
+            "000201010212\
+            29230011com.example0104id-1\
+            52045812530397854049.995802DE5904Shop6006Berlin\
+            61051011562090105INV-16304"
+        );
+        assert_eq!(crc, format!("{:04X}", crc16(fields.as_bytes())));
+    }
+
+    #[test]
+    fn emv_invalid() {
+        let pix = EmvMerchant::pix("key", "Name", "City");
+        assert!(pix.clone().build().is_ok());
+        assert!(EmvMerchant::new("Shop", "City", "BR", "986")
+            .build()
+            .is_err());
+        assert!(pix.clone().amount("1,00").build().is_err());
+        assert!(pix.clone().category_code("12").build().is_err());
+        assert!(EmvMerchant::pix("key", "a".repeat(26), "City")
+            .build()
+            .is_err());
+        assert!(EmvMerchant::pix("key", "Name", "São Paulo")
+            .build()
+            .is_err());
+        assert!(EmvMerchant::pix("k".repeat(100), "Name", "City")
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! EPC SEPA credit transfer payload.
+
+use super::PayloadError;
+
+/// Maximum size of the complete payload in bytes.
+const MAX_PAYLOAD_SIZE: usize = 331;
+
+/// Maximum amount in euro cents.
+const MAX_AMOUNT_CENTS: u64 = 99_999_999_999;
+
+/// SEPA credit transfer, for starting a bank transfer by scanning.
+///
+/// Produces the EPC069-12 format, also known as Girocode, understood by most European
+/// banking apps. Use [`build`](Self::build) to validate all fields and obtain the payload.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::EpcTransfer;
+///
+/// let transfer = EpcTransfer::new("Red Cross", "BE72 0000 0000 1616")
+///     .bic("BPOTBEB1")
+///     .amount_cents(1000)
+///     .text("Donation");
+/// qr2term::print_qr(transfer.build().unwrap()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpcTransfer {
+    name: String,
+    iban: String,
+    bic: Option<String>,
+    amount_cents: Option<u64>,
+    purpose: Option<String>,
+    reference: Option<String>,
+    text: Option<String>,
+    information: Option<String>,
+}
+
+impl EpcTransfer {
+    /// Construct a credit transfer to the beneficiary with the given `name` and `iban`.
+    pub fn new<N: Into<String>, I: Into<String>>(name: N, iban: I) -> Self {
+        Self {
+            name: name.into(),
+            iban: iban.into(),
+            bic: None,
+            amount_cents: None,
+            purpose: None,
+            reference: None,
+            text: None,
+            information: None,
+        }
+    }
+
+    /// Set the BIC of the beneficiary bank, optional within the EEA.
+    pub fn bic<S: Into<String>>(mut self, bic: S) -> Self {
+        self.bic = Some(bic.into());
+        self
+    }
+
+    /// Set the amount in euro cents.
+    pub fn amount_cents(mut self, amount_cents: u64) -> Self {
+        self.amount_cents = Some(amount_cents);
+        self
+    }
+
+    /// Set the four character purpose code, such as `CHAR` for charity.
+    pub fn purpose<S: Into<String>>(mut self, purpose: S) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Set the structured creditor reference, such as an RF reference.
+    ///
+    /// Can't be combined with [`text`](Self::text).
+    pub fn reference<S: Into<String>>(mut self, reference: S) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Set the unstructured remittance text.
+    ///
+    /// Can't be combined with [`reference`](Self::reference).
+    pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Set the information shown to the person making the transfer.
+    pub fn information<S: Into<String>>(mut self, information: S) -> Self {
+        self.information = Some(information.into());
+        self
+    }
+
+    /// Validate all fields, and build the payload.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        check_length("name", &self.name, 1, 70)?;
+        let iban = normalize_iban(&self.iban)?;
+        let bic = match &self.bic {
+            Some(bic) => normalize_bic(bic)?,
+            None => String::new(),
+        };
+        let amount = match self.amount_cents {
+            Some(0) | None => String::new(),
+            Some(cents) if cents > MAX_AMOUNT_CENTS => {
+                return Err(PayloadError::Invalid {
+                    field: "amount",
+                    reason: "amount must not exceed EUR 999999999.99",
+                })
+            }
+            Some(cents) => format!("EUR{}.{:02}", cents / 100, cents % 100),
+        };
+        let purpose = self.purpose.as_deref().unwrap_or_default();
+        if !purpose.is_empty()
+            && (purpose.len() != 4 || !purpose.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            return Err(PayloadError::Invalid {
+                field: "purpose",
+                reason: "purpose must be a four character code",
+            });
+        }
+        if self.reference.is_some() && self.text.is_some() {
+            return Err(PayloadError::Invalid {
+                field: "reference",
+                reason: "reference and text can't both be set",
+            });
+        }
+        let reference = self.reference.as_deref().unwrap_or_default();
+        check_length("reference", reference, 0, 35)?;
+        let text = self.text.as_deref().unwrap_or_default();
+        check_length("text", text, 0, 140)?;
+        let information = self.information.as_deref().unwrap_or_default();
+        check_length("information", information, 0, 70)?;
+
+        // Service tag, version 002 with optional BIC, UTF-8 and SEPA credit transfer
+        let fields = [
+            "BCD",
+            "002",
+            "1",
+            "SCT",
+            &bic,
+            &self.name,
+            &iban,
+            &amount,
+            purpose,
+            reference,
+            text,
+            information,
+        ];
+        let payload = fields.join("\n").trim_end_matches('\n').to_string();
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            return Err(PayloadError::TooLong {
+                field: "payload",
+                max: MAX_PAYLOAD_SIZE,
+            });
+        }
+        Ok(payload)
+    }
+}
+
+/// Check a field has a character length within the given bounds, and has no line breaks.
+pub(super) fn check_length(
+    field: &'static str,
+    value: &str,
+    min: usize,
+    max: usize,
+) -> Result<(), PayloadError> {
+    let len = value.chars().count();
+    if len < min {
+        return Err(PayloadError::Missing { field });
+    }
+    if len > max {
+        return Err(PayloadError::TooLong { field, max });
+    }
+    if let Some(character) = value.chars().find(|c| matches!(c, '\n' | '\r')) {
+        return Err(PayloadError::InvalidCharacter { field, character });
+    }
+    Ok(())
+}
+
+/// Normalize an IBAN by removing spaces, and validate its format and checksum.
+pub(super) fn normalize_iban(iban: &str) -> Result<String, PayloadError> {
+    let iban: String = iban
+        .chars()
+        .filter(|c| *c != ' ')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if let Some(character) = iban.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(PayloadError::InvalidCharacter {
+            field: "iban",
+            character,
+        });
+    }
+    if !(15..=34).contains(&iban.len()) {
+        return Err(PayloadError::Invalid {
+            field: "iban",
+            reason: "IBAN must be 15 to 34 characters long",
+        });
+    }
+    if iso7064_mod97(&iban) != 1 {
+        return Err(PayloadError::Invalid {
+            field: "iban",
+            reason: "IBAN checksum is incorrect",
+        });
+    }
+
+    Ok(iban)
+}
+
+/// Compute the ISO 7064 MOD 97-10 remainder, as used by IBANs and RF creditor references.
+///
+/// The first four characters are moved to the end, and letters count as 10 to 35.
+/// The value is valid if the remainder is 1. Value must be ASCII alphanumeric.
+pub(super) fn iso7064_mod97(value: &str) -> u32 {
+    value[4..]
+        .chars()
+        .chain(value[..4].chars())
+        .fold(0, |acc, c| {
+            let value = c.to_digit(36).unwrap();
+            if value < 10 {
+                (acc * 10 + value) % 97
+            } else {
+                (acc * 100 + value) % 97
+            }
+        })
+}
+
+/// Normalize a BIC to uppercase, and validate its format.
+fn normalize_bic(bic: &str) -> Result<String, PayloadError> {
+    let bic = bic.trim().to_ascii_uppercase();
+    if let Some(character) = bic.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(PayloadError::InvalidCharacter {
+            field: "bic",
+            character,
+        });
+    }
+    if bic.len() != 8 && bic.len() != 11 {
+        return Err(PayloadError::Invalid {
+            field: "bic",
+            reason: "BIC must be 8 or 11 characters long",
+        });
+    }
+    Ok(bic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epc_full() {
+        let transfer = EpcTransfer::new("Red Cross of Belgium", "be72 0000 0000 1616")
+            .bic("bpotbeb1")
+            .amount_cents(12345)
+            .purpose("CHAR")
+            .text("Urgency fund")
+            .information("Thanks!");
+        assert_eq!(
+            transfer.build().unwrap(),
+            "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross of Belgium\nBE72000000001616\n\
+            EUR123.45\nCHAR\n\nUrgency fund\nThanks!"
+        );
+    }
+
+    #[test]
+    fn epc_minimal_trims_trailing_fields() {
+        let transfer = EpcTransfer::new("Jane", "DE89370400440532013000");
+        assert_eq!(
+            transfer.build().unwrap(),
+            "BCD\n002\n1\nSCT\n\nJane\nDE89370400440532013000"
+        );
+    }
+
+    #[test]
+    fn epc_invalid() {
+        let valid = EpcTransfer::new("Jane", "DE89370400440532013000");
+        assert!(valid.clone().build().is_ok());
+        assert!(EpcTransfer::new("", "DE89370400440532013000")
+            .build()
+            .is_err());
+        assert!(EpcTransfer::new("Jane", "DE89370400440532013001")
+            .build()
+            .is_err());
+        assert!(valid.clone().bic("ABC").build().is_err());
+        assert!(valid
+            .clone()
+            .amount_cents(MAX_AMOUNT_CENTS + 1)
+            .build()
+            .is_err());
+        assert!(valid.clone().purpose("CHARITY").build().is_err());
+        assert!(valid
+            .clone()
+            .reference("RF18")
+            .text("text")
+            .build()
+            .is_err());
+        assert!(valid.text("a".repeat(141)).build().is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Payload validation error.
+
+use std::{error, fmt};
+
+use crate::QrError;
+
+/// Error returned when payload fields don't meet the requirements of the payload format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadError {
+    /// A required field was not set.
+    Missing {
+        /// Name of the field.
+        field: &'static str,
+    },
+
+    /// A field value is longer than the format allows.
+    TooLong {
+        /// Name of the field.
+        field: &'static str,
+
+        /// Maximum allowed length.
+        max: usize,
+    },
+
+    /// A field value contains a character the format doesn't allow.
+    InvalidCharacter {
+        /// Name of the field.
+        field: &'static str,
+
+        /// The offending character.
+        character: char,
+    },
+
+    /// A field value is otherwise invalid.
+    Invalid {
+        /// Name of the field.
+        field: &'static str,
+
+        /// Description of why the value is invalid.
+        reason: &'static str,
+    },
+
+    /// The payload is valid, but couldn't be encoded as QR code.
+    Encode(QrError),
+}
+
+impl fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PayloadError::Missing { field } => write!(f, "payload field '{}' is required", field),
+            PayloadError::TooLong { field, max } => write!(
+                f,
+                "payload field '{}' is longer than {} characters",
+                field, max
+            ),
+            PayloadError::InvalidCharacter { field, character } => write!(
+                f,
+                "payload field '{}' contains invalid character {:?}",
+                field, character
+            ),
+            PayloadError::Invalid { field, reason } => {
+                write!(f, "payload field '{}' is invalid: {}", field, reason)
+            }
+            PayloadError::Encode(err) => write!(f, "failed to encode payload: {}", err),
+        }
+    }
+}
+
+impl error::Error for PayloadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PayloadError::Encode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<QrError> for PayloadError {
+    fn from(err: QrError) -> Self {
+        PayloadError::Encode(err)
+    }
+}
@@ -0,0 +1,384 @@
+//! Calendar event `VEVENT` payload.
+
+use std::fmt::{self, Write};
+
+use super::vcard::escape;
+use super::PayloadError;
+
+/// Calendar date and time, in the time zone of the event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    /// Year, such as 2024.
+    pub year: u16,
+
+    /// Month, from 1 to 12.
+    pub month: u8,
+
+    /// Day of the month, from 1 to 31.
+    pub day: u8,
+
+    /// Hour, from 0 to 23.
+    pub hour: u8,
+
+    /// Minute, from 0 to 59.
+    pub minute: u8,
+
+    /// Second, from 0 to 59.
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Construct a date and time.
+    ///
+    /// Fields are validated when building the [`Event`].
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// Check that all fields are in range, describing the `field` otherwise.
+    fn validate(&self, field: &'static str) -> Result<(), PayloadError> {
+        let reason = if self.year > 9999 {
+            "year must be at most 9999"
+        } else if !(1..=12).contains(&self.month) {
+            "month must be from 1 to 12"
+        } else if self.day < 1 || self.day > days_in_month(self.year, self.month) {
+            "day does not exist in the month"
+        } else if self.hour > 23 || self.minute > 59 || self.second > 59 {
+            "time must be from 00:00:00 to 23:59:59"
+        } else {
+            return Ok(());
+        };
+        Err(PayloadError::Invalid { field, reason })
+    }
+
+    /// Shift this date and time by the given number of minutes, across day boundaries.
+    ///
+    /// Returns `None` if the year leaves the range from 0 to 9999.
+    fn shift_minutes(&self, minutes: i64) -> Option<Self> {
+        let days = days_from_civil(self.year as i64, self.month as i64, self.day as i64);
+        let total = days * 24 * 60 + self.hour as i64 * 60 + self.minute as i64 + minutes;
+        let (year, month, day) = civil_from_days(total.div_euclid(24 * 60));
+        let minute_of_day = total.rem_euclid(24 * 60);
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        Some(Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (minute_of_day / 60) as u8,
+            minute: (minute_of_day % 60) as u8,
+            second: self.second,
+        })
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}{:02}{:02}T{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second,
+        )
+    }
+}
+
+/// Time zone the event times are specified in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum TimeZone {
+    /// Coordinated Universal Time.
+    #[default]
+    Utc,
+
+    /// Fixed offset from UTC in minutes, such as `120` for UTC+2.
+    ///
+    /// Times are converted to UTC in the payload, which all calendar apps understand.
+    Offset(i16),
+
+    /// Named time zone from the tz database, such as `Europe/Amsterdam`.
+    Named(String),
+
+    /// Floating time, interpreted in the local time zone of whoever scans the code.
+    Floating,
+}
+
+/// Calendar event, for adding an event to a calendar by scanning.
+///
+/// Produces an iCalendar `VEVENT` block as specified in RFC 5545.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{DateTime, Event};
+///
+/// let event = Event::new("Release party", DateTime::new(2024, 6, 1, 18, 0, 0))
+///     .end(DateTime::new(2024, 6, 1, 22, 0, 0))
+///     .location("Rooftop");
+/// qr2term::print_qr(event.build().unwrap()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    summary: String,
+    start: DateTime,
+    end: Option<DateTime>,
+    time_zone: TimeZone,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+impl Event {
+    /// Construct an event with the given `summary`, starting at `start`.
+    pub fn new<S: Into<String>>(summary: S, start: DateTime) -> Self {
+        Self {
+            summary: summary.into(),
+            start,
+            end: None,
+            time_zone: TimeZone::default(),
+            location: None,
+            description: None,
+        }
+    }
+
+    /// Set the end time.
+    pub fn end(mut self, end: DateTime) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Set the time zone the start and end time are specified in, defaults to UTC.
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Set the location.
+    pub fn location<S: Into<String>>(mut self, location: S) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Set the description.
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Build the `VEVENT` payload.
+    ///
+    /// Returns an error if a date or time is out of range, or the time zone name contains
+    /// characters that can't be written in a property parameter.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        if let TimeZone::Named(tzid) = &self.time_zone {
+            if let Some(character) = tzid
+                .chars()
+                .find(|c| matches!(c, ':' | ';' | ',' | '"') || c.is_control())
+            {
+                return Err(PayloadError::InvalidCharacter {
+                    field: "time_zone",
+                    character,
+                });
+            }
+        }
+
+        let mut out = String::new();
+        out.push_str("BEGIN:VEVENT\r\n");
+        write!(out, "SUMMARY:{}\r\n", escape(&self.summary)).unwrap();
+        self.write_time(&mut out, "DTSTART", "start", &self.start)?;
+        if let Some(end) = &self.end {
+            self.write_time(&mut out, "DTEND", "end", end)?;
+        }
+        if let Some(location) = &self.location {
+            write!(out, "LOCATION:{}\r\n", escape(location)).unwrap();
+        }
+        if let Some(description) = &self.description {
+            write!(out, "DESCRIPTION:{}\r\n", escape(description)).unwrap();
+        }
+        out.push_str("END:VEVENT");
+        Ok(out)
+    }
+
+    /// Write a date and time property, in the configured time zone.
+    fn write_time(
+        &self,
+        out: &mut String,
+        name: &str,
+        field: &'static str,
+        time: &DateTime,
+    ) -> Result<(), PayloadError> {
+        time.validate(field)?;
+        match &self.time_zone {
+            TimeZone::Utc => write!(out, "{}:{}Z\r\n", name, time),
+            TimeZone::Offset(offset) => {
+                let utc = time
+                    .shift_minutes(-(*offset as i64))
+                    .ok_or(PayloadError::Invalid {
+                        field,
+                        reason: "time is out of range in UTC",
+                    })?;
+                write!(out, "{}:{}Z\r\n", name, utc)
+            }
+            TimeZone::Named(tzid) => write!(out, "{};TZID={}:{}\r\n", name, tzid, time),
+            TimeZone::Floating => write!(out, "{}:{}\r\n", name, time),
+        }
+        .unwrap();
+        Ok(())
+    }
+}
+
+/// Number of days in the given month of the proleptic Gregorian calendar.
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Number of days since 1970-01-01 for the given proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Proleptic Gregorian date for the given number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_utc() {
+        let event = Event::new("Meeting, weekly", DateTime::new(2024, 3, 5, 9, 30, 0))
+            .end(DateTime::new(2024, 3, 5, 10, 0, 0))
+            .location("Room 1")
+            .description("Agenda:\nstatus");
+        assert_eq!(
+            event.build().unwrap(),
+            "BEGIN:VEVENT\r\n\
+            SUMMARY:Meeting\\, weekly\r\n\
+            DTSTART:20240305T093000Z\r\n\
+            DTEND:20240305T100000Z\r\n\
+            LOCATION:Room 1\r\n\
+            DESCRIPTION:Agenda:\\nstatus\r\n\
+            END:VEVENT"
+        );
+    }
+
+    #[test]
+    fn event_time_zones() {
+        let start = DateTime::new(2024, 1, 1, 1, 15, 0);
+        let event = Event::new("New year", start).time_zone(TimeZone::Offset(120));
+        assert!(event
+            .build()
+            .unwrap()
+            .contains("DTSTART:20231231T231500Z\r\n"));
+
+        let event = Event::new("New year", start).time_zone(TimeZone::Offset(-90));
+        assert!(event
+            .build()
+            .unwrap()
+            .contains("DTSTART:20240101T024500Z\r\n"));
+
+        let event = Event::new("New year", start).time_zone(TimeZone::Offset(i16::MIN));
+        assert!(event
+            .build()
+            .unwrap()
+            .contains("DTSTART:20240123T192300Z\r\n"));
+
+        let event =
+            Event::new("New year", start).time_zone(TimeZone::Named("Europe/Amsterdam".into()));
+        assert!(event
+            .build()
+            .unwrap()
+            .contains("DTSTART;TZID=Europe/Amsterdam:20240101T011500\r\n"));
+
+        let event = Event::new("New year", start).time_zone(TimeZone::Floating);
+        assert!(event
+            .build()
+            .unwrap()
+            .contains("DTSTART:20240101T011500\r\n"));
+    }
+
+    #[test]
+    fn invalid_time_zone_names() {
+        let start = DateTime::new(2024, 1, 1, 1, 15, 0);
+        for tzid in ["Europe/Amsterdam:X", "A;B", "A\r\nATTENDEE:x"] {
+            let event = Event::new("New year", start).time_zone(TimeZone::Named(tzid.into()));
+            assert!(matches!(
+                event.build(),
+                Err(PayloadError::InvalidCharacter {
+                    field: "time_zone",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_times() {
+        for start in [
+            DateTime::new(2024, 13, 1, 0, 0, 0),
+            DateTime::new(2024, 1, 40, 0, 0, 0),
+            DateTime::new(2023, 2, 29, 0, 0, 0),
+            DateTime::new(2024, 1, 1, 99, 0, 0),
+            DateTime::new(10000, 1, 1, 0, 0, 0),
+        ] {
+            assert!(matches!(
+                Event::new("Broken", start).build(),
+                Err(PayloadError::Invalid { field: "start", .. })
+            ));
+        }
+        let event = Event::new("Leap", DateTime::new(2024, 1, 1, 0, 0, 0))
+            .end(DateTime::new(2024, 2, 30, 0, 0, 0));
+        assert!(matches!(
+            event.build(),
+            Err(PayloadError::Invalid { field: "end", .. })
+        ));
+
+        // Moving back across midnight of year 0 doesn't wrap around
+        let event =
+            Event::new("Early", DateTime::new(0, 1, 1, 0, 30, 0)).time_zone(TimeZone::Offset(60));
+        assert!(matches!(
+            event.build(),
+            Err(PayloadError::Invalid { field: "start", .. })
+        ));
+    }
+
+    #[test]
+    fn shift_across_leap_day() {
+        let time = DateTime::new(2024, 2, 28, 23, 0, 0).shift_minutes(24 * 60);
+        assert_eq!(time, Some(DateTime::new(2024, 2, 29, 23, 0, 0)));
+        let time = DateTime::new(2024, 3, 1, 0, 30, 0).shift_minutes(-60);
+        assert_eq!(time, Some(DateTime::new(2024, 2, 29, 23, 30, 0)));
+    }
+}
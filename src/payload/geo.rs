@@ -0,0 +1,148 @@
+//! Geographic location `geo:` URI payload.
+
+use std::fmt;
+
+use super::{percent, PayloadError};
+
+/// Default number of decimals for coordinates, which is roughly 10 centimeters.
+const DEFAULT_PRECISION: usize = 6;
+
+/// Geographic location, for opening a map or navigation app by scanning.
+///
+/// Produces a `geo:` URI as specified in RFC 5870, optionally with a search query as
+/// understood by Android.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Geo;
+///
+/// let geo = Geo::new(52.370216, 4.895168).unwrap().query("Dam Square");
+/// assert_eq!(geo.to_string(), "geo:52.370216,4.895168?q=Dam%20Square");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geo {
+    lat: f64,
+    lon: f64,
+    altitude: Option<f64>,
+    query: Option<String>,
+    precision: usize,
+}
+
+impl Geo {
+    /// Construct a location at the given latitude and longitude in decimal degrees.
+    ///
+    /// Returns an error if the latitude is outside -90 to 90 degrees, or the longitude
+    /// outside -180 to 180 degrees.
+    pub fn new(lat: f64, lon: f64) -> Result<Self, PayloadError> {
+        // Also rejects NaN, which isn't in any range
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(PayloadError::Invalid {
+                field: "lat",
+                reason: "latitude must be from -90 to 90 degrees",
+            });
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(PayloadError::Invalid {
+                field: "lon",
+                reason: "longitude must be from -180 to 180 degrees",
+            });
+        }
+        Ok(Self {
+            lat,
+            lon,
+            altitude: None,
+            query: None,
+            precision: DEFAULT_PRECISION,
+        })
+    }
+
+    /// Set the altitude in meters.
+    pub fn altitude(mut self, altitude: f64) -> Self {
+        self.altitude = Some(altitude);
+        self
+    }
+
+    /// Set a search query, such as a place name or address.
+    pub fn query<S: Into<String>>(mut self, query: S) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// Set the maximum number of decimals for coordinates, defaults to 6.
+    ///
+    /// Trailing zeros are always omitted, keeping the payload short.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+}
+
+impl fmt::Display for Geo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "geo:{},{}",
+            format_coordinate(self.lat, self.precision),
+            format_coordinate(self.lon, self.precision),
+        )?;
+        if let Some(altitude) = self.altitude {
+            write!(f, ",{}", format_coordinate(altitude, self.precision))?;
+        }
+        if let Some(query) = &self.query {
+            write!(f, "?q={}", percent::encode(query, b","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Format a coordinate with at most `precision` decimals, without trailing zeros.
+fn format_coordinate(value: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, value);
+    let formatted = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+
+    // Don't emit negative zero
+    match formatted {
+        "-0" => "0".into(),
+        formatted => formatted.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geo_altitude() {
+        let geo = Geo::new(-33.8568, 151.2153).unwrap().altitude(12.5);
+        assert_eq!(geo.to_string(), "geo:-33.8568,151.2153,12.5");
+    }
+
+    #[test]
+    fn geo_precision() {
+        let geo = Geo::new(52.3702157, -0.0000001).unwrap().precision(3);
+        assert_eq!(geo.to_string(), "geo:52.37,0");
+        assert_eq!(Geo::new(1.0, 2.0).unwrap().to_string(), "geo:1,2");
+    }
+
+    #[test]
+    fn geo_invalid() {
+        assert!(Geo::new(90.0, -180.0).is_ok());
+        for lat in [f64::NAN, f64::INFINITY, -90.5, 91.0] {
+            assert!(matches!(
+                Geo::new(lat, 0.0),
+                Err(PayloadError::Invalid { field: "lat", .. })
+            ));
+        }
+        for lon in [f64::NAN, f64::NEG_INFINITY, 180.1] {
+            assert!(matches!(
+                Geo::new(0.0, lon),
+                Err(PayloadError::Invalid { field: "lon", .. })
+            ));
+        }
+    }
+}
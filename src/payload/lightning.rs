@@ -0,0 +1,106 @@
+//! Lightning Network BOLT11 invoice payload.
+
+use std::fmt;
+
+use super::PayloadError;
+use crate::qr::{EcLevel, Mode, Qr};
+use crate::QrError;
+
+/// Lightning Network invoice, for paying from a wallet app by scanning.
+///
+/// BOLT11 invoices are bech32 encoded and case insensitive. This uppercases the invoice
+/// so it can be encoded using the QR alphanumeric mode, which results in a significantly
+/// smaller code than the byte mode needed for lowercase text.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Lightning;
+///
+/// let invoice = Lightning::new("lightning:lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyq")
+///     .unwrap();
+/// assert!(invoice.to_string().starts_with("LIGHTNING:LNBC1PVJLUEZ"));
+/// let qr = invoice.to_qr().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lightning {
+    invoice: String,
+}
+
+impl Lightning {
+    /// Construct from a BOLT11 invoice, optionally prefixed with the `lightning:` scheme.
+    ///
+    /// Fails if the invoice contains characters that would force the QR byte mode.
+    pub fn new<S: AsRef<str>>(invoice: S) -> Result<Self, PayloadError> {
+        let invoice = invoice.as_ref().trim().to_ascii_uppercase();
+        if let Some(character) = invoice.chars().find(|c| !is_alphanumeric_mode(*c)) {
+            return Err(PayloadError::InvalidCharacter {
+                field: "invoice",
+                character,
+            });
+        }
+        if !invoice
+            .strip_prefix("LIGHTNING:")
+            .unwrap_or(&invoice)
+            .starts_with("LN")
+        {
+            return Err(PayloadError::Invalid {
+                field: "invoice",
+                reason: "BOLT11 invoice must start with ln",
+            });
+        }
+        Ok(Self { invoice })
+    }
+
+    /// Encode the invoice as QR code, using the alphanumeric mode.
+    pub fn to_qr(&self) -> Result<Qr, QrError> {
+        Qr::with_mode(&self.invoice, Mode::Alphanumeric, EcLevel::M)
+    }
+}
+
+impl fmt::Display for Lightning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.invoice)
+    }
+}
+
+/// Check whether the given character can be encoded in the QR alphanumeric mode.
+fn is_alphanumeric_mode(c: char) -> bool {
+    matches!(c, '0'..='9' | 'A'..='Z' | ' ' | '$' | '%' | '*' | '+' | '-' | '.' | '/' | ':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example invoice from the BOLT11 specification.
+    const INVOICE: &str =
+        "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqc\
+        yq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twv\
+        ssxgmm9v3nx2c3qwdjhx6tj9ywqvp2a9h0m8vztfug3p7pxxx8t0pqhd7u4yxkcz2hfl7xzf4gnr9gfks2geqsk0g\
+        v349txkvwuq5peqtnxmlaj5nn235kp8nsh6pwr6sdmd5or9k5l224rsq6s5pq9cqflw5q";
+
+    #[test]
+    fn lightning_alphanumeric_is_smaller() {
+        let invoice = Lightning::new(INVOICE).unwrap();
+        let alphanumeric = invoice.to_qr().unwrap().to_matrix().size();
+        let byte = Qr::with_mode(INVOICE, Mode::Byte, EcLevel::M)
+            .unwrap()
+            .to_matrix()
+            .size();
+        assert!(alphanumeric < byte);
+    }
+
+    #[test]
+    fn lightning_invalid() {
+        assert_eq!(
+            Lightning::new("lnbc1_invalid"),
+            Err(PayloadError::InvalidCharacter {
+                field: "invoice",
+                character: '_'
+            })
+        );
+        assert!(Lightning::new("bitcoin:abc").is_err());
+        assert!(Lightning::new("LIGHTNING:lnbc1abc").is_ok());
+    }
+}
@@ -0,0 +1,213 @@
+//! Matter smart home device onboarding payload.
+
+use std::fmt;
+
+use super::PayloadError;
+
+/// Base38 alphabet, as specified by Matter.
+const BASE38_ALPHABET: &[u8; 38] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-.";
+
+/// Setup passcodes that are too trivial to be allowed.
+const INVALID_PASSCODES: [u32; 12] = [
+    0, 11111111, 22222222, 33333333, 44444444, 55555555, 66666666, 77777777, 88888888, 99999999,
+    12345678, 87654321,
+];
+
+/// Flow a Matter device uses to enter commissioning mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CommissioningFlow {
+    /// Device is in commissioning mode when powered on.
+    #[default]
+    Standard,
+
+    /// Device requires a user action, such as pressing a button.
+    UserIntent,
+
+    /// Device requires vendor specific steps.
+    Custom,
+}
+
+/// Ways a Matter device can be discovered for commissioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Discovery {
+    /// Discoverable through a Wi-Fi soft access point.
+    pub soft_ap: bool,
+
+    /// Discoverable through Bluetooth Low Energy.
+    pub ble: bool,
+
+    /// Discoverable on the IP network it is already connected to.
+    pub on_network: bool,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Self {
+            soft_ap: false,
+            ble: true,
+            on_network: false,
+        }
+    }
+}
+
+/// Matter device onboarding payload, for pairing a smart home device by scanning.
+///
+/// Produces the `MT:` prefixed Base38 encoded setup payload, as printed on Matter devices.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{Discovery, Matter};
+///
+/// let matter = Matter::new(0xFFF1, 0x8001, 3840, 20202021)
+///     .unwrap()
+///     .discovery(Discovery {
+///         soft_ap: false,
+///         ble: false,
+///         on_network: true,
+///     });
+/// assert_eq!(matter.to_string(), "MT:-24J0AFN00KA0648G00");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matter {
+    vendor_id: u16,
+    product_id: u16,
+    discriminator: u16,
+    passcode: u32,
+    flow: CommissioningFlow,
+    discovery: Discovery,
+}
+
+impl Matter {
+    /// Construct an onboarding payload for the given device identification, 12 bit
+    /// `discriminator` and setup `passcode`.
+    ///
+    /// Fails if the discriminator or passcode is out of range, or if the passcode is one of
+    /// the trivial passcodes the specification disallows.
+    pub fn new(
+        vendor_id: u16,
+        product_id: u16,
+        discriminator: u16,
+        passcode: u32,
+    ) -> Result<Self, PayloadError> {
+        if discriminator > 0xFFF {
+            return Err(PayloadError::Invalid {
+                field: "discriminator",
+                reason: "discriminator must fit in 12 bits",
+            });
+        }
+        if passcode > 99999998 || INVALID_PASSCODES.contains(&passcode) {
+            return Err(PayloadError::Invalid {
+                field: "passcode",
+                reason: "passcode must be between 1 and 99999998, and not trivial",
+            });
+        }
+
+        Ok(Self {
+            vendor_id,
+            product_id,
+            discriminator,
+            passcode,
+            flow: CommissioningFlow::default(),
+            discovery: Discovery::default(),
+        })
+    }
+
+    /// Set the commissioning flow, defaults to standard.
+    pub fn flow(mut self, flow: CommissioningFlow) -> Self {
+        self.flow = flow;
+        self
+    }
+
+    /// Set the discovery capabilities, defaults to BLE only.
+    pub fn discovery(mut self, discovery: Discovery) -> Self {
+        self.discovery = discovery;
+        self
+    }
+
+    /// Pack all fields into the 88 bit payload, least significant bit first.
+    fn pack(&self) -> [u8; 11] {
+        let flow = match self.flow {
+            CommissioningFlow::Standard => 0,
+            CommissioningFlow::UserIntent => 1,
+            CommissioningFlow::Custom => 2,
+        };
+        let discovery = self.discovery.soft_ap as u128
+            | (self.discovery.ble as u128) << 1
+            | (self.discovery.on_network as u128) << 2;
+
+        // Version is 0, followed by the fields and 4 bits of padding
+        let bits = (self.vendor_id as u128) << 3
+            | (self.product_id as u128) << 19
+            | flow << 35
+            | discovery << 37
+            | (self.discriminator as u128) << 45
+            | (self.passcode as u128) << 57;
+
+        let mut bytes = [0; 11];
+        bytes.copy_from_slice(&bits.to_le_bytes()[..11]);
+        bytes
+    }
+}
+
+impl fmt::Display for Matter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MT:")?;
+
+        // Encode chunks of 3 bytes into 5 characters, and shorter chunks into fewer
+        for chunk in self.pack().chunks(3) {
+            let mut value = chunk
+                .iter()
+                .rev()
+                .fold(0u32, |value, &byte| value << 8 | byte as u32);
+            let chars = match chunk.len() {
+                3 => 5,
+                2 => 4,
+                _ => 2,
+            };
+            for _ in 0..chars {
+                write!(f, "{}", BASE38_ALPHABET[(value % 38) as usize] as char)?;
+                value /= 38;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matter_flow_and_discovery() {
+        let matter = Matter::new(0xFFF1, 0x8001, 0xF00, 12345679)
+            .unwrap()
+            .flow(CommissioningFlow::UserIntent)
+            .discovery(Discovery {
+                soft_ap: true,
+                ble: false,
+                on_network: true,
+            });
+        let bits = u128::from_le_bytes({
+            let mut bytes = [0; 16];
+            bytes[..11].copy_from_slice(&matter.pack());
+            bytes
+        });
+        assert_eq!(bits & 0x7, 0);
+        assert_eq!(bits >> 3 & 0xFFFF, 0xFFF1);
+        assert_eq!(bits >> 19 & 0xFFFF, 0x8001);
+        assert_eq!(bits >> 35 & 0x3, 1);
+        assert_eq!(bits >> 37 & 0xFF, 0b101);
+        assert_eq!(bits >> 45 & 0xFFF, 0xF00);
+        assert_eq!(bits >> 57, 12345679);
+        assert_eq!(matter.to_string().len(), 22);
+    }
+
+    #[test]
+    fn matter_invalid() {
+        assert!(Matter::new(1, 1, 0x1000, 20202021).is_err());
+        assert!(Matter::new(1, 1, 1, 11111111).is_err());
+        assert!(Matter::new(1, 1, 1, 99999999).is_err());
+        assert!(Matter::new(1, 1, 1, 0).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Payload builders for common QR code contents.
+//!
+//! Each builder produces the textual payload that scanning apps recognize, which can be
+//! passed straight into [`print_qr`](fn@crate::print_qr) or
+//! [`generate_qr_string`](crate::generate_qr_string). All builders implement [`ToQrPayload`],
+//! to print them with the recommended encoding using
+//! [`print_qr_payload`](crate::print_qr_payload).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::payload::{Security, Wifi};
+//!
+//! let wifi = Wifi::new("My network").password("secret").security(Security::Wpa);
+//! qr2term::print_qr(wifi.to_string()).unwrap();
+//! ```
+
+pub mod percent;
+
+pub(crate) mod base64;
+mod bcbp;
+mod binary;
+mod crypto;
+mod email;
+mod emv;
+mod epc;
+mod error;
+mod event;
+mod geo;
+mod lightning;
+mod matter;
+mod otp;
+mod phone;
+mod qr_payload;
+mod ssh;
+mod swiss;
+mod upi;
+mod url;
+mod vcard;
+mod wifi;
+mod wireguard;
+
+pub use bcbp::{BoardingPass, Leg};
+pub use binary::{Binary, BinaryEncoding};
+pub use crypto::CryptoUri;
+pub use email::Email;
+pub use emv::EmvMerchant;
+pub use epc::EpcTransfer;
+pub use error::PayloadError;
+pub use event::{DateTime, Event, TimeZone};
+pub use geo::Geo;
+pub use lightning::Lightning;
+pub use matter::{CommissioningFlow, Discovery, Matter};
+pub use otp::{Algorithm, Hotp, Migration, Totp};
+pub use phone::Phone;
+pub use qr_payload::{QrPayload, ToQrPayload};
+pub use ssh::SshKey;
+pub use swiss::{Currency, SwissAddress, SwissQrBill, SwissReference};
+pub use upi::Upi;
+pub use url::{Change, Suggestion, Url};
+pub use vcard::{Address, VCard, VCardVersion};
+pub use wifi::{Security, Wifi};
+pub use wireguard::{WireGuard, WireGuardPeer};
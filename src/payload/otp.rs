@@ -0,0 +1,500 @@
+//! One-time password `otpauth://` URI payloads.
+
+use std::fmt;
+
+use super::percent::{self, COMPONENT};
+use super::{base64, PayloadError};
+
+/// Base32 alphabet, as specified in RFC 4648.
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Hash algorithm used to generate one-time passwords.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// SHA-1, supported by all authenticator apps.
+    #[default]
+    Sha1,
+
+    /// SHA-256.
+    Sha256,
+
+    /// SHA-512.
+    Sha512,
+}
+
+impl Algorithm {
+    /// The value used for the `algorithm` parameter.
+    fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::Sha1 => "SHA1",
+            Algorithm::Sha256 => "SHA256",
+            Algorithm::Sha512 => "SHA512",
+        }
+    }
+
+    /// The value used in the migration format.
+    fn migration_value(&self) -> u64 {
+        match self {
+            Algorithm::Sha1 => 1,
+            Algorithm::Sha256 => 2,
+            Algorithm::Sha512 => 3,
+        }
+    }
+}
+
+/// Time-based one-time password (TOTP) secret, for enrolling an authenticator app.
+///
+/// Produces an `otpauth://totp/` URI in the Key URI format used by Google Authenticator
+/// and compatible apps. Parameters left at their default are omitted to keep the
+/// code small.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Totp;
+///
+/// let totp = Totp::new("alice@example.com", b"12345678901234567890").issuer("Example Co");
+/// assert_eq!(
+///     totp.to_string(),
+///     "otpauth://totp/Example%20Co:alice%40example.com\
+///     ?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Co",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Totp {
+    account: String,
+    secret: Vec<u8>,
+    issuer: Option<String>,
+    algorithm: Algorithm,
+    digits: u8,
+    period: u32,
+}
+
+impl Totp {
+    /// Construct a TOTP secret for the given `account` name, from raw `secret` bytes.
+    pub fn new<S: Into<String>, B: Into<Vec<u8>>>(account: S, secret: B) -> Self {
+        Self {
+            account: account.into(),
+            secret: secret.into(),
+            issuer: None,
+            algorithm: Algorithm::default(),
+            digits: 6,
+            period: 30,
+        }
+    }
+
+    /// Construct a TOTP secret for the given `account` name, from a Base32 encoded `secret`.
+    ///
+    /// Spaces and padding are ignored, and the secret is case insensitive.
+    pub fn from_base32<S: Into<String>>(account: S, secret: &str) -> Result<Self, PayloadError> {
+        Ok(Self::new(account, base32_decode(secret)?))
+    }
+
+    /// Set the issuer, the provider or service the account belongs to.
+    pub fn issuer<S: Into<String>>(mut self, issuer: S) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Set the hash algorithm, defaults to SHA-1.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the number of digits of generated passwords, defaults to 6.
+    pub fn digits(mut self, digits: u8) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Set the period in seconds a password is valid for, defaults to 30.
+    pub fn period(mut self, period: u32) -> Self {
+        self.period = period;
+        self
+    }
+}
+
+impl fmt::Display for Totp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "otpauth://totp/")?;
+        write_label(f, self.issuer.as_deref(), &self.account)?;
+        write!(f, "?secret={}", base32_encode(&self.secret))?;
+        if let Some(issuer) = &self.issuer {
+            write!(f, "&issuer={}", percent::encode(issuer, COMPONENT))?;
+        }
+        if self.algorithm != Algorithm::default() {
+            write!(f, "&algorithm={}", self.algorithm.as_str())?;
+        }
+        if self.digits != 6 {
+            write!(f, "&digits={}", self.digits)?;
+        }
+        if self.period != 30 {
+            write!(f, "&period={}", self.period)?;
+        }
+        Ok(())
+    }
+}
+
+/// HMAC-based one-time password (HOTP) secret, for enrolling an authenticator app.
+///
+/// Produces an `otpauth://hotp/` URI in the Key URI format used by Google Authenticator
+/// and compatible apps.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Hotp;
+///
+/// let hotp = Hotp::new("alice", b"12345678901234567890").counter(5);
+/// assert_eq!(
+///     hotp.to_string(),
+///     "otpauth://hotp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&counter=5",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotp {
+    account: String,
+    secret: Vec<u8>,
+    issuer: Option<String>,
+    algorithm: Algorithm,
+    digits: u8,
+    counter: u64,
+}
+
+impl Hotp {
+    /// Construct a HOTP secret for the given `account` name, from raw `secret` bytes.
+    pub fn new<S: Into<String>, B: Into<Vec<u8>>>(account: S, secret: B) -> Self {
+        Self {
+            account: account.into(),
+            secret: secret.into(),
+            issuer: None,
+            algorithm: Algorithm::default(),
+            digits: 6,
+            counter: 0,
+        }
+    }
+
+    /// Construct a HOTP secret for the given `account` name, from a Base32 encoded `secret`.
+    ///
+    /// Spaces and padding are ignored, and the secret is case insensitive.
+    pub fn from_base32<S: Into<String>>(account: S, secret: &str) -> Result<Self, PayloadError> {
+        Ok(Self::new(account, base32_decode(secret)?))
+    }
+
+    /// Set the issuer, the provider or service the account belongs to.
+    pub fn issuer<S: Into<String>>(mut self, issuer: S) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Set the hash algorithm, defaults to SHA-1.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the number of digits of generated passwords, defaults to 6.
+    pub fn digits(mut self, digits: u8) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Set the initial counter value, defaults to 0.
+    pub fn counter(mut self, counter: u64) -> Self {
+        self.counter = counter;
+        self
+    }
+}
+
+impl fmt::Display for Hotp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "otpauth://hotp/")?;
+        write_label(f, self.issuer.as_deref(), &self.account)?;
+        write!(f, "?secret={}", base32_encode(&self.secret))?;
+        if let Some(issuer) = &self.issuer {
+            write!(f, "&issuer={}", percent::encode(issuer, COMPONENT))?;
+        }
+        if self.algorithm != Algorithm::default() {
+            write!(f, "&algorithm={}", self.algorithm.as_str())?;
+        }
+        if self.digits != 6 {
+            write!(f, "&digits={}", self.digits)?;
+        }
+        write!(f, "&counter={}", self.counter)
+    }
+}
+
+/// Batch export of one-time password accounts, in the Google Authenticator migration format.
+///
+/// Produces an `otpauth-migration://offline?data=` URI, holding a Base64 encoded protobuf
+/// message with all accounts. This allows moving many accounts to a phone with a single
+/// scan.
+///
+/// The format only supports 6 or 8 digit passwords, and a TOTP period of 30 seconds.
+/// [`build`](Self::build) fails for accounts using anything else.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{Hotp, Migration, Totp};
+///
+/// let migration = Migration::new()
+///     .totp(Totp::new("alice", b"secret").issuer("Example"))
+///     .hotp(Hotp::new("bob", b"secret").counter(3));
+/// qr2term::print_qr(migration.build().unwrap()).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Migration {
+    accounts: Vec<Account>,
+}
+
+/// Account in a migration batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Account {
+    Totp(Totp),
+    Hotp(Hotp),
+}
+
+impl Migration {
+    /// Construct an empty migration batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a TOTP account.
+    pub fn totp(mut self, totp: Totp) -> Self {
+        self.accounts.push(Account::Totp(totp));
+        self
+    }
+
+    /// Add a HOTP account.
+    pub fn hotp(mut self, hotp: Hotp) -> Self {
+        self.accounts.push(Account::Hotp(hotp));
+        self
+    }
+
+    /// Build the migration URI.
+    ///
+    /// Fails if an account uses parameters the migration format can't represent.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        let mut payload = Vec::new();
+        for account in &self.accounts {
+            let mut params = Vec::new();
+            let (secret, name, issuer, algorithm, digits) = match account {
+                Account::Totp(totp) => {
+                    if totp.period != 30 {
+                        return Err(PayloadError::Invalid {
+                            field: "period",
+                            reason: "migration format only supports a period of 30 seconds",
+                        });
+                    }
+                    (
+                        &totp.secret,
+                        &totp.account,
+                        &totp.issuer,
+                        totp.algorithm,
+                        totp.digits,
+                    )
+                }
+                Account::Hotp(hotp) => (
+                    &hotp.secret,
+                    &hotp.account,
+                    &hotp.issuer,
+                    hotp.algorithm,
+                    hotp.digits,
+                ),
+            };
+            let digits = match digits {
+                6 => 1,
+                8 => 2,
+                _ => {
+                    return Err(PayloadError::Invalid {
+                        field: "digits",
+                        reason: "migration format only supports 6 or 8 digits",
+                    })
+                }
+            };
+
+            protobuf_bytes(&mut params, 1, secret);
+            protobuf_bytes(&mut params, 2, name.as_bytes());
+            if let Some(issuer) = issuer {
+                protobuf_bytes(&mut params, 3, issuer.as_bytes());
+            }
+            protobuf_varint(&mut params, 4, algorithm.migration_value());
+            protobuf_varint(&mut params, 5, digits);
+            match account {
+                Account::Totp(_) => protobuf_varint(&mut params, 6, 2),
+                Account::Hotp(hotp) => {
+                    protobuf_varint(&mut params, 6, 1);
+                    protobuf_varint(&mut params, 7, hotp.counter);
+                }
+            }
+            protobuf_bytes(&mut payload, 1, &params);
+        }
+
+        // Version, batch size and batch index
+        protobuf_varint(&mut payload, 2, 1);
+        protobuf_varint(&mut payload, 3, 1);
+        protobuf_varint(&mut payload, 4, 0);
+
+        Ok(format!(
+            "otpauth-migration://offline?data={}",
+            percent::encode(&base64::encode(&payload), COMPONENT)
+        ))
+    }
+}
+
+/// Write a protobuf varint encoded value.
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Write a protobuf varint field.
+fn protobuf_varint(out: &mut Vec<u8>, field: u64, value: u64) {
+    varint(out, field << 3);
+    varint(out, value);
+}
+
+/// Write a protobuf length-delimited field.
+fn protobuf_bytes(out: &mut Vec<u8>, field: u64, value: &[u8]) {
+    varint(out, field << 3 | 2);
+    varint(out, value.len() as u64);
+    out.extend_from_slice(value);
+}
+
+/// Write the `issuer:account` label, with both parts percent-encoded.
+fn write_label(f: &mut fmt::Formatter, issuer: Option<&str>, account: &str) -> fmt::Result {
+    if let Some(issuer) = issuer {
+        write!(f, "{}:", percent::encode(issuer, COMPONENT))?;
+    }
+    write!(f, "{}", percent::encode(account, COMPONENT))
+}
+
+/// Encode bytes as Base32 without padding.
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() * 8 + 4) / 5);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1F) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+    }
+    out
+}
+
+/// Decode a Base32 string, ignoring spaces, padding and case.
+fn base32_decode(data: &str) -> Result<Vec<u8>, PayloadError> {
+    let mut out = Vec::with_capacity(data.len() * 5 / 8);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for c in data.chars().filter(|c| !matches!(c, ' ' | '=')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())
+            .ok_or(PayloadError::InvalidCharacter {
+                field: "secret",
+                character: c,
+            })?;
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base32_round_trip() {
+        assert_eq!(base32_encode(b""), "");
+        assert_eq!(base32_encode(b"f"), "MY");
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw 6ytb oi==").unwrap(), b"foobar");
+        assert!(base32_decode("MZ1").is_err());
+    }
+
+    #[test]
+    fn hotp_issuer() {
+        let hotp = Hotp::new("bob", b"foobar")
+            .issuer("ACME")
+            .digits(8)
+            .counter(42);
+        assert_eq!(
+            hotp.to_string(),
+            "otpauth://hotp/ACME:bob?secret=MZXW6YTBOI&issuer=ACME&digits=8&counter=42"
+        );
+    }
+
+    #[test]
+    fn migration_protobuf() {
+        let migration = Migration::new()
+            .totp(Totp::new("a", b"AB").issuer("I"))
+            .hotp(Hotp::new("b", b"C").counter(300));
+        let expected = base64::encode(&[
+            0x0A, 0x10, // otp_parameters, 16 bytes
+            0x0A, 0x02, b'A', b'B', // secret
+            0x12, 0x01, b'a', // name
+            0x1A, 0x01, b'I', // issuer
+            0x20, 0x01, // algorithm SHA1
+            0x28, 0x01, // digits six
+            0x30, 0x02, // type TOTP
+            0x0A, 0x0F, // otp_parameters, 15 bytes
+            0x0A, 0x01, b'C', // secret
+            0x12, 0x01, b'b', // name
+            0x20, 0x01, // algorithm SHA1
+            0x28, 0x01, // digits six
+            0x30, 0x01, // type HOTP
+            0x38, 0xAC, 0x02, // counter 300
+            0x10, 0x01, // version
+            0x18, 0x01, // batch size
+            0x20, 0x00, // batch index
+        ]);
+        assert_eq!(
+            migration.build().unwrap(),
+            format!(
+                "otpauth-migration://offline?data={}",
+                percent::encode(&expected, COMPONENT)
+            )
+        );
+    }
+
+    #[test]
+    fn migration_unsupported() {
+        let migration = Migration::new().totp(Totp::new("a", b"A").period(60));
+        assert!(migration.build().is_err());
+        let migration = Migration::new().hotp(Hotp::new("a", b"A").digits(7));
+        assert!(migration.build().is_err());
+    }
+
+    #[test]
+    fn totp_all_parameters() {
+        let totp = Totp::from_base32("bob:smith", "JBSWY3DPEHPK3PXP")
+            .unwrap()
+            .issuer("ACME: Corp")
+            .algorithm(Algorithm::Sha256)
+            .digits(8)
+            .period(60);
+        assert_eq!(
+            totp.to_string(),
+            "otpauth://totp/ACME%3A%20Corp:bob%3Asmith?secret=JBSWY3DPEHPK3PXP\
+            &issuer=ACME%3A%20Corp&algorithm=SHA256&digits=8&period=60"
+        );
+    }
+}
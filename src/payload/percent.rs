@@ -0,0 +1,88 @@
+//! Percent-encoding for URI payloads.
+//!
+//! Use these to build custom URI payloads, as the payload builders in this crate do.
+//! Unreserved characters, being ASCII letters, digits and `-._~` as specified in RFC 3986,
+//! are never encoded. Pass one of the sets below to also keep characters that are safe in a
+//! particular URI component.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::payload::percent;
+//!
+//! let uri = format!(
+//!     "myapp://{}?name={}",
+//!     percent::encode("files/a b.txt", percent::PATH),
+//!     percent::encode("Jane & Joe", percent::QUERY_VALUE),
+//! );
+//! assert_eq!(uri, "myapp://files/a%20b.txt?name=Jane%20%26%20Joe");
+//! assert_eq!(percent::decode("Jane%20%26%20Joe").unwrap(), "Jane & Joe");
+//! ```
+
+/// Characters to keep in path segments, including the `/` separator.
+pub const PATH: &[u8] = b"!$&'()*+,;=:@/";
+
+/// Characters to keep in query parameter keys and values.
+///
+/// Excludes `&`, `=`, `+` and `#`, which have special meaning in the query string.
+pub const QUERY_VALUE: &[u8] = b"!$'()*,;:@/?";
+
+/// Characters to keep in a URI component that must not contain any reserved characters.
+pub const COMPONENT: &[u8] = b"";
+
+/// Percent-encode `value`, keeping unreserved characters and the bytes in `keep` as is.
+///
+/// Unreserved characters are ASCII letters, digits and `-._~` as specified in RFC 3986.
+pub fn encode(value: &str, keep: &[u8]) -> String {
+    let mut out = String::with_capacity(value.len());
+    for &byte in value.as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Decode a percent-encoded `value`.
+///
+/// Returns `None` if it contains an invalid escape, or doesn't decode to valid UTF-8.
+pub fn decode(value: &str) -> Option<String> {
+    let mut out = Vec::with_capacity(value.len());
+    let mut bytes = value.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let high = (bytes.next()? as char).to_digit(16)?;
+            let low = (bytes.next()? as char).to_digit(16)?;
+            out.push((high * 16 + low) as u8);
+        } else {
+            out.push(byte);
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_reserved() {
+        assert_eq!(encode("a b&c=d/é", &[]), "a%20b%26c%3Dd%2F%C3%A9");
+        assert_eq!(encode("a@b/c", b"@"), "a@b%2Fc");
+        assert_eq!(encode("AZaz09-._~", &[]), "AZaz09-._~");
+        assert_eq!(encode("a/b?c=d&e#f", PATH), "a/b%3Fc=d&e%23f");
+        assert_eq!(encode("a/b?c=d&e#f", QUERY_VALUE), "a/b?c%3Dd%26e%23f");
+    }
+
+    #[test]
+    fn decode_roundtrip() {
+        let value = "a b&c=d/é%";
+        assert_eq!(decode(&encode(value, COMPONENT)).unwrap(), value);
+        assert_eq!(decode("%e2%82%ac").unwrap(), "€");
+        assert_eq!(decode("%2"), None);
+        assert_eq!(decode("%zz"), None);
+        assert_eq!(decode("%FF"), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! Phone call `tel:` URI payload.
+
+use std::fmt;
+
+use super::PayloadError;
+
+/// Maximum number of digits in an E.164 phone number.
+const E164_MAX_DIGITS: usize = 15;
+
+/// Phone number, for starting a call by scanning.
+///
+/// Produces a `tel:` URI as specified in RFC 3966. Visual separators such as spaces,
+/// dashes, dots and parentheses are stripped.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Phone;
+///
+/// let phone = Phone::new("+1 (555) 010-9999").unwrap();
+/// assert_eq!(phone.to_string(), "tel:+15550109999");
+///
+/// let phone = Phone::new("06 12345678").unwrap().e164(31).unwrap();
+/// assert_eq!(phone.to_string(), "tel:+31612345678");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phone {
+    number: String,
+}
+
+impl Phone {
+    /// Construct a phone number, validating it only contains digits and separators.
+    ///
+    /// The number may start with `+` to indicate it's in international format.
+    pub fn new<S: AsRef<str>>(number: S) -> Result<Self, PayloadError> {
+        let number = number.as_ref().trim();
+        let mut normalized = String::with_capacity(number.len());
+        for (i, c) in number.chars().enumerate() {
+            match c {
+                '0'..='9' => normalized.push(c),
+                '+' if i == 0 => normalized.push(c),
+                ' ' | '-' | '.' | '(' | ')' => {}
+                character => {
+                    return Err(PayloadError::InvalidCharacter {
+                        field: "number",
+                        character,
+                    })
+                }
+            }
+        }
+
+        if !normalized.chars().any(|c| c.is_ascii_digit()) {
+            return Err(PayloadError::Missing { field: "number" });
+        }
+
+        Ok(Self { number: normalized })
+    }
+
+    /// Normalize into E.164 international format, using `country_code` for national numbers.
+    ///
+    /// A leading `00` international prefix is replaced by `+`, and a leading `0` trunk
+    /// prefix on national numbers is dropped. Fails if the result has more than 15 digits.
+    pub fn e164(self, country_code: u16) -> Result<Self, PayloadError> {
+        let number = if let Some(number) = self.number.strip_prefix('+') {
+            format!("+{}", number)
+        } else if let Some(number) = self.number.strip_prefix("00") {
+            format!("+{}", number)
+        } else {
+            let national = self.number.strip_prefix('0').unwrap_or(&self.number);
+            format!("+{}{}", country_code, national)
+        };
+
+        if number.len() - 1 > E164_MAX_DIGITS {
+            return Err(PayloadError::TooLong {
+                field: "number",
+                max: E164_MAX_DIGITS,
+            });
+        }
+
+        Ok(Self { number })
+    }
+}
+
+impl fmt::Display for Phone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tel:{}", self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phone_invalid() {
+        assert_eq!(
+            Phone::new("555-CALL"),
+            Err(PayloadError::InvalidCharacter {
+                field: "number",
+                character: 'C'
+            })
+        );
+        assert!(Phone::new("12+3").is_err());
+        assert!(Phone::new(" - ").is_err());
+    }
+
+    #[test]
+    fn phone_e164() {
+        let e164 = |number| Phone::new(number).unwrap().e164(44).unwrap().to_string();
+        assert_eq!(e164("+44 20 7946 0958"), "tel:+442079460958");
+        assert_eq!(e164("0044 20 7946 0958"), "tel:+442079460958");
+        assert_eq!(e164("020 7946 0958"), "tel:+442079460958");
+        assert!(Phone::new("1234567890123456").unwrap().e164(1).is_err());
+    }
+}
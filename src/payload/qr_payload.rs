@@ -0,0 +1,187 @@
+//! Generic payload trait.
+
+use super::{
+    Binary, BoardingPass, CryptoUri, Email, EmvMerchant, EpcTransfer, Event, Geo, Hotp, Lightning,
+    Matter, Migration, PayloadError, Phone, SshKey, SwissQrBill, Totp, Upi, Url, VCard, Wifi,
+    WireGuard,
+};
+use crate::qr::{EcLevel, Mode, Qr};
+
+/// Encoded payload, with the recommended way to encode it as QR code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrPayload {
+    /// The raw payload data.
+    pub data: Vec<u8>,
+
+    /// The recommended error correction level.
+    pub ec_level: EcLevel,
+
+    /// The mode to encode all data in, or `None` to segment the data automatically.
+    pub mode: Option<Mode>,
+}
+
+impl QrPayload {
+    /// Construct a payload from the given `data`, using the default error correction level
+    /// and automatic segmentation.
+    pub fn new<D: Into<Vec<u8>>>(data: D) -> Self {
+        Self {
+            data: data.into(),
+            ec_level: EcLevel::M,
+            mode: None,
+        }
+    }
+
+    /// Set the recommended error correction level.
+    pub fn ec_level(mut self, ec_level: EcLevel) -> Self {
+        self.ec_level = ec_level;
+        self
+    }
+
+    /// Set the mode to encode all data in.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Encode this payload as QR code, as recommended.
+    pub fn to_qr(&self) -> Result<Qr, PayloadError> {
+        let qr = match self.mode {
+            Some(mode) => Qr::with_mode(&self.data, mode, self.ec_level)?,
+            None => Qr::with_error_correction_level(&self.data, self.ec_level)?,
+        };
+        Ok(qr)
+    }
+}
+
+/// A type that can be encoded as QR code payload.
+///
+/// Implemented by all payload builders in this module. Implement it for your own payload
+/// types to print them with [`print_qr_payload`](crate::print_qr_payload).
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{PayloadError, QrPayload, ToQrPayload};
+/// use qr2term::qr::EcLevel;
+///
+/// struct Ticket(u32);
+///
+/// impl ToQrPayload for Ticket {
+///     fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+///         Ok(QrPayload::new(format!("TICKET:{}", self.0)).ec_level(EcLevel::H))
+///     }
+/// }
+///
+/// qr2term::print_qr_payload(&Ticket(42)).unwrap();
+/// ```
+pub trait ToQrPayload {
+    /// Validate and encode this value as payload.
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError>;
+}
+
+impl ToQrPayload for QrPayload {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(self.clone())
+    }
+}
+
+impl ToQrPayload for str {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self))
+    }
+}
+
+impl ToQrPayload for String {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self.as_str()))
+    }
+}
+
+impl ToQrPayload for [u8] {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self))
+    }
+}
+
+/// Implement [`ToQrPayload`] for builders producing their payload through `Display`.
+macro_rules! impl_display {
+    ($($ty:ty),*) => {
+        $(
+            impl ToQrPayload for $ty {
+                fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+                    Ok(QrPayload::new(self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+/// Implement [`ToQrPayload`] for builders producing their payload through `build`.
+macro_rules! impl_build {
+    ($($ty:ty),*) => {
+        $(
+            impl ToQrPayload for $ty {
+                fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+                    Ok(QrPayload::new(self.build()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_display!(CryptoUri, Email, Geo, Hotp, Matter, Phone, SshKey, Totp, Upi, Url, VCard, Wifi);
+impl_build!(BoardingPass, EmvMerchant, EpcTransfer, Event, Migration);
+
+impl ToQrPayload for Binary {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self.to_string()).mode(self.mode()))
+    }
+}
+
+impl ToQrPayload for Lightning {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self.to_string()).mode(Mode::Alphanumeric))
+    }
+}
+
+impl ToQrPayload for SwissQrBill {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self.build()?).ec_level(SwissQrBill::ERROR_CORRECTION_LEVEL))
+    }
+}
+
+impl ToQrPayload for WireGuard {
+    fn to_qr_payload(&self) -> Result<QrPayload, PayloadError> {
+        Ok(QrPayload::new(self.build()?).ec_level(self.suggested_ec_level()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders_to_payload() {
+        let wifi = Wifi::new("Home").password("hunter2");
+        let payload = wifi.to_qr_payload().unwrap();
+        assert_eq!(payload.data, wifi.to_string().into_bytes());
+        assert_eq!(payload.ec_level, EcLevel::M);
+        assert_eq!(payload.mode, None);
+
+        let lightning = Lightning::new("lnbc1pvjluez").unwrap();
+        let payload = lightning.to_qr_payload().unwrap();
+        assert_eq!(payload.mode, Some(Mode::Alphanumeric));
+        assert!(payload.to_qr().is_ok());
+
+        assert!(EpcTransfer::new("", "").to_qr_payload().is_err());
+    }
+
+    #[test]
+    fn to_qr_encode_error() {
+        let payload = QrPayload::new("lowercase").mode(Mode::Numeric);
+        assert_eq!(
+            payload.to_qr().err(),
+            Some(PayloadError::Encode(crate::QrError::InvalidCharacter))
+        );
+    }
+}
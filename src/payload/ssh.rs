@@ -0,0 +1,164 @@
+//! SSH public key and host key fingerprint payloads.
+
+use std::fmt;
+
+use super::{base64, PayloadError};
+use crate::util;
+
+/// SSH public key, for transferring host keys to a phone or tablet for out-of-band
+/// verification.
+///
+/// The payload is the key in OpenSSH `known_hosts` format, optionally prefixed with the
+/// host name. Alternatively, a much smaller code holding just a fingerprint can be
+/// made using [`fingerprint`](Self::fingerprint) or [`sshfp`](Self::sshfp).
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::SshKey;
+///
+/// let key = SshKey::new(
+///     "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDMCT1OZce2kKa3swuQNEQwYMH8GzvBreoxH468twzGo",
+/// )
+/// .unwrap()
+/// .host("example.com");
+/// assert_eq!(
+///     key.fingerprint(),
+///     "SHA256:L3kctTBzb0VYxBr+Q9eszTqjCX1Gz1VisWPEEGpe6BY",
+/// );
+/// qr2term::print_qr(key.to_string()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshKey {
+    algorithm: String,
+    blob: Vec<u8>,
+    comment: Option<String>,
+    host: Option<String>,
+}
+
+impl SshKey {
+    /// Parse a public key in OpenSSH format, such as a line from `id_ed25519.pub` or
+    /// `ssh_host_ed25519_key.pub`.
+    pub fn new<S: AsRef<str>>(public_key: S) -> Result<Self, PayloadError> {
+        let mut parts = public_key.as_ref().split_whitespace();
+        let algorithm = parts.next().ok_or(PayloadError::Missing { field: "key" })?;
+        if sshfp_algorithm(algorithm).is_none() {
+            return Err(PayloadError::Invalid {
+                field: "key",
+                reason: "unsupported key algorithm",
+            });
+        }
+        let blob = parts
+            .next()
+            .and_then(base64::decode)
+            .ok_or(PayloadError::Invalid {
+                field: "key",
+                reason: "key data must be Base64 encoded",
+            })?;
+
+        // The key data starts with the length prefixed algorithm name
+        let name = blob
+            .get(0..4)
+            .map(|len| u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize)
+            .and_then(|len| blob.get(4..4 + len));
+        if name != Some(algorithm.as_bytes()) {
+            return Err(PayloadError::Invalid {
+                field: "key",
+                reason: "key data doesn't match key algorithm",
+            });
+        }
+
+        let comment = parts.collect::<Vec<_>>().join(" ");
+        Ok(Self {
+            algorithm: algorithm.into(),
+            blob,
+            comment: Some(comment).filter(|comment| !comment.is_empty()),
+            host: None,
+        })
+    }
+
+    /// Set the host name this key belongs to, formatting the payload as `known_hosts` line.
+    ///
+    /// The key comment is omitted if a host is set.
+    pub fn host<S: Into<String>>(mut self, host: S) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Get the SHA-256 fingerprint, in the format `ssh-keygen -l` shows.
+    pub fn fingerprint(&self) -> String {
+        let digest = base64::encode(&util::sha256(&self.blob));
+        format!("SHA256:{}", digest.trim_end_matches('='))
+    }
+
+    /// Get the SHA-256 fingerprint as SSHFP DNS record, in the format `ssh-keygen -r` shows.
+    ///
+    /// The record starts with the host name if set.
+    pub fn sshfp(&self) -> String {
+        let record = format!(
+            "IN SSHFP {} 2 {}",
+            sshfp_algorithm(&self.algorithm).unwrap(),
+            util::hex(&util::sha256(&self.blob)),
+        );
+        match &self.host {
+            Some(host) => format!("{} {}", host, record),
+            None => record,
+        }
+    }
+}
+
+impl fmt::Display for SshKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(host) = &self.host {
+            write!(f, "{} ", host)?;
+        }
+        write!(f, "{} {}", self.algorithm, base64::encode(&self.blob))?;
+        match (&self.host, &self.comment) {
+            (None, Some(comment)) => write!(f, " {}", comment),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Get the SSHFP algorithm number for the given key algorithm, as specified in RFC 4255
+/// and its successors.
+fn sshfp_algorithm(algorithm: &str) -> Option<u8> {
+    match algorithm {
+        "ssh-rsa" => Some(1),
+        "ssh-dss" => Some(2),
+        "ecdsa-sha2-nistp256" | "ecdsa-sha2-nistp384" | "ecdsa-sha2-nistp521" => Some(3),
+        "ssh-ed25519" => Some(4),
+        "ssh-ed448" => Some(6),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDMCT1OZce2kKa3swuQNEQwYMH8GzvBreoxH468twzGo";
+
+    #[test]
+    fn ssh_key_formats() {
+        let key = SshKey::new(format!("{} user@host", KEY)).unwrap();
+        assert_eq!(key.to_string(), format!("{} user@host", KEY));
+        assert_eq!(
+            key.sshfp(),
+            "IN SSHFP 4 2 2f791cb530736f4558c41afe43d7accd3aa3097d46cf5562b163c4106a5ee816"
+        );
+
+        let key = key.host("example.com");
+        assert_eq!(key.to_string(), format!("example.com {}", KEY));
+        assert!(key.sshfp().starts_with("example.com IN SSHFP 4 2 "));
+    }
+
+    #[test]
+    fn ssh_key_invalid() {
+        assert!(SshKey::new("").is_err());
+        assert!(SshKey::new("ssh-foo AAAA").is_err());
+        assert!(SshKey::new("ssh-ed25519 !!!!").is_err());
+        assert!(SshKey::new(KEY.replace("ssh-ed25519", "ssh-rsa")).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! Swiss QR-bill payment part payload.
+
+use super::epc::{check_length, iso7064_mod97, normalize_iban};
+use super::PayloadError;
+use crate::qr::EcLevel;
+
+/// Maximum size of the complete payload in characters.
+const MAX_PAYLOAD_SIZE: usize = 997;
+
+/// Maximum amount in cents.
+const MAX_AMOUNT_CENTS: u64 = 99_999_999_999;
+
+/// Currency of a Swiss QR-bill.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Currency {
+    /// Swiss franc.
+    #[default]
+    Chf,
+
+    /// Euro.
+    Eur,
+}
+
+/// Payment reference of a Swiss QR-bill.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum SwissReference {
+    /// 27 digit QR reference, requires a QR-IBAN.
+    Qr(String),
+
+    /// ISO 11649 creditor reference, starting with `RF`.
+    Creditor(String),
+
+    /// No reference.
+    #[default]
+    None,
+}
+
+/// Structured address of a creditor or debtor on a Swiss QR-bill.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SwissAddress {
+    /// Name or company, at most 70 characters.
+    pub name: String,
+
+    /// Street, at most 70 characters.
+    pub street: String,
+
+    /// Building number, at most 16 characters.
+    pub building_number: String,
+
+    /// Postal code, at most 16 characters.
+    pub postal_code: String,
+
+    /// Town, at most 35 characters.
+    pub town: String,
+
+    /// Two letter ISO 3166-1 country code, such as `CH`.
+    pub country: String,
+}
+
+/// Swiss QR-bill payment part, for paying an invoice by scanning.
+///
+/// Produces the Swiss Payment Standards QR-bill data structure, version 2.0, using
+/// structured addresses. Use [`build`](Self::build) to validate all fields and obtain the
+/// payload. The standard requires the code to use error correction level
+/// [`ERROR_CORRECTION_LEVEL`](Self::ERROR_CORRECTION_LEVEL).
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{SwissAddress, SwissQrBill};
+/// use qr2term::qr::Qr;
+///
+/// let creditor = SwissAddress {
+///     name: "Robert Schneider AG".into(),
+///     street: "Rue du Lac".into(),
+///     building_number: "1268".into(),
+///     postal_code: "2501".into(),
+///     town: "Biel".into(),
+///     country: "CH".into(),
+/// };
+/// let bill = SwissQrBill::new("CH93 0076 2011 6238 5295 7", creditor).amount_cents(19950);
+/// let qr = Qr::with_error_correction_level(
+///     bill.build().unwrap(),
+///     SwissQrBill::ERROR_CORRECTION_LEVEL,
+/// )
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwissQrBill {
+    iban: String,
+    creditor: SwissAddress,
+    amount_cents: Option<u64>,
+    currency: Currency,
+    debtor: Option<SwissAddress>,
+    reference: SwissReference,
+    message: Option<String>,
+    bill_information: Option<String>,
+}
+
+impl SwissQrBill {
+    /// Error correction level required by the standard.
+    pub const ERROR_CORRECTION_LEVEL: EcLevel = EcLevel::M;
+
+    /// Construct a QR-bill, paying to the given creditor `iban` and `creditor` address.
+    pub fn new<S: Into<String>>(iban: S, creditor: SwissAddress) -> Self {
+        Self {
+            iban: iban.into(),
+            creditor,
+            amount_cents: None,
+            currency: Currency::default(),
+            debtor: None,
+            reference: SwissReference::default(),
+            message: None,
+            bill_information: None,
+        }
+    }
+
+    /// Set the amount in cents, if not set the payer enters the amount.
+    pub fn amount_cents(mut self, amount_cents: u64) -> Self {
+        self.amount_cents = Some(amount_cents);
+        self
+    }
+
+    /// Set the currency, defaults to CHF.
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Set the address of the debtor paying the bill.
+    pub fn debtor(mut self, debtor: SwissAddress) -> Self {
+        self.debtor = Some(debtor);
+        self
+    }
+
+    /// Set the payment reference.
+    pub fn reference(mut self, reference: SwissReference) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Set the unstructured message.
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Set the structured bill information, such as Swico S1 data.
+    pub fn bill_information<S: Into<String>>(mut self, bill_information: S) -> Self {
+        self.bill_information = Some(bill_information.into());
+        self
+    }
+
+    /// Validate all fields, and build the payload.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        let iban = normalize_iban(&self.iban)?;
+        if !iban.starts_with("CH") && !iban.starts_with("LI") || iban.len() != 21 {
+            return Err(PayloadError::Invalid {
+                field: "iban",
+                reason: "IBAN must be a Swiss or Liechtenstein IBAN",
+            });
+        }
+
+        // A QR-IBAN has an institution identification in the range 30000 to 31999
+        let qr_iban = matches!(iban[4..9].parse::<u32>(), Ok(30000..=31999));
+        let (reference_type, reference) = match &self.reference {
+            SwissReference::Qr(reference) => {
+                if !qr_iban {
+                    return Err(PayloadError::Invalid {
+                        field: "reference",
+                        reason: "QR reference requires a QR-IBAN",
+                    });
+                }
+                ("QRR", normalize_qr_reference(reference)?)
+            }
+            SwissReference::Creditor(reference) => {
+                if qr_iban {
+                    return Err(PayloadError::Invalid {
+                        field: "reference",
+                        reason: "QR-IBAN requires a QR reference",
+                    });
+                }
+                ("SCOR", normalize_creditor_reference(reference)?)
+            }
+            SwissReference::None => {
+                if qr_iban {
+                    return Err(PayloadError::Invalid {
+                        field: "reference",
+                        reason: "QR-IBAN requires a QR reference",
+                    });
+                }
+                ("NON", String::new())
+            }
+        };
+
+        let amount = match self.amount_cents {
+            None => String::new(),
+            Some(cents) if cents == 0 || cents > MAX_AMOUNT_CENTS => {
+                return Err(PayloadError::Invalid {
+                    field: "amount",
+                    reason: "amount must be between 0.01 and 999999999.99",
+                })
+            }
+            Some(cents) => format!("{}.{:02}", cents / 100, cents % 100),
+        };
+        let currency = match self.currency {
+            Currency::Chf => "CHF",
+            Currency::Eur => "EUR",
+        };
+
+        let message = self.message.as_deref().unwrap_or_default();
+        let bill_information = self.bill_information.as_deref().unwrap_or_default();
+        check_length("message", message, 0, 140)?;
+        check_length("bill_information", bill_information, 0, 140)?;
+        if message.chars().count() + bill_information.chars().count() > 140 {
+            return Err(PayloadError::TooLong {
+                field: "message",
+                max: 140,
+            });
+        }
+
+        // Header: QR type, version 2.0 and UTF-8 coding
+        let mut fields = vec!["SPC".to_string(), "0200".into(), "1".into(), iban];
+        push_address(&mut fields, Some(&self.creditor))?;
+
+        // Ultimate creditor, reserved for future use
+        fields.extend(std::iter::repeat(String::new()).take(7));
+
+        fields.push(amount);
+        fields.push(currency.into());
+        push_address(&mut fields, self.debtor.as_ref())?;
+        fields.push(reference_type.into());
+        fields.push(reference);
+        fields.push(message.into());
+        fields.push("EPD".into());
+        if !bill_information.is_empty() {
+            fields.push(bill_information.into());
+        }
+
+        let payload = fields.join("\r\n");
+        if payload.chars().count() > MAX_PAYLOAD_SIZE {
+            return Err(PayloadError::TooLong {
+                field: "payload",
+                max: MAX_PAYLOAD_SIZE,
+            });
+        }
+        Ok(payload)
+    }
+}
+
+/// Validate and push the seven address fields, or empty fields if there's no address.
+fn push_address(
+    fields: &mut Vec<String>,
+    address: Option<&SwissAddress>,
+) -> Result<(), PayloadError> {
+    let address = match address {
+        Some(address) => address,
+        None => {
+            fields.extend(std::iter::repeat(String::new()).take(7));
+            return Ok(());
+        }
+    };
+
+    check_length("name", &address.name, 1, 70)?;
+    check_length("street", &address.street, 0, 70)?;
+    check_length("building_number", &address.building_number, 0, 16)?;
+    check_length("postal_code", &address.postal_code, 1, 16)?;
+    check_length("town", &address.town, 1, 35)?;
+    if address.country.len() != 2 || !address.country.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(PayloadError::Invalid {
+            field: "country",
+            reason: "country must be a two letter uppercase ISO 3166-1 code",
+        });
+    }
+
+    // Address type S for structured addresses
+    fields.extend([
+        "S".into(),
+        address.name.clone(),
+        address.street.clone(),
+        address.building_number.clone(),
+        address.postal_code.clone(),
+        address.town.clone(),
+        address.country.clone(),
+    ]);
+    Ok(())
+}
+
+/// Normalize a QR reference by removing spaces, and validate its format and check digit.
+fn normalize_qr_reference(reference: &str) -> Result<String, PayloadError> {
+    const TABLE: [u32; 10] = [0, 9, 4, 6, 8, 2, 7, 1, 3, 5];
+
+    let reference: String = reference.chars().filter(|c| *c != ' ').collect();
+    if let Some(character) = reference.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(PayloadError::InvalidCharacter {
+            field: "reference",
+            character,
+        });
+    }
+    if reference.len() != 27 {
+        return Err(PayloadError::Invalid {
+            field: "reference",
+            reason: "QR reference must be 27 digits long",
+        });
+    }
+
+    // Check digit using recursive modulo 10
+    let carry = reference[..26].chars().fold(0, |carry, c| {
+        TABLE[((carry + c.to_digit(10).unwrap()) % 10) as usize]
+    });
+    if (10 - carry) % 10 != reference[26..].parse().unwrap() {
+        return Err(PayloadError::Invalid {
+            field: "reference",
+            reason: "QR reference check digit is incorrect",
+        });
+    }
+
+    Ok(reference)
+}
+
+/// Normalize a creditor reference by removing spaces, and validate its format and checksum.
+fn normalize_creditor_reference(reference: &str) -> Result<String, PayloadError> {
+    let reference: String = reference
+        .chars()
+        .filter(|c| *c != ' ')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if let Some(character) = reference.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(PayloadError::InvalidCharacter {
+            field: "reference",
+            character,
+        });
+    }
+    if !reference.starts_with("RF") || !(5..=25).contains(&reference.len()) {
+        return Err(PayloadError::Invalid {
+            field: "reference",
+            reason: "creditor reference must start with RF and be 5 to 25 characters long",
+        });
+    }
+    if iso7064_mod97(&reference) != 1 {
+        return Err(PayloadError::Invalid {
+            field: "reference",
+            reason: "creditor reference checksum is incorrect",
+        });
+    }
+
+    Ok(reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creditor() -> SwissAddress {
+        SwissAddress {
+            name: "Robert Schneider AG".into(),
+            street: "Rue du Lac".into(),
+            building_number: "1268".into(),
+            postal_code: "2501".into(),
+            town: "Biel".into(),
+            country: "CH".into(),
+        }
+    }
+
+    #[test]
+    fn swiss_qr_reference() {
+        let bill = SwissQrBill::new("CH44 3199 9123 0008 8901 2", creditor())
+            .amount_cents(19995)
+            .reference(SwissReference::Qr(
+                "21 00000 00003 13947 14300 09017".into(),
+            ))
+            .message("Order of 15 June");
+        assert_eq!(
+            bill.build().unwrap(),
+            [
+                "SPC",
+                "0200",
+                "1",
+                "CH4431999123000889012",
+                "S",
+                "Robert Schneider AG",
+                "Rue du Lac",
+                "1268",
+                "2501",
+                "Biel",
+                "CH",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "199.95",
+                "CHF",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "QRR",
+                "210000000003139471430009017",
+                "Order of 15 June",
+                "EPD",
+            ]
+            .join("\r\n")
+        );
+    }
+
+    #[test]
+    fn swiss_creditor_reference() {
+        let bill = SwissQrBill::new("CH9300762011623852957", creditor())
+            .currency(Currency::Eur)
+            .debtor(creditor())
+            .reference(SwissReference::Creditor("RF18 5390 0754 7034".into()));
+        let payload = bill.build().unwrap();
+        assert!(payload.contains("\r\nEUR\r\nS\r\nRobert Schneider AG\r\n"));
+        assert!(payload.ends_with("\r\nSCOR\r\nRF18539007547034\r\n\r\nEPD"));
+    }
+
+    #[test]
+    fn swiss_invalid() {
+        let qr_iban = "CH4431999123000889012";
+        let iban = "CH9300762011623852957";
+        assert!(SwissQrBill::new(qr_iban, creditor()).build().is_err());
+        assert!(SwissQrBill::new(iban, creditor())
+            .reference(SwissReference::Qr("210000000003139471430009017".into()))
+            .build()
+            .is_err());
+        assert!(SwissQrBill::new(qr_iban, creditor())
+            .reference(SwissReference::Qr("210000000003139471430009018".into()))
+            .build()
+            .is_err());
+        assert!(SwissQrBill::new(iban, creditor())
+            .reference(SwissReference::Creditor("RF19539007547034".into()))
+            .build()
+            .is_err());
+        assert!(SwissQrBill::new("DE89370400440532013000", creditor())
+            .build()
+            .is_err());
+        assert!(SwissQrBill::new(iban, SwissAddress::default())
+            .build()
+            .is_err());
+    }
+}
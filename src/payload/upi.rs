@@ -0,0 +1,132 @@
+//! UPI payment `upi://pay` deep link payload.
+
+use std::fmt;
+
+use super::percent::{self, COMPONENT};
+use super::PayloadError;
+
+/// UPI payment request, for paying from an Indian payments app by scanning.
+///
+/// Produces a `upi://pay?...` deep link as specified by NPCI, understood by all UPI apps.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::Upi;
+///
+/// let upi = Upi::new("shop@okbank", "Corner Shop")
+///     .unwrap()
+///     .amount_paise(4950)
+///     .note("Order 42");
+/// assert_eq!(
+///     upi.to_string(),
+///     "upi://pay?pa=shop@okbank&pn=Corner%20Shop&am=49.50&cu=INR&tn=Order%2042",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upi {
+    vpa: String,
+    name: String,
+    amount_paise: Option<u64>,
+    note: Option<String>,
+    transaction_ref: Option<String>,
+}
+
+impl Upi {
+    /// Construct a payment request to the payee with the given virtual payment address and
+    /// `name`.
+    ///
+    /// Fails if `vpa` isn't a valid virtual payment address, such as `name@bank`.
+    pub fn new<V: AsRef<str>, N: Into<String>>(vpa: V, name: N) -> Result<Self, PayloadError> {
+        let vpa = vpa.as_ref().trim();
+        let valid = match vpa.split_once('@') {
+            Some((user, handle)) => {
+                !user.is_empty()
+                    && !handle.is_empty()
+                    && user
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+                    && handle.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            None => false,
+        };
+        if !valid {
+            return Err(PayloadError::Invalid {
+                field: "vpa",
+                reason: "virtual payment address must be formatted as name@handle",
+            });
+        }
+
+        Ok(Self {
+            vpa: vpa.into(),
+            name: name.into(),
+            amount_paise: None,
+            note: None,
+            transaction_ref: None,
+        })
+    }
+
+    /// Set the amount in paise, if not set the payer enters the amount.
+    pub fn amount_paise(mut self, amount_paise: u64) -> Self {
+        self.amount_paise = Some(amount_paise);
+        self
+    }
+
+    /// Set the transaction note shown to the payer.
+    pub fn note<S: Into<String>>(mut self, note: S) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Set the transaction reference, such as an order or invoice number.
+    pub fn transaction_ref<S: Into<String>>(mut self, transaction_ref: S) -> Self {
+        self.transaction_ref = Some(transaction_ref.into());
+        self
+    }
+}
+
+impl fmt::Display for Upi {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "upi://pay?pa={}&pn={}",
+            percent::encode(&self.vpa, b"@"),
+            percent::encode(&self.name, COMPONENT),
+        )?;
+        if let Some(paise) = self.amount_paise {
+            write!(f, "&am={}.{:02}&cu=INR", paise / 100, paise % 100)?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, "&tn={}", percent::encode(note, COMPONENT))?;
+        }
+        if let Some(transaction_ref) = &self.transaction_ref {
+            write!(f, "&tr={}", percent::encode(transaction_ref, COMPONENT))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upi_minimal() {
+        let upi = Upi::new("jane.doe-1@upi", "Jane & Co")
+            .unwrap()
+            .transaction_ref("INV/7");
+        assert_eq!(
+            upi.to_string(),
+            "upi://pay?pa=jane.doe-1@upi&pn=Jane%20%26%20Co&tr=INV%2F7"
+        );
+    }
+
+    #[test]
+    fn upi_invalid_vpa() {
+        assert!(Upi::new("janedoe", "Jane").is_err());
+        assert!(Upi::new("@upi", "Jane").is_err());
+        assert!(Upi::new("jane@", "Jane").is_err());
+        assert!(Upi::new("ja ne@upi", "Jane").is_err());
+        assert!(Upi::new("jane@up@i", "Jane").is_err());
+    }
+}
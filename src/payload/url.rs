@@ -0,0 +1,377 @@
+//! Validated URL payload.
+
+use std::fmt;
+use std::ops::Range;
+
+use super::PayloadError;
+use crate::qr::Qr;
+
+/// Query parameters only used for tracking, which can be dropped without changing the page.
+const TRACKING_PARAMS: [&str; 4] = ["fbclid", "gclid", "mc_eid", "msclkid"];
+
+/// A change to a URL that results in a smaller QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// Uppercase the scheme and host, allowing the more compact alphanumeric mode.
+    Uppercase,
+
+    /// Drop `utm_*` and other tracking query parameters.
+    DropTrackingParams,
+
+    /// Drop the `#fragment`.
+    DropFragment,
+
+    /// Drop a trailing slash.
+    DropTrailingSlash,
+}
+
+/// A suggested change to a URL, and the QR code size it would result in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The suggested change.
+    pub change: Change,
+
+    /// The URL with the change applied.
+    pub url: String,
+
+    /// The width of the QR code for the changed URL, in modules.
+    pub size: usize,
+}
+
+/// URL, validated before encoding to prevent codes that don't open when scanned.
+///
+/// Requires the URL to have a scheme and to contain no whitespace. Use
+/// [`normalize`](Self::normalize) to clean up user input first, and
+/// [`suggestions`](Self::suggestions) to find changes that make the QR code smaller.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{Change, Url};
+///
+/// let url = Url::normalize("  HTTPS://Bücher.example/?utm_source=qr ").unwrap();
+/// assert_eq!(url.to_string(), "https://xn--bcher-kva.example/?utm_source=qr");
+///
+/// assert!(Url::new("example.com").is_err());
+///
+/// for suggestion in url.suggestions() {
+///     println!("{:?} gives a {} module code", suggestion.change, suggestion.size);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    url: String,
+}
+
+impl Url {
+    /// Construct from the given `url`, validating it has a scheme and no whitespace.
+    pub fn new<S: Into<String>>(url: S) -> Result<Self, PayloadError> {
+        let url = url.into();
+        if url.is_empty() {
+            return Err(PayloadError::Missing { field: "url" });
+        }
+        if let Some(character) = url.chars().find(|c| c.is_whitespace() || c.is_control()) {
+            return Err(PayloadError::InvalidCharacter {
+                field: "url",
+                character,
+            });
+        }
+        scheme_len(&url).ok_or(PayloadError::Invalid {
+            field: "url",
+            reason: "URL must start with a scheme, such as https:",
+        })?;
+        Ok(Self { url })
+    }
+
+    /// Construct from the given `url` after normalizing it.
+    ///
+    /// Surrounding whitespace is trimmed, the scheme and host are lowercased, and
+    /// internationalized host names are converted to their ASCII Punycode form.
+    pub fn normalize<S: AsRef<str>>(url: S) -> Result<Self, PayloadError> {
+        let url = Self::new(url.as_ref().trim())?.url;
+        let scheme = scheme_len(&url).unwrap_or_default();
+        let mut normalized = url[..scheme].to_ascii_lowercase();
+        match host_range(&url) {
+            Some(host) => {
+                normalized.push_str(&url[scheme..host.start]);
+                normalized.push_str(&to_ascii_host(&url[host.clone()])?);
+                normalized.push_str(&url[host.end..]);
+            }
+            None => normalized.push_str(&url[scheme..]),
+        }
+        Ok(Self { url: normalized })
+    }
+
+    /// Find changes to this URL that would result in a smaller QR code.
+    ///
+    /// Each change is evaluated on its own. Changes that don't reduce the size are omitted.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let current = match code_size(&self.url) {
+            Some(size) => size,
+            None => return Vec::new(),
+        };
+
+        let changes = [
+            (Change::Uppercase, self.uppercase()),
+            (Change::DropTrackingParams, self.drop_tracking_params()),
+            (
+                Change::DropFragment,
+                self.url.split('#').next().map(String::from),
+            ),
+            (
+                Change::DropTrailingSlash,
+                self.url.strip_suffix('/').map(String::from),
+            ),
+        ];
+        changes
+            .into_iter()
+            .filter_map(|(change, url)| {
+                let url = url.filter(|url| *url != self.url)?;
+                let size = code_size(&url).filter(|size| *size < current)?;
+                Some(Suggestion { change, url, size })
+            })
+            .collect()
+    }
+
+    /// The URL with its scheme and host uppercased.
+    fn uppercase(&self) -> Option<String> {
+        let host = host_range(&self.url)?;
+        Some(format!(
+            "{}{}",
+            self.url[..host.end].to_ascii_uppercase(),
+            &self.url[host.end..]
+        ))
+    }
+
+    /// The URL without tracking query parameters.
+    fn drop_tracking_params(&self) -> Option<String> {
+        let (base, rest) = self.url.split_once('?')?;
+        let (query, fragment) = match rest.split_once('#') {
+            Some((query, fragment)) => (query, Some(fragment)),
+            None => (rest, None),
+        };
+        let query: Vec<&str> = query
+            .split('&')
+            .filter(|param| {
+                let key = param.split('=').next().unwrap_or_default();
+                !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key)
+            })
+            .collect();
+
+        let mut url = base.to_string();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        if let Some(fragment) = fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        Some(url)
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+/// Get the length of the scheme including the colon, if the URL starts with a valid scheme.
+fn scheme_len(url: &str) -> Option<usize> {
+    let colon = url.find(':')?;
+    let scheme = &url[..colon];
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if valid {
+        Some(colon + 1)
+    } else {
+        None
+    }
+}
+
+/// Get the byte range of the host in the URL, if it has an authority.
+fn host_range(url: &str) -> Option<Range<usize>> {
+    let start = scheme_len(url)?;
+    if !url[start..].starts_with("//") {
+        return None;
+    }
+    let start = start + 2;
+    let end = url[start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |end| start + end);
+
+    // Skip user info, and strip the port unless the host is an IPv6 address
+    let start = url[start..end]
+        .rfind('@')
+        .map_or(start, |at| start + at + 1);
+    let end = match url[start..end].rfind(':') {
+        Some(colon) if !url[start + colon..end].contains(']') => start + colon,
+        _ => end,
+    };
+    Some(start..end)
+}
+
+/// Convert a host name to lowercase ASCII, encoding internationalized labels with Punycode.
+fn to_ascii_host(host: &str) -> Result<String, PayloadError> {
+    let labels: Option<Vec<String>> = host
+        .split('.')
+        .map(|label| {
+            let label = label.to_lowercase();
+            if label.is_ascii() {
+                Some(label)
+            } else {
+                punycode(&label).map(|label| format!("xn--{}", label))
+            }
+        })
+        .collect();
+    labels
+        .map(|labels| labels.join("."))
+        .ok_or(PayloadError::Invalid {
+            field: "url",
+            reason: "host name can't be converted to Punycode",
+        })
+}
+
+/// Encode a label with Punycode, as specified in RFC 3492.
+fn punycode(input: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+
+    fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+        let mut delta = if first { delta / 700 } else { delta / 2 };
+        delta += delta / points;
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        k + (BASE - T_MIN + 1) * delta / (delta + 38)
+    }
+
+    fn digit(d: u32) -> char {
+        match d {
+            0..=25 => (b'a' + d as u8) as char,
+            _ => (b'0' + (d - 26) as u8) as char,
+        }
+    }
+
+    let input: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut out: String = input
+        .iter()
+        .filter(|c| **c < 0x80)
+        .map(|c| *c as u8 as char)
+        .collect();
+    let basic = out.len() as u32;
+    if basic > 0 {
+        out.push('-');
+    }
+
+    let (mut n, mut delta, mut bias, mut handled) = (0x80, 0u32, 72, basic);
+    while (handled as usize) < input.len() {
+        let m = *input.iter().filter(|c| **c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in &input {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = if k <= bias {
+                        T_MIN
+                    } else if k >= bias + T_MAX {
+                        T_MAX
+                    } else {
+                        k - bias
+                    };
+                    if q < t {
+                        break;
+                    }
+                    out.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                out.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    Some(out)
+}
+
+/// Get the width of the QR code for the given data, if it fits.
+fn code_size(data: &str) -> Option<usize> {
+    Qr::from(data).ok().map(|qr| qr.to_matrix().size())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_validate() {
+        assert!(Url::new("https://example.com/").is_ok());
+        assert!(Url::new("mailto:jane@example.com").is_ok());
+        assert_eq!(
+            Url::new("").err(),
+            Some(PayloadError::Missing { field: "url" })
+        );
+        assert!(Url::new("example.com/path").is_err());
+        assert!(Url::new("1http://example.com").is_err());
+        assert_eq!(
+            Url::new("https://example.com/a b").err(),
+            Some(PayloadError::InvalidCharacter {
+                field: "url",
+                character: ' '
+            })
+        );
+    }
+
+    #[test]
+    fn url_normalize() {
+        let url = Url::normalize(" HTTP://User@MÜNCHEN.Example:8080/Path?Q#F\n").unwrap();
+        assert_eq!(
+            url.to_string(),
+            "http://User@xn--mnchen-3ya.example:8080/Path?Q#F"
+        );
+        let url = Url::normalize("https://[::1]:443/").unwrap();
+        assert_eq!(url.to_string(), "https://[::1]:443/");
+    }
+
+    #[test]
+    fn punycode_vectors() {
+        assert_eq!(punycode("bücher").unwrap(), "bcher-kva");
+        assert_eq!(punycode("münchen").unwrap(), "mnchen-3ya");
+        assert_eq!(
+            punycode("ليهمابتكلموشعربي؟").unwrap(),
+            "egbpdaj6bu4bxfgehfvwxn"
+        );
+    }
+
+    #[test]
+    fn url_suggestions() {
+        let url = Url::new(
+            "https://example.com/some/page/?utm_source=newsletter&utm_medium=email&id=1#top",
+        )
+        .unwrap();
+        let suggestions = url.suggestions();
+        let tracking = suggestions
+            .iter()
+            .find(|s| s.change == Change::DropTrackingParams)
+            .unwrap();
+        assert_eq!(tracking.url, "https://example.com/some/page/?id=1#top");
+        assert!(tracking.size < code_size(&url.to_string()).unwrap());
+
+        assert!(Url::new("https://a.b").unwrap().suggestions().is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+//! vCard contact payload.
+
+use std::fmt;
+
+/// vCard format version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VCardVersion {
+    /// vCard 3.0, as specified in RFC 2426. Supported by nearly all scanners.
+    #[default]
+    V3,
+
+    /// vCard 4.0, as specified in RFC 6350.
+    V4,
+}
+
+/// Postal address of a contact.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Address {
+    /// Street address, including the house number.
+    pub street: String,
+
+    /// City or locality.
+    pub city: String,
+
+    /// Region, state or province.
+    pub region: String,
+
+    /// Postal code.
+    pub postal_code: String,
+
+    /// Country name.
+    pub country: String,
+}
+
+/// Contact card, for sharing contact details by scanning.
+///
+/// Serializes into a vCard 3.0 or 4.0 string.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::VCard;
+///
+/// let card = VCard::new("Jane Doe")
+///     .org("Example Corp.")
+///     .phone("+31612345678")
+///     .email("jane@example.com");
+/// qr2term::print_qr(card.to_string()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VCard {
+    version: VCardVersion,
+    name: String,
+    structured_name: Option<(String, String)>,
+    org: Option<String>,
+    phones: Vec<String>,
+    emails: Vec<String>,
+    url: Option<String>,
+    address: Option<Address>,
+}
+
+impl VCard {
+    /// Construct a contact card with the given formatted display `name`.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            version: VCardVersion::default(),
+            name: name.into(),
+            structured_name: None,
+            org: None,
+            phones: Vec::new(),
+            emails: Vec::new(),
+            url: None,
+            address: None,
+        }
+    }
+
+    /// Set the vCard version, defaults to 3.0.
+    pub fn version(mut self, version: VCardVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Set the structured name, split into `family` and `given` name.
+    ///
+    /// If not set, the display name is used as family name.
+    pub fn structured_name<F: Into<String>, G: Into<String>>(
+        mut self,
+        family: F,
+        given: G,
+    ) -> Self {
+        self.structured_name = Some((family.into(), given.into()));
+        self
+    }
+
+    /// Set the organization.
+    pub fn org<S: Into<String>>(mut self, org: S) -> Self {
+        self.org = Some(org.into());
+        self
+    }
+
+    /// Add a phone number.
+    pub fn phone<S: Into<String>>(mut self, phone: S) -> Self {
+        self.phones.push(phone.into());
+        self
+    }
+
+    /// Add an email address.
+    pub fn email<S: Into<String>>(mut self, email: S) -> Self {
+        self.emails.push(email.into());
+        self
+    }
+
+    /// Set the website URL.
+    pub fn url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Set the postal address.
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+}
+
+impl fmt::Display for VCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Lines are separated by CRLF as required by the specification
+        write!(f, "BEGIN:VCARD\r\n")?;
+        match self.version {
+            VCardVersion::V3 => write!(f, "VERSION:3.0\r\n")?,
+            VCardVersion::V4 => write!(f, "VERSION:4.0\r\n")?,
+        }
+
+        // The structured name is required in 3.0, but optional in 4.0
+        match &self.structured_name {
+            Some((family, given)) => write!(f, "N:{};{};;;\r\n", escape(family), escape(given))?,
+            None if self.version == VCardVersion::V3 => {
+                write!(f, "N:{};;;;\r\n", escape(&self.name))?
+            }
+            None => {}
+        }
+        write!(f, "FN:{}\r\n", escape(&self.name))?;
+
+        if let Some(org) = &self.org {
+            write!(f, "ORG:{}\r\n", escape(org))?;
+        }
+        for phone in &self.phones {
+            write!(f, "TEL:{}\r\n", escape(phone))?;
+        }
+        for email in &self.emails {
+            write!(f, "EMAIL:{}\r\n", escape(email))?;
+        }
+        if let Some(url) = &self.url {
+            write!(f, "URL:{}\r\n", escape(url))?;
+        }
+        if let Some(address) = &self.address {
+            write!(
+                f,
+                "ADR:;;{};{};{};{};{}\r\n",
+                escape(&address.street),
+                escape(&address.city),
+                escape(&address.region),
+                escape(&address.postal_code),
+                escape(&address.country),
+            )?;
+        }
+
+        write!(f, "END:VCARD")
+    }
+}
+
+/// Escape a text value, as specified for vCard and iCalendar property values.
+pub(super) fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vcard_v3() {
+        let card = VCard::new("Jane Doe")
+            .structured_name("Doe", "Jane")
+            .org("Example, Inc.")
+            .phone("+31612345678")
+            .email("jane@example.com")
+            .url("https://example.com/")
+            .address(Address {
+                street: "Main Street 1".into(),
+                city: "Springfield".into(),
+                country: "USA".into(),
+                ..Default::default()
+            });
+        assert_eq!(
+            card.to_string(),
+            "BEGIN:VCARD\r\n\
+            VERSION:3.0\r\n\
+            N:Doe;Jane;;;\r\n\
+            FN:Jane Doe\r\n\
+            ORG:Example\\, Inc.\r\n\
+            TEL:+31612345678\r\n\
+            EMAIL:jane@example.com\r\n\
+            URL:https://example.com/\r\n\
+            ADR:;;Main Street 1;Springfield;;;USA\r\n\
+            END:VCARD"
+        );
+    }
+
+    #[test]
+    fn vcard_v4_without_structured_name() {
+        let card = VCard::new("Jane;\nDoe").version(VCardVersion::V4);
+        assert_eq!(
+            card.to_string(),
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\\;\\nDoe\r\nEND:VCARD"
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! WireGuard tunnel configuration payload.
+
+use super::PayloadError;
+use crate::qr::{EcLevel, Qr, MAX_DATA_LEN};
+
+/// Width in modules of the largest code that still prints and scans comfortably,
+/// being version 20.
+const COMFORTABLE_WIDTH: usize = 97;
+
+/// Peer of a WireGuard tunnel.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WireGuardPeer {
+    /// Base64 encoded public key of the peer.
+    pub public_key: String,
+
+    /// Base64 encoded preshared key, optional.
+    pub preshared_key: Option<String>,
+
+    /// Endpoint of the peer, such as `vpn.example.com:51820`.
+    pub endpoint: Option<String>,
+
+    /// IP ranges routed through this peer, such as `0.0.0.0/0, ::/0`.
+    pub allowed_ips: String,
+
+    /// Persistent keepalive interval in seconds, optional.
+    pub persistent_keepalive: Option<u16>,
+}
+
+/// WireGuard tunnel configuration, for importing a tunnel into the mobile apps by scanning.
+///
+/// Produces the `wg-quick` configuration format, which the official Android and iOS
+/// apps import from a QR code. Use [`build`](Self::build) to validate and obtain the
+/// payload.
+///
+/// Tunnel configurations are relatively large, so codes tend to be dense. Use
+/// [`suggested_ec_level`](Self::suggested_ec_level) to get the highest error correction
+/// level that still results in a comfortably sized code.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::payload::{WireGuard, WireGuardPeer};
+/// use qr2term::qr::Qr;
+///
+/// let tunnel = WireGuard::new("yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=", "10.0.0.2/32")
+///     .dns("1.1.1.1")
+///     .peer(WireGuardPeer {
+///         public_key: "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=".into(),
+///         endpoint: Some("vpn.example.com:51820".into()),
+///         allowed_ips: "0.0.0.0/0".into(),
+///         ..Default::default()
+///     });
+/// let ec_level = tunnel.suggested_ec_level().unwrap();
+/// let qr = Qr::with_error_correction_level(tunnel.build().unwrap(), ec_level).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WireGuard {
+    private_key: String,
+    address: String,
+    dns: Option<String>,
+    listen_port: Option<u16>,
+    mtu: Option<u16>,
+    peers: Vec<WireGuardPeer>,
+}
+
+impl WireGuard {
+    /// Construct a tunnel configuration with the given interface `private_key` and `address`.
+    pub fn new<K: Into<String>, A: Into<String>>(private_key: K, address: A) -> Self {
+        Self {
+            private_key: private_key.into(),
+            address: address.into(),
+            dns: None,
+            listen_port: None,
+            mtu: None,
+            peers: Vec::new(),
+        }
+    }
+
+    /// Parse an existing `wg-quick` configuration, such as the contents of `wg0.conf`.
+    ///
+    /// Comments and settings the mobile apps don't support are dropped, which also keeps
+    /// the code small.
+    pub fn from_config(config: &str) -> Result<Self, PayloadError> {
+        let mut tunnel = Self::new("", "");
+
+        // Peer of the current section, or `None` in the interface section
+        let mut peer: Option<WireGuardPeer> = None;
+        for line in config.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let interface = line.eq_ignore_ascii_case("[Interface]");
+            if interface || line.eq_ignore_ascii_case("[Peer]") {
+                tunnel.peers.extend(peer.take());
+                if !interface {
+                    peer = Some(WireGuardPeer::default());
+                }
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(PayloadError::Invalid {
+                field: "config",
+                reason: "configuration line must be a section or key = value",
+            })?;
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim().to_string());
+            match &mut peer {
+                None => match key.as_str() {
+                    "privatekey" => tunnel.private_key = value,
+                    "address" => tunnel.address = value,
+                    "dns" => tunnel.dns = Some(value),
+                    "listenport" => tunnel.listen_port = Some(parse_number(&value, "listen_port")?),
+                    "mtu" => tunnel.mtu = Some(parse_number(&value, "mtu")?),
+                    _ => {}
+                },
+                Some(peer) => match key.as_str() {
+                    "publickey" => peer.public_key = value,
+                    "presharedkey" => peer.preshared_key = Some(value),
+                    "endpoint" => peer.endpoint = Some(value),
+                    "allowedips" => peer.allowed_ips = value,
+                    "persistentkeepalive" => {
+                        peer.persistent_keepalive =
+                            Some(parse_number(&value, "persistent_keepalive")?)
+                    }
+                    _ => {}
+                },
+            }
+        }
+        tunnel.peers.extend(peer);
+        Ok(tunnel)
+    }
+
+    /// Set the DNS servers, such as `1.1.1.1, 1.0.0.1`.
+    pub fn dns<S: Into<String>>(mut self, dns: S) -> Self {
+        self.dns = Some(dns.into());
+        self
+    }
+
+    /// Set the port to listen on.
+    pub fn listen_port(mut self, listen_port: u16) -> Self {
+        self.listen_port = Some(listen_port);
+        self
+    }
+
+    /// Set the interface MTU.
+    pub fn mtu(mut self, mtu: u16) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    /// Add a peer.
+    pub fn peer(mut self, peer: WireGuardPeer) -> Self {
+        self.peers.push(peer);
+        self
+    }
+
+    /// Validate the configuration, and build the payload.
+    pub fn build(&self) -> Result<String, PayloadError> {
+        check_key(&self.private_key, "private_key")?;
+        if self.address.is_empty() {
+            return Err(PayloadError::Missing { field: "address" });
+        }
+        check_text(&self.address, "address")?;
+        if let Some(dns) = &self.dns {
+            check_text(dns, "dns")?;
+        }
+        if self.peers.is_empty() {
+            return Err(PayloadError::Missing { field: "peer" });
+        }
+
+        let mut out = format!(
+            "[Interface]\nPrivateKey = {}\nAddress = {}\n",
+            self.private_key, self.address
+        );
+        if let Some(dns) = &self.dns {
+            out.push_str(&format!("DNS = {}\n", dns));
+        }
+        if let Some(listen_port) = self.listen_port {
+            out.push_str(&format!("ListenPort = {}\n", listen_port));
+        }
+        if let Some(mtu) = self.mtu {
+            out.push_str(&format!("MTU = {}\n", mtu));
+        }
+
+        for peer in &self.peers {
+            check_key(&peer.public_key, "public_key")?;
+            if peer.allowed_ips.is_empty() {
+                return Err(PayloadError::Missing {
+                    field: "allowed_ips",
+                });
+            }
+            check_text(&peer.allowed_ips, "allowed_ips")?;
+            if let Some(endpoint) = &peer.endpoint {
+                check_text(endpoint, "endpoint")?;
+            }
+            out.push_str(&format!("\n[Peer]\nPublicKey = {}\n", peer.public_key));
+            if let Some(preshared_key) = &peer.preshared_key {
+                check_key(preshared_key, "preshared_key")?;
+                out.push_str(&format!("PresharedKey = {}\n", preshared_key));
+            }
+            out.push_str(&format!("AllowedIPs = {}\n", peer.allowed_ips));
+            if let Some(endpoint) = &peer.endpoint {
+                out.push_str(&format!("Endpoint = {}\n", endpoint));
+            }
+            if let Some(keepalive) = peer.persistent_keepalive {
+                out.push_str(&format!("PersistentKeepalive = {}\n", keepalive));
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Suggest the highest error correction level that keeps the code comfortably sized.
+    ///
+    /// Higher levels make the code more robust when printed or shown on a poor screen.
+    /// Falls back to the lowest level if the code is large regardless, and fails if the
+    /// configuration doesn't fit in a QR code at all.
+    pub fn suggested_ec_level(&self) -> Result<EcLevel, PayloadError> {
+        let payload = self.build()?;
+        for ec_level in [EcLevel::H, EcLevel::Q, EcLevel::M] {
+            if let Ok(qr) = Qr::with_error_correction_level(&payload, ec_level) {
+                if qr.to_matrix().size() <= COMFORTABLE_WIDTH {
+                    return Ok(ec_level);
+                }
+            }
+        }
+        match Qr::with_error_correction_level(&payload, EcLevel::L) {
+            Ok(_) => Ok(EcLevel::L),
+            Err(_) => Err(PayloadError::TooLong {
+                field: "config",
+                max: MAX_DATA_LEN,
+            }),
+        }
+    }
+}
+
+/// Check a key is a Base64 encoded 32 byte key.
+fn check_key(key: &str, field: &'static str) -> Result<(), PayloadError> {
+    if key.is_empty() {
+        return Err(PayloadError::Missing { field });
+    }
+    let valid = key.len() == 44
+        && key.ends_with('=')
+        && key[..43]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/');
+    if !valid {
+        return Err(PayloadError::Invalid {
+            field,
+            reason: "key must be a Base64 encoded 32 byte key",
+        });
+    }
+    Ok(())
+}
+
+/// Check a configuration value doesn't contain control characters, which could add lines.
+fn check_text(value: &str, field: &'static str) -> Result<(), PayloadError> {
+    match value.chars().find(|c| c.is_control()) {
+        Some(character) => Err(PayloadError::InvalidCharacter { field, character }),
+        None => Ok(()),
+    }
+}
+
+/// Parse a numeric configuration value.
+fn parse_number(value: &str, field: &'static str) -> Result<u16, PayloadError> {
+    value.parse().map_err(|_| PayloadError::Invalid {
+        field,
+        reason: "value must be a number",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=";
+    const PUBLIC_KEY: &str = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+
+    #[test]
+    fn wireguard_from_config() {
+        let config = format!(
+            "# Home tunnel\n\
+            [Interface]\n\
+            PrivateKey = {}\n\
+            Address = 10.0.0.2/32\n\
+            PostUp = iptables -A FORWARD\n\
+            \n\
+            [Peer]\n\
+            PublicKey={}\n\
+            AllowedIPs = 0.0.0.0/0 # everything\n\
+            Endpoint = vpn.example.com:51820\n\
+            PersistentKeepalive = 25\n",
+            PRIVATE_KEY, PUBLIC_KEY
+        );
+        let tunnel = WireGuard::from_config(&config).unwrap();
+        assert_eq!(
+            tunnel.build().unwrap(),
+            format!(
+                "[Interface]\n\
+                PrivateKey = {}\n\
+                Address = 10.0.0.2/32\n\
+                \n\
+                [Peer]\n\
+                PublicKey = {}\n\
+                AllowedIPs = 0.0.0.0/0\n\
+                Endpoint = vpn.example.com:51820\n\
+                PersistentKeepalive = 25\n",
+                PRIVATE_KEY, PUBLIC_KEY
+            )
+        );
+    }
+
+    #[test]
+    fn wireguard_invalid() {
+        assert!(WireGuard::new(PRIVATE_KEY, "10.0.0.2/32").build().is_err());
+        let peer = WireGuardPeer {
+            public_key: PUBLIC_KEY.into(),
+            allowed_ips: "0.0.0.0/0".into(),
+            ..Default::default()
+        };
+        assert!(WireGuard::new("short=", "10.0.0.2/32")
+            .peer(peer.clone())
+            .build()
+            .is_err());
+        assert!(WireGuard::new(PRIVATE_KEY, "10.0.0.2/32")
+            .peer(peer.clone())
+            .build()
+            .is_ok());
+        assert_eq!(
+            WireGuard::new(PRIVATE_KEY, "10.0.0.2/32\nPostUp = rm -rf ~")
+                .peer(peer.clone())
+                .build(),
+            Err(PayloadError::InvalidCharacter {
+                field: "address",
+                character: '\n'
+            })
+        );
+        let endpoint = WireGuardPeer {
+            endpoint: Some("vpn.example.com:51820\r\nPostUp = x".into()),
+            ..peer
+        };
+        assert_eq!(
+            WireGuard::new(PRIVATE_KEY, "10.0.0.2/32")
+                .peer(endpoint)
+                .build(),
+            Err(PayloadError::InvalidCharacter {
+                field: "endpoint",
+                character: '\r'
+            })
+        );
+        assert!(WireGuard::from_config("[Interface]\nbogus").is_err());
+
+        let config = "[Peer]\nPublicKey = a\n[Interface]\nAddress = b\n[Peer]\nPublicKey = c\n";
+        let tunnel = WireGuard::from_config(config).unwrap();
+        assert_eq!(tunnel.address, "b");
+        assert_eq!(tunnel.peers.len(), 2);
+    }
+
+    #[test]
+    fn wireguard_suggested_ec_level() {
+        let peer = WireGuardPeer {
+            public_key: PUBLIC_KEY.into(),
+            allowed_ips: "0.0.0.0/0".into(),
+            ..Default::default()
+        };
+        let tunnel = WireGuard::new(PRIVATE_KEY, "10.0.0.2/32").peer(peer.clone());
+        assert_eq!(tunnel.suggested_ec_level(), Ok(EcLevel::H));
+
+        let tunnel = (0..40).fold(tunnel, |tunnel, _| tunnel.peer(peer.clone()));
+        assert!(tunnel.suggested_ec_level().is_err());
+    }
+}
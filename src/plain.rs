@@ -0,0 +1,169 @@
+//! Plain text rendering, without any escape sequences.
+//!
+//! Available without the `std` feature, to generate barcode text for serial consoles and
+//! other environments without a terminal emulator. Glyphs are the same as those of a
+//! [`Renderer`](crate::render::Renderer) with colors disabled.
+
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use crate::matrix::Matrix;
+
+/// How barcode pixels are mapped onto terminal characters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Density {
+    /// One character holds two pixels above each other, using half block glyphs.
+    #[default]
+    HalfBlock,
+
+    /// Two characters hold one pixel, for terminals or fonts without block glyphs.
+    ///
+    /// Without colors, dark pixels are drawn using `#`, so the output is plain ASCII.
+    Full,
+
+    /// One character holds eight pixels in a 2 by 4 grid, using braille glyphs.
+    ///
+    /// Gives the smallest output, but leaves gaps between dots that some scanners
+    /// have trouble with.
+    Braille,
+}
+
+/// Pixel of a barcode matrix, being either dark or light.
+pub trait Pixel {
+    /// Whether this pixel is dark.
+    fn is_dark(&self) -> bool;
+}
+
+impl Pixel for bool {
+    fn is_dark(&self) -> bool {
+        *self
+    }
+}
+
+#[cfg(feature = "std")]
+impl Pixel for crate::render::Color {
+    fn is_dark(&self) -> bool {
+        *self == crate::render::QrDark
+    }
+}
+
+/// Render a matrix describing a 2D barcode as plain text to the given target.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::matrix::Matrix;
+/// use qr2term::plain::{self, Density};
+///
+/// let matrix = Matrix::new(vec![true, false, false, true]);
+/// let mut text = String::new();
+/// plain::render(&matrix, Density::HalfBlock, &mut text).unwrap();
+/// assert_eq!(text, "▀▄\n");
+/// ```
+pub fn render<T: Pixel, W: Write>(
+    matrix: &Matrix<T>,
+    density: Density,
+    target: &mut W,
+) -> fmt::Result {
+    let width = matrix.size();
+    let dark =
+        |x: usize, y: usize| x < width && y < width && matrix.pixels()[y * width + x].is_dark();
+
+    match density {
+        Density::HalfBlock => {
+            for y in (0..width).step_by(2) {
+                for x in 0..width {
+                    let glyph = match (dark(x, y), dark(x, y + 1)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    };
+                    target.write_char(glyph)?;
+                }
+                target.write_char('\n')?;
+            }
+        }
+        Density::Full => {
+            for y in 0..width {
+                for x in 0..width {
+                    target.write_str(if dark(x, y) { "##" } else { "  " })?;
+                }
+                target.write_char('\n')?;
+            }
+        }
+        Density::Braille => {
+            // Braille dot bits, indexed by row and column within the character
+            const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+            for y in (0..width).step_by(4) {
+                for x in (0..width).step_by(2) {
+                    let mut bits = 0;
+                    for (dy, dots) in DOTS.iter().enumerate() {
+                        for (dx, dot) in dots.iter().enumerate() {
+                            if dark(x + dx, y + dy) {
+                                bits |= dot;
+                            }
+                        }
+                    }
+                    target.write_char(char::from_u32(0x2800 + bits).unwrap_or(' '))?;
+                }
+                target.write_char('\n')?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Render a matrix describing a 2D barcode as plain text.
+pub fn to_string<T: Pixel>(matrix: &Matrix<T>, density: Density) -> String {
+    let mut text = String::new();
+    // Writing to a string never fails
+    let _ = render(matrix, density, &mut text);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn densities() {
+        let matrix = Matrix::new(vec![
+            true, false, true, false, true, false, true, true, true,
+        ]);
+        assert_eq!(to_string(&matrix, Density::HalfBlock), "▀▄▀\n▀▀▀\n");
+        assert_eq!(
+            to_string(&matrix, Density::Full),
+            "##  ##\n  ##  \n######\n"
+        );
+        assert_eq!(to_string(&matrix, Density::Braille), "\u{2835}\u{2805}\n");
+    }
+
+    /// Plain output must match the renderer without colors.
+    #[test]
+    #[cfg(feature = "std")]
+    fn matches_renderer() {
+        use crate::render::{QrDark, QrLight, Renderer};
+
+        let pixels = [
+            1, 0, 1, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0, 1, 1, 0, 0, 1, 1, 0, 1, 0, 1,
+        ];
+        let matrix = Matrix::new(
+            pixels
+                .iter()
+                .map(|p| if *p == 1 { QrDark } else { QrLight })
+                .collect(),
+        );
+        for density in [Density::HalfBlock, Density::Full, Density::Braille] {
+            let mut buf = Vec::new();
+            Renderer::default()
+                .density(density)
+                .colors(false)
+                .render(&matrix, &mut buf)
+                .unwrap();
+            assert_eq!(to_string(&matrix, density).as_bytes(), buf.as_slice());
+        }
+    }
+}
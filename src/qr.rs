@@ -1,12 +1,24 @@
 //! QR code type.
 
-use qrcode::{types::Color, QrCode};
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
 
-use super::QrError;
-use crate::Matrix;
+use qrcode::{bits::Bits, types::Color, QrCode, Version};
+pub use qrcode::{types::Mode, EcLevel};
+
+use super::{Error, QrError};
+use crate::plain::{self, Density};
+use crate::{Matrix, QUIET_ZONE_WIDTH};
+
+/// Maximum number of bytes a QR code can hold, in a version 40 code with error correction
+/// level [`EcLevel::L`].
+pub const MAX_DATA_LEN: usize = 2953;
 
 /// Raw QR code.
-#[allow(missing_debug_implementations)]
+///
+/// Formatting with [`Display`](fmt::Display) gives the plain text representation, with
+/// a quiet zone and without any escape sequences.
 pub struct Qr {
     code: QrCode,
 }
@@ -20,12 +32,175 @@ impl Qr {
         })
     }
 
+    /// Construct a new QR code from the data read from `reader`, until its end.
+    ///
+    /// Reads at most `max_len` bytes, which is capped at [`MAX_DATA_LEN`]. Fails with
+    /// [`QrError::DataTooLong`] if there is more, or the data doesn't fit, and with
+    /// [`Error::Io`] if reading failed.
+    pub fn from_reader<R: Read>(reader: R, max_len: usize) -> Result<Self, Error> {
+        Ok(Self::from(read_limited(reader, max_len)?)?)
+    }
+
+    /// Construct a new QR code, using the given error correction level.
+    ///
+    /// A higher level makes the code more resilient to damage or bad rendering,
+    /// at the cost of a larger code.
+    pub fn with_error_correction_level<D: AsRef<[u8]>>(
+        data: D,
+        ec_level: EcLevel,
+    ) -> Result<Self, QrError> {
+        Ok(Self {
+            code: QrCode::with_error_correction_level(data.as_ref(), ec_level)?,
+        })
+    }
+
+    /// Construct a new QR code with the given `version` from 1 to 40, and error correction level.
+    ///
+    /// The version determines the size of the code, being `17 + version * 4` pixels wide.
+    /// Fails with [`QrError::DataTooLong`] if the data doesn't fit, and
+    /// [`QrError::InvalidVersion`] if the version is out of range.
+    pub fn with_version<D: AsRef<[u8]>>(
+        data: D,
+        version: i16,
+        ec_level: EcLevel,
+    ) -> Result<Self, QrError> {
+        if !(1..=40).contains(&version) {
+            return Err(QrError::InvalidVersion);
+        }
+        Ok(Self {
+            code: QrCode::with_version(data.as_ref(), Version::Normal(version), ec_level)?,
+        })
+    }
+
+    /// Construct a new QR code, encoding all data in the given `mode`.
+    ///
+    /// This skips automatic segmentation and encodes all data as a single segment,
+    /// choosing the smallest version it fits in. Fails with
+    /// [`QrError::InvalidCharacter`] if the data contains characters the mode can't encode.
+    pub fn with_mode<D: AsRef<[u8]>>(
+        data: D,
+        mode: Mode,
+        ec_level: EcLevel,
+    ) -> Result<Self, QrError> {
+        let data = data.as_ref();
+        if !fits_mode(data, mode) {
+            return Err(QrError::InvalidCharacter);
+        }
+
+        for version in 1..=40 {
+            let mut bits = Bits::new(Version::Normal(version));
+            let result = match mode {
+                Mode::Numeric => bits.push_numeric_data(data),
+                Mode::Alphanumeric => bits.push_alphanumeric_data(data),
+                Mode::Byte => bits.push_byte_data(data),
+                Mode::Kanji => bits.push_kanji_data(data),
+            }
+            .and_then(|_| bits.push_terminator(ec_level));
+            match result {
+                Ok(()) => {
+                    return Ok(Self {
+                        code: QrCode::with_bits(bits, ec_level)?,
+                    })
+                }
+                Err(QrError::DataTooLong) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Err(QrError::DataTooLong)
+    }
+
+    /// Get the error correction level of this QR code.
+    pub fn error_correction_level(&self) -> EcLevel {
+        self.code.error_correction_level()
+    }
+
+    /// Get the version of this QR code, from 1 to 40.
+    ///
+    /// The version determines the size of the code, being `17 + version * 4` pixels wide.
+    pub fn version(&self) -> i16 {
+        match self.code.version() {
+            Version::Normal(version) | Version::Micro(version) => version,
+        }
+    }
+
     /// Create pixel matrix from this QR code.
     pub fn to_matrix(&self) -> Matrix<Color> {
         Matrix::new(self.code.to_colors())
     }
 }
 
+impl FromStr for Qr {
+    type Err = QrError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        Self::from(data)
+    }
+}
+
+impl TryFrom<&str> for Qr {
+    type Error = QrError;
+
+    fn try_from(data: &str) -> Result<Self, Self::Error> {
+        Self::from(data)
+    }
+}
+
+impl TryFrom<&[u8]> for Qr {
+    type Error = QrError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::from(data)
+    }
+}
+
+impl fmt::Display for Qr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut matrix = self.to_matrix();
+        matrix.surround(QUIET_ZONE_WIDTH, crate::render::QrLight);
+        plain::render(&matrix, Density::HalfBlock, f)
+    }
+}
+
+impl fmt::Debug for Qr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Qr")
+            .field("version", &self.version())
+            .field("error_correction_level", &self.error_correction_level())
+            .field("width", &self.code.width())
+            .finish()
+    }
+}
+
+/// Read all data from `reader`, failing if there are more than `max_len` bytes or more than
+/// fit in a QR code.
+pub(crate) fn read_limited<R: Read>(reader: R, max_len: usize) -> Result<Vec<u8>, Error> {
+    let limit = max_len.min(MAX_DATA_LEN);
+    let mut data = Vec::new();
+    reader.take(limit as u64 + 1).read_to_end(&mut data)?;
+    if data.len() > limit {
+        return Err(QrError::DataTooLong.into());
+    }
+    Ok(data)
+}
+
+/// Check whether all of the given data can be encoded in the given mode.
+fn fits_mode(data: &[u8], mode: Mode) -> bool {
+    match mode {
+        Mode::Numeric => data.iter().all(u8::is_ascii_digit),
+        Mode::Alphanumeric => data
+            .iter()
+            .all(|b| matches!(b, b'0'..=b'9' | b'A'..=b'Z' | b' ' | b'$' | b'%' | b'*' | b'+' | b'-' | b'.' | b'/' | b':')),
+        Mode::Byte => true,
+        Mode::Kanji => {
+            data.len() % 2 == 0
+                && data.chunks(2).all(|pair| {
+                    let c = u16::from_be_bytes([pair[0], pair[1]]);
+                    (0x8140..=0x9FFC).contains(&c) || (0xE040..=0xEBBF).contains(&c)
+                })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,4 +211,80 @@ mod tests {
     fn print_qr_too_long() {
         Qr::from(String::from_utf8(vec![b'a'; 8000]).unwrap()).unwrap();
     }
+
+    #[test]
+    fn display() {
+        let qr = Qr::from("a").unwrap();
+        let text = qr.to_string();
+        assert_eq!(text.lines().count(), (21 + 2 * QUIET_ZONE_WIDTH + 1) / 2);
+        assert!(text.lines().all(|line| line.chars().count() == 25));
+        assert!(!text.contains('\x1b'));
+        assert_eq!(
+            format!("{:?}", qr),
+            "Qr { version: 1, error_correction_level: M, width: 21 }"
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let size = |qr: Qr| qr.to_matrix().size();
+        assert_eq!(size("hello".parse().unwrap()), 21);
+        assert_eq!(size(Qr::try_from("hello").unwrap()), 21);
+        assert_eq!(size(Qr::try_from(&b"hello"[..]).unwrap()), 21);
+        assert_eq!(
+            "a".repeat(8000).parse::<Qr>().err(),
+            Some(QrError::DataTooLong)
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let qr = Qr::from_reader(&b"hello"[..], 100).unwrap();
+        assert_eq!(qr.to_string(), Qr::from("hello").unwrap().to_string());
+        assert!(Qr::from_reader(&b"hello"[..], 5).is_ok());
+        assert!(matches!(
+            Qr::from_reader(&b"hello"[..], 4),
+            Err(Error::Qr(QrError::DataTooLong))
+        ));
+
+        // Capped at the capacity, without reading everything
+        let mut reader = std::io::repeat(b'a');
+        assert!(matches!(
+            Qr::from_reader(&mut reader, usize::MAX),
+            Err(Error::Qr(QrError::DataTooLong))
+        ));
+    }
+
+    #[test]
+    fn with_mode() {
+        let qr = Qr::with_mode("HELLO WORLD", Mode::Alphanumeric, EcLevel::M).unwrap();
+        assert_eq!(qr.to_matrix().size(), 21);
+        assert_eq!(
+            Qr::with_mode("hello", Mode::Alphanumeric, EcLevel::M).err(),
+            Some(QrError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn with_version() {
+        let qr = Qr::with_version("a", 5, EcLevel::L).unwrap();
+        assert_eq!(qr.to_matrix().size(), 37);
+        assert_eq!(qr.version(), 5);
+        assert_eq!(Qr::from("a").unwrap().version(), 1);
+        assert_eq!(
+            Qr::with_version("a".repeat(100), 1, EcLevel::L).err(),
+            Some(QrError::DataTooLong)
+        );
+        assert_eq!(
+            Qr::with_version("a", 41, EcLevel::L).err(),
+            Some(QrError::InvalidVersion)
+        );
+    }
+
+    #[test]
+    fn error_correction_level() {
+        assert_eq!(Qr::from("a").unwrap().error_correction_level(), EcLevel::M);
+        let qr = Qr::with_error_correction_level("a", EcLevel::H).unwrap();
+        assert_eq!(qr.error_correction_level(), EcLevel::H);
+    }
 }
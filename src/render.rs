@@ -1,66 +1,877 @@
 //! Rendering utilities.
 
-use std::io::{self, Result as IoResult, Write};
+use std::io::{self, IoSlice, Result as IoResult, Write};
+use std::sync::Arc;
+use std::{error, fmt};
 
-use crossterm::style::Stylize;
+#[cfg(feature = "crossterm")]
+use crossterm::queue;
+#[cfg(feature = "crossterm")]
+use crossterm::style::{
+    style, Color as CrosstermColor, Print, SetBackgroundColor, SetForegroundColor, Stylize,
+};
 pub use qrcode::types::Color::{self, Dark as QrDark, Light as QrLight};
 
+use crate::linear::Bars;
 use crate::matrix::Matrix;
+pub use crate::plain::Density;
+use crate::term::{self, Capabilities, ColorDepth};
+
+/// Terminal color, used to draw barcode pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermColor {
+    /// The terminal's black color.
+    Black,
+
+    /// The terminal's bright white color.
+    White,
+
+    /// A color from the 256 color ANSI palette.
+    Ansi(u8),
+
+    /// A 24 bit RGB color, for terminals supporting true color.
+    Rgb(u8, u8, u8),
+}
+
+impl TermColor {
+    /// Approximate RGB value of this color, using the xterm palette for ANSI colors.
+    pub(crate) fn rgb(self) -> (u8, u8, u8) {
+        #[rustfmt::skip]
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+            (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+            (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+            (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+        ];
+        const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        match self {
+            TermColor::Black => BASIC[0],
+            TermColor::White => BASIC[15],
+            TermColor::Ansi(value @ 0..=15) => BASIC[value as usize],
+            TermColor::Ansi(value @ 16..=231) => {
+                let value = value as usize - 16;
+                (LEVELS[value / 36], LEVELS[value / 6 % 6], LEVELS[value % 6])
+            }
+            TermColor::Ansi(value) => {
+                let gray = 8 + (value - 232) * 10;
+                (gray, gray, gray)
+            }
+            TermColor::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// Get the closest color the terminal can draw with the given color depth.
+    ///
+    /// RGB colors become the closest color of the 256 color palette, or any color the
+    /// closest of the 16 basic colors. Without colors, the basic colors are used too.
+    pub fn downgrade(self, depth: ColorDepth) -> TermColor {
+        let palette = match (depth, self) {
+            (ColorDepth::TrueColor, _) | (_, TermColor::Black | TermColor::White) => return self,
+            (ColorDepth::Ansi256, TermColor::Ansi(_)) => return self,
+            (_, TermColor::Ansi(value)) if value < 16 => return self,
+            (ColorDepth::Ansi256, _) => 16..=255,
+            (ColorDepth::Ansi16 | ColorDepth::None, _) => 0..=15,
+        };
+
+        let (r, g, b) = self.rgb();
+        let distance = |color: &TermColor| {
+            let (r2, g2, b2) = color.rgb();
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, r2) + d(g, g2) + d(b, b2)
+        };
+        palette
+            .map(TermColor::Ansi)
+            .min_by_key(distance)
+            .unwrap_or(self)
+    }
+
+    /// Relative luminance of this color from 0 for black to 1 for white, as defined by WCAG.
+    pub fn luminance(self) -> f64 {
+        let linear = |channel: u8| {
+            let channel = channel as f64 / 255.0;
+            if channel <= 0.03928 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = self.rgb();
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    /// Write the SGR color parameters following the `38;` or `48;` prefix.
+    #[cfg(not(feature = "crossterm"))]
+    fn write_sgr(self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TermColor::Black => write!(f, "5;0"),
+            TermColor::White => write!(f, "5;15"),
+            TermColor::Ansi(value) => write!(f, "5;{}", value),
+            TermColor::Rgb(r, g, b) => write!(f, "2;{};{};{}", r, g, b),
+        }
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl From<TermColor> for CrosstermColor {
+    fn from(color: TermColor) -> Self {
+        match color {
+            TermColor::Black => CrosstermColor::Black,
+            TermColor::White => CrosstermColor::White,
+            TermColor::Ansi(value) => CrosstermColor::AnsiValue(value),
+            TermColor::Rgb(r, g, b) => CrosstermColor::Rgb { r, g, b },
+        }
+    }
+}
+
+/// Colors used to draw dark and light barcode pixels.
+///
+/// Scanners need enough contrast between both colors, with dark pixels being darker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Color of dark pixels.
+    pub dark: TermColor,
+
+    /// Color of light pixels.
+    pub light: TermColor,
+}
+
+impl Theme {
+    /// Black on white, using the terminal's own palette.
+    pub const CLASSIC: Theme = Theme {
+        dark: TermColor::Black,
+        light: TermColor::White,
+    };
+
+    /// Names of all built-in themes, for use with [`Theme::named`].
+    pub const NAMES: [&'static str; 4] = ["classic", "solarized", "gruvbox", "nord"];
+
+    /// Get a built-in theme by name.
+    pub fn named(name: &str) -> Option<Theme> {
+        let (dark, light) = match name {
+            "classic" => return Some(Theme::CLASSIC),
+            "solarized" => ((0x00, 0x2B, 0x36), (0xFD, 0xF6, 0xE3)),
+            "gruvbox" => ((0x28, 0x28, 0x28), (0xFB, 0xF1, 0xC7)),
+            "nord" => ((0x2E, 0x34, 0x40), (0xEC, 0xEF, 0xF4)),
+            _ => return None,
+        };
+        Some(Theme {
+            dark: TermColor::Rgb(dark.0, dark.1, dark.2),
+            light: TermColor::Rgb(light.0, light.1, light.2),
+        })
+    }
+}
+
+impl Theme {
+    /// Contrast ratio between both colors, from 1 for equal colors to 21 for black and white.
+    pub fn contrast_ratio(&self) -> f64 {
+        let (dark, light) = (self.dark.luminance(), self.light.luminance());
+        (dark.max(light) + 0.05) / (dark.min(light) + 0.05)
+    }
+
+    /// Check whether barcodes drawn with these colors are likely to scan.
+    pub fn warnings(&self) -> Vec<ColorWarning> {
+        let mut warnings = Vec::new();
+        let ratio = self.contrast_ratio();
+        if ratio < MIN_CONTRAST {
+            warnings.push(ColorWarning::LowContrast(ratio));
+        }
+        if self.dark.luminance() > self.light.luminance() {
+            warnings.push(ColorWarning::InvertedPolarity);
+        }
+        warnings
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::CLASSIC
+    }
+}
+
+/// Minimum contrast ratio between dark and light pixels for barcodes to scan reliably.
+pub const MIN_CONTRAST: f64 = 3.0;
+
+/// Problem with the colors of a barcode, making it hard or impossible to scan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorWarning {
+    /// The contrast ratio between dark and light pixels is below [`MIN_CONTRAST`].
+    LowContrast(f64),
+
+    /// Dark pixels are lighter than light pixels, which many scanners can't read.
+    InvertedPolarity,
+}
+
+impl fmt::Display for ColorWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorWarning::LowContrast(ratio) => write!(
+                f,
+                "color contrast ratio of {:.1}:1 is too low to scan reliably, use at least {}:1",
+                ratio, MIN_CONTRAST
+            ),
+            ColorWarning::InvertedPolarity => f.write_str(
+                "dark pixels are lighter than light pixels, which many scanners can't read",
+            ),
+        }
+    }
+}
+
+impl error::Error for ColorWarning {}
+
+/// Statistics about a rendered barcode.
+///
+/// Returned by [`Renderer::render_with_info`], describing exactly what was emitted so
+/// callers can log it or lay out other output around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderInfo {
+    /// Number of terminal columns (characters per line) the rendering takes.
+    pub cols: usize,
+
+    /// Number of terminal rows (lines) the rendering takes.
+    pub rows: usize,
+
+    /// Number of bytes written to the target, including escape codes.
+    pub bytes_written: usize,
+
+    /// How pixels were mapped onto characters.
+    pub density: Density,
+
+    /// Width of the quiet zone included in the rendering, in pixels.
+    pub quiet_zone: usize,
+}
 
 /// QR barcode terminal renderer intended for terminals.
-#[derive(Debug, Default)]
-pub struct Renderer {}
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    density: Density,
+    colors: bool,
+    theme: Theme,
+    invert: bool,
+    scale: usize,
+    bar_height: usize,
+    strict: bool,
+    color_depth: ColorDepth,
+    even_height: bool,
+    max_width: Option<usize>,
+    row_hook: Option<RowHook>,
+
+    /// Whether output goes to the console, to style through crossterm commands.
+    #[cfg_attr(not(feature = "crossterm"), allow(dead_code))]
+    console: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            density: Density::default(),
+            colors: true,
+            theme: Theme::default(),
+            invert: false,
+            scale: 1,
+            bar_height: 5,
+            strict: false,
+            color_depth: ColorDepth::TrueColor,
+            even_height: false,
+            max_width: None,
+            row_hook: None,
+            console: false,
+        }
+    }
+}
 
 impl Renderer {
+    /// Construct a renderer tuned to the [capabilities](term::capabilities) of the
+    /// terminal on stdout.
+    ///
+    /// Picks the densest glyphs the terminal can draw: braille with a UTF-8 encoding,
+    /// half blocks with Unicode support, and ASCII otherwise. Terminals drawing gaps
+    /// between half blocks get ASCII as well. Colors are used if the terminal supports
+    /// them, downgraded to its color depth. The terminal size isn't considered.
+    ///
+    /// Set the [density](Renderer::density) afterwards to pin a mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use qr2term::render::{Density, Renderer};
+    ///
+    /// let renderer = Renderer::auto();
+    /// let pinned = Renderer::auto().density(Density::HalfBlock);
+    /// ```
+    pub fn auto() -> Self {
+        let caps = term::capabilities();
+        Self::default()
+            .density(auto_density(&caps, term::locale_utf8()))
+            .colors(caps.color != ColorDepth::None)
+            .color_depth(caps.color)
+    }
+
+    /// Set how pixels are mapped onto terminal characters, defaults to half blocks.
+    pub fn density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Set whether to use ANSI colors, enabled by default.
+    ///
+    /// Colors draw the barcode dark on light regardless of the terminal theme. Without
+    /// colors, glyphs represent dark pixels, which requires a light terminal background
+    /// to scan.
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Set the colors to draw pixels with, defaults to [`Theme::CLASSIC`].
+    ///
+    /// Only used when colors are enabled.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set whether to swap dark and light pixels.
+    ///
+    /// Without colors, this makes the barcode scannable on a dark terminal background.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Set how many times each pixel is repeated horizontally and vertically, defaults
+    /// to 1.
+    ///
+    /// Makes the barcode bigger, for scanning from a distance. Rendering fails with
+    /// [`Error::InvalidOption`](crate::Error::InvalidOption) if `scale` is zero.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the height of one-dimensional barcodes in rows, defaults to 5.
+    ///
+    /// Multiplied by the [scale](Renderer::scale). Rendering fails with
+    /// [`Error::InvalidOption`](crate::Error::InvalidOption) if `rows` is zero.
+    pub fn bar_height(mut self, rows: usize) -> Self {
+        self.bar_height = rows;
+        self
+    }
+
+    /// Set whether to pad codes with an odd pixel height with a light pixel row at the
+    /// bottom, disabled by default.
+    ///
+    /// Half blocks draw two pixel rows per line and braille four, so they always pad the
+    /// last line of odd codes with light pixels. This pads full density output the same
+    /// way, so layouts get the same cell composition and an even pixel height for every
+    /// density.
+    pub fn even_height(mut self, even_height: bool) -> Self {
+        self.even_height = even_height;
+        self
+    }
+
+    /// Set a callback invoked with the index and content of each rendered row of a 2D
+    /// barcode, before it is written.
+    ///
+    /// Rows include the styling escape sequences, but not the line ending. The returned
+    /// string is written instead, or nothing if `None`. Lets callers wrap rows in their
+    /// own framing, log them or skip some, without reimplementing the renderer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use qr2term::qr::Qr;
+    /// use qr2term::render::Renderer;
+    ///
+    /// let matrix = Qr::from("https://rust-lang.org/").unwrap().to_matrix();
+    /// Renderer::default()
+    ///     .row_hook(|_, row| Some(format!("| {} |", row)))
+    ///     .print_stdout(&matrix)
+    ///     .unwrap();
+    /// ```
+    pub fn row_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(usize, &str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.row_hook = Some(RowHook(Arc::new(hook)));
+        self
+    }
+
+    /// Set the maximum width of the output in columns, unlimited by default.
+    ///
+    /// Rendering a barcode wider than this fails with an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error wrapping
+    /// [`Error::TooWide`](crate::Error::TooWide), which converts into it, instead of
+    /// letting the terminal wrap lines. Meant for fixed width layouts.
+    pub fn max_width(mut self, columns: usize) -> Self {
+        self.max_width = Some(columns);
+        self
+    }
+
+    /// Set the colors the terminal can draw, defaults to [`ColorDepth::TrueColor`].
+    ///
+    /// Theme colors the terminal can't draw are replaced with the closest one it can, see
+    /// [`TermColor::downgrade`]. Printing to the terminal uses its detected
+    /// [capabilities](term::capabilities) instead.
+    pub fn color_depth(mut self, depth: ColorDepth) -> Self {
+        self.color_depth = depth;
+        self
+    }
+
+    /// Get a copy with the theme downgraded to the color depth, if that changes it.
+    fn downgraded(&self) -> Option<Self> {
+        let theme = Theme {
+            dark: self.theme.dark.downgrade(self.color_depth),
+            light: self.theme.light.downgrade(self.color_depth),
+        };
+        if theme == self.theme {
+            return None;
+        }
+        Some(self.clone().theme(theme))
+    }
+
+    /// Set whether to refuse rendering with colors that are hard to scan, disabled by
+    /// default.
+    ///
+    /// In strict mode, rendering fails with an [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// error wrapping [`Error::InvalidOption`](crate::Error::InvalidOption), describing the
+    /// first of the [warnings](Renderer::warnings).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Check whether barcodes drawn by this renderer are likely to scan.
+    ///
+    /// Takes inverting and the [color depth](Renderer::color_depth) into account, and is
+    /// always empty without colors as the terminal colors are unknown.
+    pub fn warnings(&self) -> Vec<ColorWarning> {
+        if !self.colors {
+            return Vec::new();
+        }
+        let theme = self
+            .downgraded()
+            .map_or(self.theme, |renderer| renderer.theme);
+        let theme = if self.invert {
+            Theme {
+                dark: theme.light,
+                light: theme.dark,
+            }
+        } else {
+            theme
+        };
+        theme.warnings()
+    }
+
+    /// Fail if an option is out of range.
+    fn check_options(&self) -> IoResult<()> {
+        let invalid = match (self.scale, self.bar_height) {
+            (0, _) => "scale must be at least 1",
+            (_, 0) => "bar height must be at least 1",
+            _ => return Ok(()),
+        };
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            crate::Error::InvalidOption(invalid.into()),
+        ))
+    }
+
+    /// Fail in strict mode if the colors are hard to scan.
+    fn check_colors(&self) -> IoResult<()> {
+        match self.warnings().first() {
+            Some(warning) if self.strict => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                crate::Error::InvalidOption(warning.to_string()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail if output `pixels` wide exceeds the maximum width.
+    fn check_width(&self, pixels: usize) -> IoResult<()> {
+        let needed = self.columns(pixels);
+        match self.max_width {
+            Some(max) if needed > max => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                crate::Error::TooWide { needed, max },
+            )),
+            _ => Ok(()),
+        }
+    }
+
     /// Print a matrix describing a 2D barcode to the given writer.
     pub fn render<W: Write>(&self, matrix: &Matrix<Color>, target: &mut W) -> IoResult<()> {
-        let width = matrix.size();
-        let pixels = matrix.pixels();
+        self.render_with_info(matrix, target).map(|_| ())
+    }
 
-        for row in 0..width / 2 {
-            for col in 0..width {
-                let vec_pos = (row * 2) * width + col;
-                let vec_pos_below = (row * 2 + 1) * width + col;
-                match (pixels[vec_pos], pixels[vec_pos_below]) {
-                    (QrDark, QrDark) => self.black_above_black(target)?,
-                    (QrDark, QrLight) => self.black_above_white(target)?,
-                    (QrLight, QrDark) => self.white_above_black(target)?,
-                    (QrLight, QrLight) => self.white_above_white(target)?,
-                };
+    /// Print a matrix describing a 2D barcode to the given writer, and report what was written.
+    pub fn render_with_info<W: Write>(
+        &self,
+        matrix: &Matrix<Color>,
+        target: &mut W,
+    ) -> IoResult<RenderInfo> {
+        self.check_options()?;
+        self.check_colors()?;
+        self.check_width(matrix.size() * self.scale)?;
+        if let Some(renderer) = self.downgraded() {
+            return renderer.render_with_info(matrix, target);
+        }
+        let transformed;
+        let pixels = if self.invert || self.scale > 1 {
+            transformed = self.transform(matrix);
+            &transformed
+        } else {
+            matrix
+        };
+
+        // Assemble the frame first to write it with as few system calls as possible,
+        // except on Windows consoles that may be styled through the console API instead
+        let (mut cols, mut rows) = (self.width(matrix), self.height(matrix));
+        let bytes_written = if cfg!(windows) && self.console && self.row_hook.is_none() {
+            let mut target = CountingWriter::new(target);
+            self.render_pixels(pixels, &mut target)?;
+            target.count
+        } else {
+            let mut frame = Vec::new();
+            self.render_pixels(pixels, &mut frame)?;
+            if let Some(hook) = &self.row_hook {
+                let hooked = hook.apply(frame)?;
+                frame = hooked.0;
+                (cols, rows) = (hooked.1, hooked.2);
             }
-            self.newline(target)?;
+            write_rows(target, &frame)?;
+            frame.len()
+        };
+
+        Ok(RenderInfo {
+            cols,
+            rows,
+            bytes_written,
+            density: self.density,
+            quiet_zone: matrix.quiet_zone(),
+        })
+    }
+
+    /// Render the transformed pixels in the configured density.
+    fn render_pixels<W: Write>(&self, pixels: &Matrix<Color>, target: &mut W) -> IoResult<()> {
+        match self.density {
+            Density::HalfBlock => self.render_half_block(pixels, target),
+            Density::Full => self.render_full(pixels, target),
+            Density::Braille => self.render_braille(pixels, target),
         }
+    }
 
-        // Because one character is two "pixels" above each other, the last pixel-line
-        // has only white ("empty") "pixels" in case of an odd number of pixelrows.
-        if width % 2 == 1 {
-            for col in 0..width {
-                let vec_pos = width * (width - 1) + col;
-                match pixels[vec_pos] {
-                    QrDark => self.black_above_white(target)?,
-                    QrLight => self.white_above_white(target)?,
-                };
+    /// Render a matrix describing a 2D barcode as lines of text, without line endings.
+    ///
+    /// Meant for laying out other output around the barcode, each line taking
+    /// [`width`](Renderer::width) columns.
+    pub fn lines(&self, matrix: &Matrix<Color>) -> IoResult<Vec<String>> {
+        let mut buf = Vec::new();
+        self.render(matrix, &mut buf)?;
+        let text = String::from_utf8(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(text.lines().map(String::from).collect())
+    }
+
+    /// Print a matrix describing a 2D barcode to the terminal.
+    ///
+    /// On legacy Windows consoles without support for ANSI escape sequences, colors are
+    /// set through the console API instead.
+    ///
+    /// Returns an error if writing to stdout failed, for example because it was closed.
+    pub fn print_stdout(&self, matrix: &Matrix<Color>) -> IoResult<()> {
+        self.clone()
+            .for_console()
+            .render(matrix, &mut io::stdout().lock())
+    }
+
+    /// Print bars describing a one-dimensional barcode to the given writer.
+    ///
+    /// Each module is a column repeated for the [bar height](Renderer::bar_height), being
+    /// one character wide for half blocks, two for full density, and half a character for
+    /// braille.
+    pub fn render_bars<W: Write>(&self, bars: &Bars, target: &mut W) -> IoResult<()> {
+        self.check_options()?;
+        self.check_colors()?;
+        self.check_width(bars.width() * self.scale)?;
+        if let Some(renderer) = self.downgraded() {
+            return renderer.render_bars(bars, target);
+        }
+        let modules: Vec<Color> = bars
+            .modules()
+            .iter()
+            .flat_map(|module| std::iter::repeat(*module).take(self.scale))
+            .map(|module| if self.invert { !module } else { module })
+            .collect();
+
+        for _ in 0..self.bar_height * self.scale {
+            match self.density {
+                Density::HalfBlock => {
+                    for module in &modules {
+                        self.bar(target, *module, "█", " ")?;
+                    }
+                }
+                Density::Full => {
+                    for module in &modules {
+                        self.bar(target, *module, "##", "  ")?;
+                    }
+                }
+                Density::Braille => {
+                    for pair in modules.chunks(2) {
+                        // Dots of the left and right column of a braille glyph
+                        let bits = match (pair[0], pair.get(1).copied().unwrap_or(QrLight)) {
+                            (QrDark, QrDark) => 0xFF,
+                            (QrDark, QrLight) => 0x47,
+                            (QrLight, QrDark) => 0xB8,
+                            (QrLight, QrLight) => 0x00,
+                        };
+                        let glyph = char::from_u32(0x2800 + bits).unwrap_or(' ');
+                        if self.colors {
+                            self.write_styled(target, self.styled(glyph, true))?;
+                        } else {
+                            write!(target, "{}", glyph)?;
+                        }
+                    }
+                }
             }
             self.newline(target)?;
         }
 
+        if !bars.text().is_empty() {
+            self.render_bars_text(bars, modules.len(), target)?;
+        }
         Ok(())
     }
 
-    /// Print a matrix describing a 2D barcode to the terminal.
-    pub fn print_stdout(&self, matrix: &Matrix<Color>) {
-        self.render(matrix, &mut io::stdout())
-            .expect("failed to print QR code to stdout");
+    /// Write the human-readable text of bars as a line beneath them.
+    fn render_bars_text<W: Write>(
+        &self,
+        bars: &Bars,
+        modules: usize,
+        target: &mut W,
+    ) -> IoResult<()> {
+        // Columns per module, doubled to support half columns for braille
+        let columns = match self.density {
+            Density::HalfBlock => 2,
+            Density::Full => 4,
+            Density::Braille => 1,
+        };
+        let mut line = vec![' '; (modules * columns + 1) / 2];
+        for (module, c) in bars.text() {
+            let column = (module * self.scale * 2 + self.scale) * columns / 4;
+            if let Some(cell) = line.get_mut(column) {
+                *cell = *c;
+            }
+        }
+        let line: String = line.into_iter().collect();
+
+        if self.colors {
+            let (fg, bg) = if self.invert {
+                (self.theme.light, self.theme.dark)
+            } else {
+                (self.theme.dark, self.theme.light)
+            };
+            self.write_styled(target, Styled::new(line, Some(fg), bg))?;
+        } else {
+            write!(target, "{}", line.trim_end())?;
+        }
+        self.newline(target)
+    }
+
+    /// Print bars describing a one-dimensional barcode to the terminal.
+    ///
+    /// Returns an error if writing to stdout failed.
+    pub fn print_bars_stdout(&self, bars: &Bars) -> IoResult<()> {
+        self.clone()
+            .for_console()
+            .render_bars(bars, &mut io::stdout().lock())
+    }
+
+    /// Write one bar module, drawn with the theme color or with the given glyphs.
+    fn bar<W: Write>(
+        &self,
+        target: &mut W,
+        module: Color,
+        dark: &str,
+        light: &str,
+    ) -> IoResult<()> {
+        let (glyph, color) = match module {
+            QrDark => (dark, self.theme.dark),
+            QrLight => (light, self.theme.light),
+        };
+        if !self.colors {
+            return write!(target, "{}", glyph);
+        }
+        self.write_styled(target, Styled::new(" ".repeat(light.len()), None, color))
+    }
+
+    /// Style output through crossterm commands, for rendering straight to the console.
+    ///
+    /// Falls back to [`Density::Full`], drawn with ASCII characters only, if the console
+    /// code page or the [locale](term::locale_utf8) can't display the glyphs of the
+    /// configured density.
+    pub(crate) fn for_console(self) -> Self {
+        self.for_terminal(
+            &term::capabilities(),
+            console_code_page(),
+            term::locale_utf8(),
+        )
+    }
+
+    /// Style output for a console with the given capabilities, output code page and
+    /// [locale](term::locale_utf8) encoding, like [`Renderer::for_console`].
+    pub(crate) fn for_terminal(
+        mut self,
+        caps: &Capabilities,
+        code_page: Option<u32>,
+        utf8: Option<bool>,
+    ) -> Self {
+        self.console = true;
+        self.density = console_density(self.density, code_page);
+        if utf8 == Some(false) {
+            self.density = Density::Full;
+        }
+        self.color_depth = caps.color;
+        self
+    }
+
+    /// How many columns and rows in the terminal it takes to render `matrix`.
+    ///
+    /// Accounts for the configured density, scale and padding, and the quiet zone the
+    /// matrix is surrounded with, matching the real output.
+    pub fn dimensions(&self, matrix: &Matrix<Color>) -> (usize, usize) {
+        (self.width(matrix), self.height(matrix))
     }
 
     /// How many horizontal characters or columns in the terminal it takes to render `matrix`.
     pub fn width(&self, matrix: &Matrix<Color>) -> usize {
-        matrix.size()
+        self.columns(matrix.size() * self.scale)
+    }
+
+    /// How many columns it takes to render a row of `pixels`, after scaling.
+    fn columns(&self, pixels: usize) -> usize {
+        match self.density {
+            Density::HalfBlock => pixels,
+            Density::Full => pixels * 2,
+            Density::Braille => (pixels + 1) / 2,
+        }
     }
 
     /// How many vertical characters or rows or lines in the terminal it takes to render `matrix`.
     pub fn height(&self, matrix: &Matrix<Color>) -> usize {
-        matrix.size() / 2 + matrix.size() % 2
+        let size = self.pixel_height(matrix);
+        match self.density {
+            Density::HalfBlock => (size + 1) / 2,
+            Density::Full => size,
+            Density::Braille => (size + 3) / 4,
+        }
+    }
+
+    /// Height of the rendered `matrix` in pixels, including the padding row if any.
+    fn pixel_height(&self, matrix: &Matrix<Color>) -> usize {
+        let size = matrix.size() * self.scale;
+        if self.even_height {
+            size + size % 2
+        } else {
+            size
+        }
+    }
+
+    /// Apply inversion and scaling to the given matrix.
+    fn transform(&self, matrix: &Matrix<Color>) -> Matrix<Color> {
+        let size = matrix.size();
+        let scaled = size * self.scale;
+        let mut pixels = Vec::with_capacity(scaled * scaled);
+        for y in 0..scaled {
+            for x in 0..scaled {
+                let pixel = matrix.pixels()[(y / self.scale) * size + x / self.scale];
+                pixels.push(if self.invert { !pixel } else { pixel });
+            }
+        }
+        Matrix::new(pixels)
+    }
+
+    /// Render using half block glyphs, two pixels above each other per character.
+    fn render_half_block<W: Write>(&self, matrix: &Matrix<Color>, target: &mut W) -> IoResult<()> {
+        for rows in matrix.chunks(2) {
+            for col in 0..rows.width() {
+                // Because one character is two "pixels" above each other, the last
+                // pixel-line has only white ("empty") "pixels" in case of an odd number
+                // of pixelrows.
+                let above = rows.get(col, 0).copied().unwrap_or(QrLight);
+                let below = rows.get(col, 1).copied().unwrap_or(QrLight);
+                match (above, below) {
+                    (QrDark, QrDark) => self.black_above_black(target)?,
+                    (QrDark, QrLight) => self.black_above_white(target)?,
+                    (QrLight, QrDark) => self.white_above_black(target)?,
+                    (QrLight, QrLight) => self.white_above_white(target)?,
+                };
+            }
+            self.newline(target)?;
+        }
+        Ok(())
+    }
+
+    /// Render using two characters per pixel.
+    fn render_full<W: Write>(&self, matrix: &Matrix<Color>, target: &mut W) -> IoResult<()> {
+        let width = matrix.size();
+        for row in matrix.pixels().chunks(width.max(1)) {
+            for pixel in row {
+                match (pixel, self.colors) {
+                    (QrDark, true) => {
+                        self.write_styled(target, Styled::new("  ", None, self.theme.dark))?
+                    }
+                    (QrLight, true) => {
+                        self.write_styled(target, Styled::new("  ", None, self.theme.light))?
+                    }
+                    (QrDark, false) => write!(target, "##")?,
+                    (QrLight, false) => write!(target, "  ")?,
+                }
+            }
+            self.newline(target)?;
+        }
+
+        if self.even_height && width % 2 == 1 {
+            for _ in 0..width {
+                if self.colors {
+                    self.write_styled(target, Styled::new("  ", None, self.theme.light))?;
+                } else {
+                    write!(target, "  ")?;
+                }
+            }
+            self.newline(target)?;
+        }
+        Ok(())
+    }
+
+    /// Render using braille glyphs, a 2 by 4 grid of pixels per character.
+    fn render_braille<W: Write>(&self, matrix: &Matrix<Color>, target: &mut W) -> IoResult<()> {
+        // Braille dot bits, indexed by row and column within the character
+        const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+        let width = matrix.size();
+        for cell in matrix.tiles(2, 4) {
+            let mut bits = 0;
+            for (dy, dots) in DOTS.iter().enumerate() {
+                for (dx, dot) in dots.iter().enumerate() {
+                    if cell.get(dx, dy) == Some(&QrDark) {
+                        bits |= dot;
+                    }
+                }
+            }
+            let glyph = char::from_u32(0x2800 + bits).unwrap_or(' ');
+            if self.colors {
+                self.write_styled(target, self.styled(glyph, true))?;
+            } else {
+                write!(target, "{}", glyph)?;
+            }
+
+            // End the line after the last cell of each row
+            if cell.x() + cell.width() == width {
+                self.newline(target)?;
+            }
+        }
+        Ok(())
     }
 
     /// Terminal-format and print one character that show a black pixel above a white pixel.
@@ -72,23 +883,63 @@ impl Renderer {
     /// without gap under it, so we workaround the problem by
     /// using color inversion (so "█" = " " inverted, and "▀" = "▄" inverted).
     /// "▄" seems to render better than "▅".
+    ///
+    /// Without colors, plain glyphs drawing the dark pixels are used instead.
     fn black_above_white<W: Write>(&self, target: &mut W) -> IoResult<()> {
-        write!(target, "{}", "▄".white().on_black())
+        if !self.colors {
+            return write!(target, "▀");
+        }
+        self.write_styled(target, self.styled("▄", false))
     }
 
     /// Similar to `black_above_white`
     fn white_above_black<W: Write>(&self, target: &mut W) -> IoResult<()> {
-        write!(target, "{}", "▄".black().on_white())
+        if !self.colors {
+            return write!(target, "▄");
+        }
+        self.write_styled(target, self.styled("▄", true))
     }
 
     /// Similar to `black_above_white`
     fn black_above_black<W: Write>(&self, target: &mut W) -> IoResult<()> {
-        write!(target, "{}", " ".white().on_black())
+        if !self.colors {
+            return write!(target, "█");
+        }
+        self.write_styled(target, self.styled(" ", false))
     }
 
     /// Similar to `black_above_white`
     fn white_above_white<W: Write>(&self, target: &mut W) -> IoResult<()> {
-        write!(target, "{}", " ".black().on_white())
+        if !self.colors {
+            return write!(target, " ");
+        }
+        self.write_styled(target, self.styled(" ", true))
+    }
+
+    /// Style a glyph using the theme colors, drawn dark on light if `dark_on_light`.
+    fn styled<D: fmt::Display>(&self, glyph: D, dark_on_light: bool) -> Styled<D> {
+        let (fg, bg) = if dark_on_light {
+            (self.theme.dark, self.theme.light)
+        } else {
+            (self.theme.light, self.theme.dark)
+        };
+        Styled::new(glyph, Some(fg), bg)
+    }
+
+    /// Write styled content to the given target.
+    ///
+    /// When printing to the console, this goes through crossterm commands, which use the
+    /// console API on legacy Windows consoles that would show raw escape sequences.
+    fn write_styled<W: Write, D: fmt::Display>(
+        &self,
+        target: &mut W,
+        styled: Styled<D>,
+    ) -> IoResult<()> {
+        #[cfg(feature = "crossterm")]
+        if self.console {
+            return styled.queue(target);
+        }
+        write!(target, "{}", styled)
     }
 
     /// Print newline that does not mess up colors.
@@ -97,63 +948,233 @@ impl Renderer {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    mod size_tracker {
-        //! Tracks how many newlines and character per line are written
+/// Content drawn with a background and optional foreground color.
+///
+/// Uses crossterm if enabled, and writes the same ANSI escape sequences directly otherwise.
+pub(crate) struct Styled<D> {
+    content: D,
+    fg: Option<TermColor>,
+    bg: TermColor,
+}
 
-        use regex::Regex;
-        use std::io::Write;
+impl<D: fmt::Display> Styled<D> {
+    pub(crate) fn new(content: D, fg: Option<TermColor>, bg: TermColor) -> Self {
+        Self { content, fg, bg }
+    }
 
-        pub struct SizeTracker {
-            data: Vec<u8>,
+    /// Queue crossterm commands drawing this content, equivalent to its display output.
+    #[cfg(feature = "crossterm")]
+    fn queue<W: Write>(&self, target: &mut W) -> IoResult<()> {
+        queue!(target, SetBackgroundColor(self.bg.into()))?;
+        if let Some(fg) = self.fg {
+            queue!(target, SetForegroundColor(fg.into()))?;
         }
+        queue!(
+            target,
+            Print(&self.content),
+            SetBackgroundColor(CrosstermColor::Reset)
+        )?;
+        if self.fg.is_some() {
+            queue!(target, SetForegroundColor(CrosstermColor::Reset))?;
+        }
+        Ok(())
+    }
+}
 
-        impl Write for SizeTracker {
-            fn write(&mut self, data: &[u8]) -> std::result::Result<usize, std::io::Error> {
-                self.data.extend(data);
-                Ok(data.len())
-            }
+impl<D: fmt::Display> fmt::Display for Styled<D> {
+    #[cfg(feature = "crossterm")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut styled = style(&self.content).on(self.bg.into());
+        if let Some(fg) = self.fg {
+            styled = styled.with(fg.into());
+        }
+        write!(f, "{}", styled)
+    }
 
-            fn flush(&mut self) -> std::result::Result<(), std::io::Error> {
-                Ok(())
-            }
+    #[cfg(not(feature = "crossterm"))]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("\x1b[48;")?;
+        self.bg.write_sgr(f)?;
+        f.write_str("m")?;
+        if let Some(fg) = self.fg {
+            f.write_str("\x1b[38;")?;
+            fg.write_sgr(f)?;
+            f.write_str("m")?;
         }
+        write!(f, "{}", self.content)?;
+        f.write_str("\x1b[49m")?;
+        if self.fg.is_some() {
+            f.write_str("\x1b[39m")?;
+        }
+        Ok(())
+    }
+}
 
-        impl SizeTracker {
-            pub fn new() -> Self {
-                SizeTracker { data: vec![] }
-            }
+/// Resolve the densest glyphs a terminal with the given capabilities and UTF-8 locale, if
+/// known, can draw.
+///
+/// Braille needs a UTF-8 encoding, half blocks Unicode support, ASCII works everywhere.
+fn auto_density(caps: &Capabilities, utf8: Option<bool>) -> Density {
+    if !caps.unicode || caps.density == Density::Full {
+        Density::Full
+    } else if utf8 == Some(true) {
+        Density::Braille
+    } else {
+        Density::HalfBlock
+    }
+}
+
+/// Windows code pages that include the block glyphs used by [`Density::HalfBlock`].
+const BLOCK_CODE_PAGES: [u32; 16] = [
+    437, 737, 775, 850, 852, 855, 857, 858, 860, 861, 862, 863, 865, 866, 869, 65001,
+];
+
+/// The density to render with on a console with the given output code page.
+///
+/// Braille glyphs need UTF-8, block glyphs are available in most OEM code pages.
+fn console_density(density: Density, code_page: Option<u32>) -> Density {
+    match (density, code_page) {
+        (_, None) | (Density::Full, _) | (_, Some(65001)) => density,
+        (Density::HalfBlock, Some(page)) if BLOCK_CODE_PAGES.contains(&page) => density,
+        _ => Density::Full,
+    }
+}
+
+/// Output code page of the attached Windows console, or `None` if there is none.
+#[cfg(windows)]
+fn console_code_page() -> Option<u32> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleOutputCP() -> u32;
+    }
 
-            fn without_ansi_codes(text: &str) -> String {
-                let regex = Regex::new("\x1B\\[.*?m").unwrap();
-                regex.replace_all(text, "").to_string()
+    // SAFETY: takes no arguments and only reads console state, returns 0 without console
+    match unsafe { GetConsoleOutputCP() } {
+        0 => None,
+        page => Some(page),
+    }
+}
+
+/// Output code page of the attached Windows console, always `None` on other platforms.
+#[cfg(not(windows))]
+fn console_code_page() -> Option<u32> {
+    None
+}
+
+/// Function post-processing a rendered row, given its index.
+type RowFn = dyn Fn(usize, &str) -> Option<String> + Send + Sync;
+
+/// Callback post-processing rendered rows, see [`Renderer::row_hook`].
+#[derive(Clone)]
+struct RowHook(Arc<RowFn>);
+
+impl RowHook {
+    /// Pass each row of the rendered `frame` through the callback.
+    ///
+    /// Returns the new frame, with the columns of its widest row and its number of rows.
+    fn apply(&self, frame: Vec<u8>) -> IoResult<(Vec<u8>, usize, usize)> {
+        let frame = String::from_utf8(frame)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut rows = String::with_capacity(frame.len());
+        let (mut cols, mut count) = (0, 0);
+        for (i, row) in frame.lines().enumerate() {
+            if let Some(row) = (self.0)(i, row) {
+                rows.push_str(&row);
+                rows.push('\n');
+                cols = cols.max(columns_of(&row));
+                count += 1;
             }
+        }
+        Ok((rows.into_bytes(), cols, count))
+    }
+}
 
-            /// Length of longest line in visible characters.
-            ///
-            /// Panics if data seen by tracker is not valid UTF-8.
-            pub fn width(&self) -> usize {
-                if self.data.is_empty() {
-                    return 0;
+/// Number of columns a rendered `row` takes, skipping escape sequences.
+///
+/// Counts one column per character, like the glyphs of the renderer take.
+fn columns_of(row: &str) -> usize {
+    let mut chars = row.chars();
+    let mut cols = 0;
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            cols += 1;
+            continue;
+        }
+        // Skip control sequences up to their final byte
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
                 }
-                let data_str = std::str::from_utf8(&self.data).unwrap();
-                let without_ansi_codes = Self::without_ansi_codes(data_str);
-                without_ansi_codes
-                    .split("\n")
-                    .map(|line| line.chars().count())
-                    .max()
-                    .unwrap()
             }
+        }
+    }
+    cols
+}
 
-            pub fn height(&self) -> usize {
-                let newline = 10;
-                self.data.iter().filter(|&elem| *elem == newline).count()
-            }
+impl fmt::Debug for RowHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("RowHook")
+    }
+}
+
+/// Write all rows of a rendered frame to `target` with vectored writes, one slice per row.
+///
+/// Writers without vectored write support fall back to writing a row at a time.
+fn write_rows<W: Write>(target: &mut W, frame: &[u8]) -> IoResult<()> {
+    let rows: Vec<&[u8]> = frame.split_inclusive(|byte| *byte == b'\n').collect();
+    let (mut row, mut offset) = (0, 0);
+    while row < rows.len() {
+        let slices: Vec<IoSlice> = std::iter::once(&rows[row][offset..])
+            .chain(rows[row + 1..].iter().copied())
+            .map(IoSlice::new)
+            .collect();
+        let mut written = match target.write_vectored(&slices) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => written,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        // Skip the rows that were written completely, and continue within the next
+        while row < rows.len() && written >= rows[row].len() - offset {
+            written -= rows[row].len() - offset;
+            row += 1;
+            offset = 0;
         }
+        offset += written;
     }
+    Ok(())
+}
+
+/// Writer wrapper counting the number of bytes written to the inner writer.
+struct CountingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    count: usize,
+}
+
+impl<'a, W: Write> CountingWriter<'a, W> {
+    fn new(inner: &'a mut W) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SizeTracker;
 
     /// Checks that the expected, promised, and actual width and height match
     /// when rendering `pixels` to a terminal QR code.
@@ -161,7 +1182,7 @@ mod tests {
         // Given: a matrix, and a renderer for that matrix.
         let matrix = Matrix::new(pixels);
         let renderer = Renderer::default();
-        let mut writer = size_tracker::SizeTracker::new();
+        let mut writer = SizeTracker::new();
 
         // When: rendering the matrix
         let promised_width = renderer.width(&matrix);
@@ -188,4 +1209,412 @@ mod tests {
         helper_width_and_height(vec![QrLight; 5 * 5], 5, 3);
         helper_width_and_height(vec![QrDark; 21 * 21], 21, 11);
     }
+
+    #[test]
+    fn densities_width_and_height() {
+        for density in [Density::HalfBlock, Density::Full, Density::Braille] {
+            for colors in [true, false] {
+                let renderer = Renderer::default().density(density).colors(colors);
+                for size in [0, 1, 5, 21] {
+                    let matrix = Matrix::new(vec![QrDark; size * size]);
+                    let mut writer = SizeTracker::new();
+                    renderer.render(&matrix, &mut writer).unwrap();
+                    assert_eq!(writer.width(), renderer.width(&matrix));
+                    assert_eq!(writer.height(), renderer.height(&matrix));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn color_warnings() {
+        assert_eq!(Theme::CLASSIC.contrast_ratio(), 21.0);
+        assert!(Theme::CLASSIC.warnings().is_empty());
+        for name in Theme::NAMES {
+            assert!(Theme::named(name).unwrap().warnings().is_empty());
+        }
+
+        let gray = Theme {
+            dark: TermColor::Ansi(244),
+            light: TermColor::Ansi(250),
+        };
+        assert!(matches!(
+            gray.warnings()[..],
+            [ColorWarning::LowContrast(ratio)] if ratio < MIN_CONTRAST
+        ));
+
+        let renderer = Renderer::default().invert(true);
+        assert_eq!(renderer.warnings(), [ColorWarning::InvertedPolarity]);
+        assert!(renderer.clone().colors(false).warnings().is_empty());
+
+        let matrix = Matrix::new(vec![QrDark]);
+        assert!(renderer.render(&matrix, &mut Vec::new()).is_ok());
+        let err = renderer
+            .strict(true)
+            .render(&matrix, &mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn invalid_options() {
+        let matrix = Matrix::new(vec![QrDark]);
+        let err = Renderer::default()
+            .scale(0)
+            .render(&matrix, &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(
+            crate::Error::from(err),
+            crate::Error::InvalidOption(_)
+        ));
+
+        let bars = Bars::new(vec![QrDark, QrLight, QrDark]);
+        let err = Renderer::default()
+            .bar_height(0)
+            .render_bars(&bars, &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(
+            crate::Error::from(err),
+            crate::Error::InvalidOption(_)
+        ));
+    }
+
+    #[test]
+    fn downgrade_colors() {
+        let orange = TermColor::Rgb(0xFF, 0x80, 0x00);
+        assert_eq!(orange.downgrade(ColorDepth::TrueColor), orange);
+        assert_eq!(orange.downgrade(ColorDepth::Ansi256), TermColor::Ansi(208));
+        assert_eq!(orange.downgrade(ColorDepth::Ansi16), TermColor::Ansi(3));
+        assert_eq!(
+            TermColor::Ansi(208).downgrade(ColorDepth::Ansi256),
+            TermColor::Ansi(208)
+        );
+        assert_eq!(
+            TermColor::Ansi(232).downgrade(ColorDepth::None),
+            TermColor::Ansi(0)
+        );
+        assert_eq!(
+            TermColor::White.downgrade(ColorDepth::Ansi16),
+            TermColor::White
+        );
+
+        let matrix = Matrix::new(vec![QrDark]);
+        let theme = Theme::named("nord").unwrap();
+        let render = |depth| {
+            let mut buf = Vec::new();
+            Renderer::default()
+                .theme(theme)
+                .color_depth(depth)
+                .render(&matrix, &mut buf)
+                .unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        assert!(render(ColorDepth::TrueColor).contains("2;46;52;64"));
+        assert!(!render(ColorDepth::Ansi256).contains(";2;"));
+        assert_eq!(render(ColorDepth::Ansi16).lines().count(), 1);
+    }
+
+    #[test]
+    fn lines() {
+        let mut matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        matrix.surround(1, QrLight);
+        let renderer = Renderer::default().colors(false);
+        assert_eq!(renderer.lines(&matrix).unwrap(), [" ▄  ", "  ▀ "]);
+        assert_eq!(
+            renderer
+                .density(Density::Full)
+                .lines(&matrix)
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn even_height() {
+        let matrix = Matrix::new(vec![QrDark; 9]);
+        let renderer = Renderer::default().colors(false).even_height(true);
+        assert_eq!(renderer.lines(&matrix).unwrap(), ["███", "▀▀▀"]);
+        let full = renderer.density(Density::Full);
+        assert_eq!(full.height(&matrix), 4);
+        assert_eq!(full.lines(&matrix).unwrap().last().unwrap(), "      ");
+        assert_eq!(full.even_height(false).lines(&matrix).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn bars() {
+        let bars = Bars::new(vec![QrDark, QrLight, QrDark]);
+        let render = |renderer: Renderer| {
+            let mut buf = Vec::new();
+            renderer.bar_height(2).render_bars(&bars, &mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        let plain = Renderer::default().colors(false);
+        assert_eq!(render(plain.clone()), "█ █\n█ █\n");
+        assert_eq!(
+            render(plain.clone().density(Density::Full)),
+            "##  ##\n##  ##\n"
+        );
+        assert_eq!(
+            render(plain.clone().density(Density::Braille)),
+            "\u{2847}\u{2847}\n\u{2847}\u{2847}\n"
+        );
+        assert_eq!(render(plain.clone().invert(true)), " █ \n █ \n");
+        assert_eq!(render(plain.clone().scale(2)).lines().count(), 4);
+
+        let mut labeled = bars.clone();
+        labeled.set_text(vec![(0, '1'), (2, '2')]);
+        let mut buf = Vec::new();
+        plain
+            .bar_height(1)
+            .scale(2)
+            .render_bars(&labeled, &mut buf)
+            .unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "██  ██\n██  ██\n 1   2\n");
+
+        let colored = render(Renderer::default());
+        let mut tracker = SizeTracker::new();
+        tracker.write_all(colored.as_bytes()).unwrap();
+        assert_eq!((tracker.width(), tracker.height()), (3, 2));
+    }
+
+    #[test]
+    fn plain_glyphs() {
+        let matrix = Matrix::new(vec![QrDark, QrLight, QrDark, QrDark]);
+        let render = |density| {
+            let mut buf = Vec::new();
+            let renderer = Renderer::default().density(density).colors(false);
+            renderer.render(&matrix, &mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        assert_eq!(render(Density::HalfBlock), "█▄\n");
+        assert_eq!(render(Density::Full), "##  \n####\n");
+        assert_eq!(render(Density::Braille), "\u{2813}\n");
+    }
+
+    #[test]
+    fn dimensions() {
+        let mut matrix = Matrix::new(vec![QrDark; 9]);
+        matrix.surround(1, QrLight);
+        for (renderer, dimensions) in [
+            (Renderer::default(), (5, 3)),
+            (Renderer::default().scale(2), (10, 5)),
+            (Renderer::default().density(Density::Full), (10, 5)),
+            (Renderer::default().density(Density::Braille), (3, 2)),
+            (
+                Renderer::default().density(Density::Full).even_height(true),
+                (10, 6),
+            ),
+        ] {
+            assert_eq!(renderer.dimensions(&matrix), dimensions);
+            let lines = renderer.lines(&matrix).unwrap();
+            let width = crate::testing::strip_ansi(&lines[0]).chars().count();
+            assert_eq!((width, lines.len()), dimensions);
+        }
+    }
+
+    #[test]
+    fn row_hook() {
+        let mut matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        matrix.surround(1, QrLight);
+        let renderer = Renderer::default()
+            .colors(false)
+            .row_hook(|i, row| (i == 0).then(|| format!("[{}]", row)));
+        assert_eq!(renderer.lines(&matrix).unwrap(), ["[ ▄  ]"]);
+        let mut buf = Vec::new();
+        let info = renderer.render_with_info(&matrix, &mut buf).unwrap();
+        assert_eq!(info.bytes_written, buf.len());
+        assert_eq!((info.cols, info.rows), (6, 1));
+
+        // Escape sequences don't take columns
+        let renderer =
+            Renderer::default().row_hook(|i, row| (i != 1).then(|| format!("> {}", row)));
+        let info = renderer.render_with_info(&matrix, &mut Vec::new()).unwrap();
+        assert_eq!((info.cols, info.rows), (6, 1));
+        assert_eq!(columns_of("\x1b[38;2;0;0;0m▀\x1b[0m ▄"), 3);
+    }
+
+    #[test]
+    fn vectored_rows() {
+        /// Writer accepting up to 5 bytes per call, counting vectored calls.
+        #[derive(Default)]
+        struct Partial {
+            data: Vec<u8>,
+            calls: usize,
+        }
+
+        impl Write for Partial {
+            fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+                self.write_vectored(&[IoSlice::new(buf)])
+            }
+
+            fn write_vectored(&mut self, bufs: &[IoSlice]) -> IoResult<usize> {
+                self.calls += 1;
+                let data: Vec<u8> = bufs.iter().flat_map(|buf| buf.iter().copied()).collect();
+                let written = data.len().min(5);
+                self.data.extend_from_slice(&data[..written]);
+                Ok(written)
+            }
+
+            fn flush(&mut self) -> IoResult<()> {
+                Ok(())
+            }
+        }
+
+        let mut target = Partial::default();
+        write_rows(&mut target, b"abc\ndefgh\nij\n").unwrap();
+        assert_eq!(target.data, b"abc\ndefgh\nij\n");
+        assert_eq!(target.calls, 3);
+
+        let mut matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        matrix.surround(1, QrLight);
+        let mut buf = Vec::new();
+        let info = Renderer::default()
+            .render_with_info(&matrix, &mut buf)
+            .unwrap();
+        assert_eq!(info.bytes_written, buf.len());
+    }
+
+    #[test]
+    fn max_width() {
+        let matrix = Matrix::new(vec![QrDark; 9]);
+        let renderer = Renderer::default().max_width(6);
+        assert!(renderer.render(&matrix, &mut Vec::new()).is_ok());
+        let err = renderer
+            .clone()
+            .density(Density::Full)
+            .scale(2)
+            .render(&matrix, &mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(matches!(
+            crate::Error::from(err),
+            crate::Error::TooWide { needed: 12, max: 6 }
+        ));
+        let bars = Bars::new(vec![QrDark; 7]);
+        assert!(renderer.render_bars(&bars, &mut Vec::new()).is_err());
+        assert!(matches!(
+            crate::Error::from(io::Error::from(io::ErrorKind::Other)),
+            crate::Error::Io(_)
+        ));
+    }
+
+    #[test]
+    fn invert_and_scale() {
+        let matrix = Matrix::new(vec![QrDark, QrLight, QrDark, QrDark]);
+        let renderer = Renderer::default()
+            .density(Density::Full)
+            .colors(false)
+            .invert(true)
+            .scale(2);
+        let mut buf = Vec::new();
+        renderer.render(&matrix, &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "    ####\n    ####\n        \n        \n"
+        );
+        assert_eq!(renderer.width(&matrix), 8);
+        assert_eq!(renderer.height(&matrix), 4);
+    }
+
+    #[test]
+    fn classic_theme_matches_default_output() {
+        let matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        let render = |renderer: Renderer| {
+            let mut buf = Vec::new();
+            renderer.render(&matrix, &mut buf).unwrap();
+            buf
+        };
+        let classic = render(Renderer::default().theme(Theme::named("classic").unwrap()));
+        assert_eq!(classic, render(Renderer::default()));
+        let nord = render(Renderer::default().theme(Theme::named("nord").unwrap()));
+        assert!(String::from_utf8(nord)
+            .unwrap()
+            .contains("\x1b[38;2;46;52;64m"));
+        assert!(Theme::NAMES.iter().all(|name| Theme::named(name).is_some()));
+    }
+
+    /// Rendering for the console must produce the same escape sequences where supported.
+    #[test]
+    #[cfg(feature = "crossterm")]
+    fn console_output_matches() {
+        let matrix = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        for density in [Density::HalfBlock, Density::Full, Density::Braille] {
+            let renderer = Renderer::default().density(density);
+            let console = renderer.clone().for_console();
+            let (mut buf, mut console_buf) = (Vec::new(), Vec::new());
+            renderer.render(&matrix, &mut buf).unwrap();
+            console.render(&matrix, &mut console_buf).unwrap();
+            assert_eq!(buf, console_buf);
+        }
+    }
+
+    #[test]
+    fn auto_densities() {
+        let caps = Capabilities {
+            tty: true,
+            color: ColorDepth::TrueColor,
+            unicode: true,
+            sixel: false,
+            kitty: false,
+            terminal: None,
+            density: Density::HalfBlock,
+        };
+        assert_eq!(auto_density(&caps, Some(true)), Density::Braille);
+        assert_eq!(auto_density(&caps, None), Density::HalfBlock);
+        let gaps = Capabilities {
+            density: Density::Full,
+            ..caps.clone()
+        };
+        assert_eq!(auto_density(&gaps, Some(true)), Density::Full);
+        let ascii = Capabilities {
+            unicode: false,
+            ..caps
+        };
+        assert_eq!(auto_density(&ascii, None), Density::Full);
+    }
+
+    #[test]
+    fn console_density_fallback() {
+        use Density::*;
+        assert_eq!(console_density(HalfBlock, None), HalfBlock);
+        assert_eq!(console_density(Braille, Some(65001)), Braille);
+        assert_eq!(console_density(HalfBlock, Some(437)), HalfBlock);
+        assert_eq!(console_density(Braille, Some(437)), Full);
+        assert_eq!(console_density(HalfBlock, Some(936)), Full);
+        assert_eq!(console_density(Full, Some(936)), Full);
+    }
+
+    /// Styled output must be the same with and without crossterm.
+    #[test]
+    fn styled_escape_sequences() {
+        let styled = Styled::new("▄", Some(TermColor::Black), TermColor::White);
+        assert_eq!(
+            styled.to_string(),
+            "\x1b[48;5;15m\x1b[38;5;0m▄\x1b[49m\x1b[39m"
+        );
+        let styled = Styled::new("  ", None, TermColor::Rgb(1, 2, 3));
+        assert_eq!(styled.to_string(), "\x1b[48;2;1;2;3m  \x1b[49m");
+        let styled = Styled::new(" ", Some(TermColor::Ansi(202)), TermColor::Black);
+        assert_eq!(
+            styled.to_string(),
+            "\x1b[48;5;0m\x1b[38;5;202m \x1b[49m\x1b[39m"
+        );
+    }
+
+    #[test]
+    fn render_with_info() {
+        let mut matrix = Matrix::new(vec![QrDark; 3 * 3]);
+        matrix.surround(1, QrLight);
+        let mut buf = Vec::new();
+        let info = Renderer::default()
+            .render_with_info(&matrix, &mut buf)
+            .unwrap();
+
+        assert_eq!(info.cols, 5);
+        assert_eq!(info.rows, 3);
+        assert_eq!(info.bytes_written, buf.len());
+        assert_eq!(info.density, Density::HalfBlock);
+        assert_eq!(info.quiet_zone, 1);
+    }
 }
@@ -0,0 +1,182 @@
+//! Splitting data too long for one code over multiple codes.
+//!
+//! Unlike the structured append mode of the QR code specification, which few scanner apps
+//! support, each code simply starts with an `i/N:` header. Receivers scan them in order,
+//! strip the headers and concatenate the rest.
+//!
+//! [`print_split_payload`] prints all codes at once, with a part indicator beneath each
+//! so they can be scanned from the scrollback in order.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::render::Renderer;
+//!
+//! let key = "-----BEGIN PGP PUBLIC KEY BLOCK-----\n...".repeat(20);
+//! for matrix in qr2term::split::split_payload(&key, 256).unwrap() {
+//!     Renderer::default().print_stdout(&matrix).unwrap();
+//! }
+//! ```
+
+use std::io::Write;
+
+use crate::annotate::Annotations;
+use crate::matrix::Matrix;
+use crate::render::Color;
+use crate::{fingerprint, options, Error};
+
+/// Split `data` into chunks of at most `chunk_size` bytes, each prefixed with an `i/N:`
+/// header, and generate a QR code for each.
+///
+/// Returns the pixel matrices with the quiet zone of the
+/// [default options](crate::default_options), ready to print. Text is not split within
+/// characters, so chunks of valid UTF-8 stay valid UTF-8. Empty data gives a single code.
+///
+/// Returns an error if generating a QR code failed.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+pub fn split_payload<D: AsRef<[u8]>>(
+    data: D,
+    chunk_size: usize,
+) -> Result<Vec<Matrix<Color>>, Error> {
+    let options = options();
+    parts(data.as_ref(), chunk_size)
+        .iter()
+        .map(|part| Ok(options.matrix(&options.qr(part)?)))
+        .collect()
+}
+
+/// Split `data` like [`split_payload`], and print the codes in the terminal with a
+/// "Part i of N" line beneath each.
+///
+/// With `header`, a machine-readable line such as `part=2/3 sha256=ba7816bf` is printed
+/// above each code as well, holding the [fingerprint] of all data, so recipients can
+/// verify they got all parts. Returns the number of printed codes.
+///
+/// Returns an error if generating a QR code failed, or [`Error::Io`] if printing failed.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// let key = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+/// let parts = qr2term::split::print_split_payload(key, 32, true).unwrap();
+/// assert_eq!(parts, 3);
+/// ```
+pub fn print_split_payload<D: AsRef<[u8]>>(
+    data: D,
+    chunk_size: usize,
+    header: bool,
+) -> Result<usize, Error> {
+    let options = options();
+    let renderer = options.renderer().for_console();
+    let parts = parts(data.as_ref(), chunk_size);
+    let matrices = parts
+        .iter()
+        .map(|part| Ok(options.matrix(&options.qr(part)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let fingerprint = fingerprint(data);
+    let mut stdout = std::io::stdout().lock();
+    for (i, matrix) in matrices.iter().enumerate() {
+        if i > 0 {
+            writeln!(stdout)?;
+        }
+        if header {
+            writeln!(
+                stdout,
+                "part={}/{} sha256={}",
+                i + 1,
+                matrices.len(),
+                fingerprint
+            )?;
+        }
+        Annotations::new()
+            .bottom(format!("Part {} of {}", i + 1, matrices.len()))
+            .render(&renderer, matrix, &mut stdout)?;
+    }
+    Ok(matrices.len())
+}
+
+/// Split `data` into chunks of at most `chunk_size` bytes, each prefixed with its header.
+pub(crate) fn parts(data: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
+    assert!(chunk_size > 0, "chunk size must be at least 1");
+    let text = std::str::from_utf8(data).ok();
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() || chunks.is_empty() {
+        let mut end = chunk_size.min(rest.len());
+        if text.is_some() && !rest.is_empty() {
+            // Move the end back to a character boundary, or forward if the first
+            // character is longer than a chunk
+            while end > 0 && end < rest.len() && is_continuation(rest[end]) {
+                end -= 1;
+            }
+            if end == 0 {
+                end = 1;
+                while end < rest.len() && is_continuation(rest[end]) {
+                    end += 1;
+                }
+            }
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut part = format!("{}/{}:", i + 1, total).into_bytes();
+            part.extend_from_slice(chunk);
+            part
+        })
+        .collect()
+}
+
+/// Whether `byte` continues a UTF-8 encoded character.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_parts(data: &str, chunk_size: usize) -> Vec<String> {
+        parts(data.as_bytes(), chunk_size)
+            .into_iter()
+            .map(|part| String::from_utf8(part).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn headers() {
+        assert_eq!(text_parts("abcdefg", 3), ["1/3:abc", "2/3:def", "3/3:g"]);
+        assert_eq!(text_parts("abc", 3), ["1/1:abc"]);
+        assert_eq!(text_parts("", 3), ["1/1:"]);
+        assert_eq!(
+            parts(&[0xFF, 0x80, 0x80], 2),
+            [b"1/2:\xFF\x80".to_vec(), b"2/2:\x80".to_vec()]
+        );
+    }
+
+    #[test]
+    fn character_boundaries() {
+        assert_eq!(text_parts("aé€", 2), ["1/3:a", "2/3:é", "3/3:€"]);
+        assert_eq!(text_parts("€€", 1), ["1/2:€", "2/2:€"]);
+    }
+
+    #[test]
+    fn matrices() {
+        let matrices = split_payload("a".repeat(100), 40).unwrap();
+        assert_eq!(matrices.len(), 3);
+    }
+}
@@ -0,0 +1,130 @@
+//! Encoders for 2D barcode symbologies.
+//!
+//! A [`Symbology`] turns data into a pixel matrix, which the top-level
+//! [`print_code`](crate::print_code) and [`generate_code_string`](crate::generate_code_string)
+//! functions render like any QR code. Implement it to plug in your own encoder.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::symbology::{DataMatrix, MicroQr};
+//!
+//! qr2term::print_code(&DataMatrix, "https://rust-lang.org/").unwrap();
+//! qr2term::print_code(&MicroQr::default(), "12345").unwrap();
+//! ```
+
+use qrcode::types::Color;
+use qrcode::{EcLevel, QrCode, Version};
+
+use crate::matrix::Matrix;
+use crate::{datamatrix, qr, Error, QrError};
+
+/// Barcode symbology, encoding data into a pixel matrix.
+pub trait Symbology {
+    /// Encode the given `data` into a pixel matrix, without a quiet zone.
+    fn encode(&self, data: &[u8]) -> Result<Matrix<Color>, Error>;
+}
+
+impl<F: Fn(&[u8]) -> Result<Matrix<Color>, Error>> Symbology for F {
+    fn encode(&self, data: &[u8]) -> Result<Matrix<Color>, Error> {
+        self(data)
+    }
+}
+
+/// QR code symbology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Qr {
+    ec_level: EcLevel,
+}
+
+impl Qr {
+    /// Use the given error correction level, defaults to [`EcLevel::M`].
+    pub fn ec_level(mut self, ec_level: EcLevel) -> Self {
+        self.ec_level = ec_level;
+        self
+    }
+}
+
+impl Default for Qr {
+    fn default() -> Self {
+        Self {
+            ec_level: EcLevel::M,
+        }
+    }
+}
+
+impl Symbology for Qr {
+    fn encode(&self, data: &[u8]) -> Result<Matrix<Color>, Error> {
+        Ok(qr::Qr::with_error_correction_level(data, self.ec_level)?.to_matrix())
+    }
+}
+
+/// Micro QR code symbology, for small amounts of data.
+///
+/// Uses the smallest of the versions M1 to M4 the data fits in, from 11 to 17 pixels wide.
+/// Scanners need a quiet zone of just 2 pixels around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MicroQr {
+    ec_level: EcLevel,
+}
+
+impl MicroQr {
+    /// Use the given error correction level, defaults to [`EcLevel::L`].
+    ///
+    /// Higher levels are only supported by larger versions, [`EcLevel::H`] not at all.
+    pub fn ec_level(mut self, ec_level: EcLevel) -> Self {
+        self.ec_level = ec_level;
+        self
+    }
+}
+
+impl Default for MicroQr {
+    fn default() -> Self {
+        Self {
+            ec_level: EcLevel::L,
+        }
+    }
+}
+
+impl Symbology for MicroQr {
+    fn encode(&self, data: &[u8]) -> Result<Matrix<Color>, Error> {
+        let mut result = Err(QrError::DataTooLong);
+        for version in 1..=4 {
+            result = QrCode::with_version(data, Version::Micro(version), self.ec_level);
+            if result.is_ok() {
+                break;
+            }
+        }
+        Ok(Matrix::new(result?.to_colors()))
+    }
+}
+
+/// Data Matrix (ECC 200) symbology, see [`datamatrix::DataMatrix`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DataMatrix;
+
+impl Symbology for DataMatrix {
+    fn encode(&self, data: &[u8]) -> Result<Matrix<Color>, Error> {
+        Ok(datamatrix::DataMatrix::from(data)?.to_matrix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!(Qr::default().encode(b"hello").unwrap().size(), 21);
+        assert_eq!(MicroQr::default().encode(b"12345").unwrap().size(), 11);
+        assert_eq!(MicroQr::default().encode(b"hello").unwrap().size(), 15);
+        assert!(matches!(
+            MicroQr::default().encode(&[b'a'; 100]),
+            Err(Error::Qr(QrError::DataTooLong))
+        ));
+        assert_eq!(DataMatrix.encode(b"123456").unwrap().size(), 10);
+
+        let custom = |data: &[u8]| Ok(Matrix::new(vec![Color::Dark; data.len() * data.len()]));
+        assert_eq!(custom.encode(b"abc").unwrap().size(), 3);
+    }
+}
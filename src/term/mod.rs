@@ -0,0 +1,500 @@
+//! Terminal capability detection.
+//!
+//! Probes what the terminal on stdout supports, so output can be tuned to it. Detection
+//! only inspects the environment of this process and never queries the terminal itself,
+//! which makes it cheap but heuristic.
+//!
+//! Known terminal emulators are recognized to apply their [quirks](Quirks), which callers
+//! can override through [`set_capabilities`].
+//!
+//! Unicode support also depends on the locale, where a character set other than UTF-8 in
+//! `LC_ALL`, `LC_CTYPE` or `LANG` disables it.
+//!
+//! With the `terminfo` feature, the terminfo entry of the terminal refines the color
+//! and Unicode detection.
+//!
+//! The terminal [size] is queried from the operating system, falling back to the
+//! `COLUMNS` and `LINES` environment variables where that fails, such as on CI.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::term::{self, ColorDepth};
+//!
+//! let caps = term::capabilities();
+//! if caps.tty && caps.color >= ColorDepth::Ansi256 {
+//!     println!("fancy output");
+//! }
+//! ```
+
+use std::sync::RwLock;
+
+use crate::plain::Density;
+
+mod quirks;
+#[cfg_attr(not(feature = "terminfo"), allow(dead_code))]
+mod terminfo;
+
+#[cfg(feature = "terminfo")]
+pub use terminfo::Terminfo;
+#[cfg(not(feature = "terminfo"))]
+use terminfo::Terminfo;
+
+pub use quirks::{Quirks, Terminal};
+
+static CAPABILITIES: RwLock<Option<Capabilities>> = RwLock::new(None);
+
+/// Number of colors a terminal can draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors or other escape sequences.
+    None,
+
+    /// The 16 basic ANSI colors.
+    Ansi16,
+
+    /// The 256 color ANSI palette.
+    Ansi256,
+
+    /// 24 bit RGB colors.
+    TrueColor,
+}
+
+/// Capabilities of the terminal on stdout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether stdout is a terminal, rather than a file or pipe.
+    ///
+    /// Always `false` without the `crossterm` feature.
+    pub tty: bool,
+
+    /// Number of colors the terminal can draw.
+    pub color: ColorDepth,
+
+    /// Whether the terminal can draw Unicode glyphs such as half blocks and braille.
+    pub unicode: bool,
+
+    /// Whether the terminal supports the sixel graphics protocol.
+    pub sixel: bool,
+
+    /// Whether the terminal supports the kitty graphics protocol.
+    pub kitty: bool,
+
+    /// The terminal emulator, if it is a known one.
+    pub terminal: Option<Terminal>,
+
+    /// Glyphs drawing pixels best, assuming Unicode support.
+    pub density: Density,
+}
+
+impl Capabilities {
+    /// Detect the capabilities of the terminal on stdout, without caching.
+    ///
+    /// Prefer [`capabilities`], which detects them once per process.
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        #[cfg(feature = "terminfo")]
+        let terminfo = var("TERM").and_then(|term| Terminfo::load(&term));
+        #[cfg(not(feature = "terminfo"))]
+        let terminfo = None;
+        Self::parse(stdout_tty(), var, terminfo.as_ref())
+    }
+
+    /// Derive capabilities from variables looked up through `var`, and the terminfo entry
+    /// of the terminal if any.
+    ///
+    /// The terminfo entry takes precedence over guesses based on the terminal name.
+    fn parse<F: Fn(&str) -> Option<String>>(
+        tty: bool,
+        var: F,
+        terminfo: Option<&Terminfo>,
+    ) -> Self {
+        let term = var("TERM").unwrap_or_default().to_ascii_lowercase();
+        let basic = matches!(
+            term.as_str(),
+            "" | "dumb" | "vt52" | "vt100" | "vt102" | "vt220"
+        );
+
+        let colorterm = var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+        let truecolor = colorterm == "truecolor"
+            || colorterm == "24bit"
+            || ["direct", "truecolor", "24bit"]
+                .iter()
+                .any(|t| term.contains(t))
+            || ["xterm-kitty", "alacritty", "wezterm", "foot"]
+                .iter()
+                .any(|t| term.starts_with(t))
+            || terminfo.map_or(false, |info| info.truecolor);
+        let colors = terminfo.map(|info| info.colors.unwrap_or(0));
+
+        // Without a terminal name the colors are unknown rather than disabled, leaving them
+        // to the quirks of a recognized terminal
+        let disabled = var("NO_COLOR").map_or(false, |value| !value.is_empty()) || term == "dumb";
+        let unknown = term.is_empty() && !disabled;
+        let color = if disabled || unknown {
+            ColorDepth::None
+        } else if truecolor || colors.map_or(false, |colors| colors >= 1 << 24) {
+            ColorDepth::TrueColor
+        } else if let Some(colors) = colors {
+            match colors {
+                256.. => ColorDepth::Ansi256,
+                8.. => ColorDepth::Ansi16,
+                _ => ColorDepth::None,
+            }
+        } else if term.contains("256") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+
+        let mut caps = Self {
+            tty,
+            color,
+            unicode: (!basic || cfg!(windows) || terminfo.map_or(false, |info| info.unicode))
+                && locale_utf8_from(&var) != Some(false),
+            sixel: term.contains("sixel") || term.starts_with("foot") || term == "mlterm",
+            kitty: false,
+            terminal: Terminal::parse(&var),
+            density: Density::HalfBlock,
+        };
+        if let Some(terminal) = caps.terminal {
+            let quirks = terminal.quirks();
+            if let (true, Some(color)) = (unknown, quirks.color) {
+                caps.color = color;
+            }
+            caps.apply(quirks);
+        }
+        caps
+    }
+
+    /// Apply the quirks of a terminal on top of the detected capabilities.
+    ///
+    /// The color depth of the quirks is only used if colors aren't disabled, and doesn't
+    /// downgrade true color support reported otherwise.
+    pub fn apply(&mut self, quirks: Quirks) {
+        if let Some(color) = quirks.color {
+            if self.color != ColorDepth::None && self.color != ColorDepth::TrueColor {
+                self.color = color;
+            }
+        }
+        self.density = quirks.density;
+        self.sixel |= quirks.sixel;
+        self.kitty |= quirks.kitty;
+    }
+}
+
+/// Get the capabilities of the terminal on stdout.
+///
+/// Detected on first use, and cached for the rest of the process.
+pub fn capabilities() -> Capabilities {
+    if let Some(caps) = &*CAPABILITIES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+    {
+        return caps.clone();
+    }
+    let caps = Capabilities::detect();
+    *CAPABILITIES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(caps.clone());
+    caps
+}
+
+/// Set the capabilities returned by [`capabilities`], overriding detection.
+///
+/// Meant for callers that know better than the heuristics, for example through a command
+/// line option or by querying the terminal.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::render::Density;
+/// use qr2term::term;
+///
+/// let mut caps = term::capabilities();
+/// caps.density = Density::Full;
+/// term::set_capabilities(caps);
+/// ```
+pub fn set_capabilities(caps: Capabilities) {
+    *CAPABILITIES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(caps);
+}
+
+/// Whether the locale uses UTF-8, from `LC_ALL`, `LC_CTYPE` or `LANG` in that order.
+///
+/// Returns `None` if no locale is set, or it doesn't name its character set such as the
+/// `C` locale.
+pub fn locale_utf8() -> Option<bool> {
+    locale_utf8_from(|name| std::env::var(name).ok())
+}
+
+/// Whether the locale from variables looked up through `var` uses UTF-8.
+fn locale_utf8_from<F: Fn(&str) -> Option<String>>(var: F) -> Option<bool> {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()))?;
+
+    // Locales look like `language_TERRITORY.charset@modifier`
+    let charset = locale.split('@').next()?.split_once('.')?.1;
+    let charset: String = charset
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    Some(charset.eq_ignore_ascii_case("utf8"))
+}
+
+/// Get the size of the terminal as columns and rows.
+///
+/// Returns `None` if the size is unknown, and not given through the `COLUMNS` and `LINES`
+/// environment variables either.
+pub fn size() -> Option<(usize, usize)> {
+    size_from(query_size(), |name| std::env::var(name).ok())
+}
+
+/// Get the width of the terminal in columns.
+///
+/// Like [`size`], but only requires `COLUMNS` to be set when falling back to the
+/// environment.
+pub fn columns() -> Option<usize> {
+    match query_size() {
+        Some((columns, _)) => Some(columns),
+        None => env_size("COLUMNS", |name| std::env::var(name).ok()),
+    }
+}
+
+/// Get the terminal size as queried, or from variables looked up through `var`.
+fn size_from<F: Fn(&str) -> Option<String>>(
+    queried: Option<(usize, usize)>,
+    var: F,
+) -> Option<(usize, usize)> {
+    queried.or_else(|| Some((env_size("COLUMNS", &var)?, env_size("LINES", &var)?)))
+}
+
+/// Parse a positive size from the variable `name` looked up through `var`.
+fn env_size<F: Fn(&str) -> Option<String>>(name: &str, var: F) -> Option<usize> {
+    var(name)
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|size| *size > 0)
+}
+
+/// Query the terminal size from the operating system.
+#[cfg(feature = "crossterm")]
+fn query_size() -> Option<(usize, usize)> {
+    crossterm::terminal::size()
+        .ok()
+        .filter(|(columns, rows)| *columns > 0 && *rows > 0)
+        .map(|(columns, rows)| (columns as usize, rows as usize))
+}
+
+/// Query the terminal size, unsupported without crossterm.
+#[cfg(not(feature = "crossterm"))]
+fn query_size() -> Option<(usize, usize)> {
+    None
+}
+
+/// Whether stdout is a terminal.
+#[cfg(feature = "crossterm")]
+fn stdout_tty() -> bool {
+    use crossterm::tty::IsTty;
+    std::io::stdout().is_tty()
+}
+
+/// Whether stdout is a terminal, unknown without crossterm.
+#[cfg(not(feature = "crossterm"))]
+fn stdout_tty() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(vars: &[(&str, &str)]) -> Capabilities {
+        parse_with(vars, None)
+    }
+
+    fn parse_with(vars: &[(&str, &str)], terminfo: Option<&Terminfo>) -> Capabilities {
+        Capabilities::parse(
+            true,
+            |name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            terminfo,
+        )
+    }
+
+    #[test]
+    fn detect() {
+        let caps = parse(&[("TERM", "xterm-256color")]);
+        assert_eq!(caps.color, ColorDepth::Ansi256);
+        assert!(caps.unicode && !caps.sixel && !caps.kitty);
+
+        assert_eq!(parse(&[("TERM", "xterm")]).color, ColorDepth::Ansi16);
+        assert_eq!(
+            parse(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]).color,
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            parse(&[("TERM", "screen"), ("COLORTERM", "24BIT")]).color,
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            parse(&[("TERM", "xterm-direct")]).color,
+            ColorDepth::TrueColor
+        );
+        assert_eq!(parse(&[("TERM", "alacritty")]).color, ColorDepth::TrueColor);
+        assert_eq!(
+            parse(&[("TERM", "screen.xterm-256color")]).color,
+            ColorDepth::Ansi256
+        );
+        assert_eq!(parse(&[]).color, ColorDepth::None);
+        assert_eq!(
+            parse(&[("TERM", "xterm"), ("NO_COLOR", "1")]).color,
+            ColorDepth::None
+        );
+        assert_eq!(parse(&[("TERM", "vt100")]).unicode, cfg!(windows));
+        assert!(parse(&[("TERM", "xterm-kitty")]).kitty);
+        assert!(parse(&[("TERM", "foot")]).sixel);
+
+        assert_eq!(capabilities(), capabilities());
+    }
+
+    #[test]
+    fn quirks() {
+        let caps = parse(&[
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "Apple_Terminal"),
+        ]);
+        assert_eq!(caps.terminal, Some(Terminal::AppleTerminal));
+        assert_eq!(
+            (caps.color, caps.density),
+            (ColorDepth::Ansi256, Density::Full)
+        );
+
+        let caps = parse(&[("TERM", "xterm-256color"), ("WT_SESSION", "abc")]);
+        assert_eq!(caps.terminal, Some(Terminal::WindowsTerminal));
+        assert_eq!(caps.color, ColorDepth::TrueColor);
+        assert!(caps.sixel && !caps.kitty);
+        assert_eq!(parse(&[("WT_SESSION", "abc")]).color, ColorDepth::TrueColor);
+
+        let caps = parse(&[("TERM", "xterm-kitty")]);
+        assert_eq!(caps.terminal, Some(Terminal::Kitty));
+        assert!(caps.kitty);
+
+        let vte = [("TERM", "xterm-256color"), ("VTE_VERSION", "7600")];
+        assert_eq!(parse(&vte).terminal, Some(Terminal::Vte));
+        assert_eq!(parse(&vte).color, ColorDepth::TrueColor);
+        assert_eq!(
+            parse(&[("TERM", "xterm"), ("TERM_PROGRAM", "mintty")]).terminal,
+            Some(Terminal::Mintty)
+        );
+        assert_eq!(
+            parse(&[("TERM", "alacritty")]).terminal,
+            Some(Terminal::Alacritty)
+        );
+        assert_eq!(parse(&[("TERM", "xterm")]).terminal, None);
+
+        // Quirks don't enable colors that were disabled
+        let caps = parse(&[("TERM", "xterm"), ("WT_SESSION", "abc"), ("NO_COLOR", "1")]);
+        assert_eq!(caps.color, ColorDepth::None);
+        let caps = parse(&[("WT_SESSION", "abc"), ("NO_COLOR", "1")]);
+        assert_eq!(caps.color, ColorDepth::None);
+        let caps = parse(&[("TERM", "dumb"), ("WT_SESSION", "abc")]);
+        assert_eq!(caps.color, ColorDepth::None);
+    }
+
+    #[test]
+    fn detect_with_terminfo() {
+        let info = |colors| Terminfo {
+            colors,
+            ..Default::default()
+        };
+        let xterm = [("TERM", "xterm")];
+        assert_eq!(
+            parse_with(&xterm, Some(&info(Some(256)))).color,
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            parse_with(&xterm, Some(&info(Some(1 << 24)))).color,
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            parse_with(&xterm, Some(&info(None))).color,
+            ColorDepth::None
+        );
+        assert_eq!(
+            parse_with(&[("TERM", "xterm-256color")], Some(&info(Some(2)))).color,
+            ColorDepth::None
+        );
+        assert_eq!(
+            parse_with(
+                &[("TERM", "xterm"), ("COLORTERM", "truecolor")],
+                Some(&info(Some(8)))
+            )
+            .color,
+            ColorDepth::TrueColor
+        );
+
+        let unicode = Terminfo {
+            unicode: true,
+            ..Default::default()
+        };
+        assert!(parse_with(&[("TERM", "vt220")], Some(&unicode)).unicode);
+    }
+
+    #[test]
+    fn locale() {
+        let utf8 = |vars: &[(&str, &str)]| {
+            locale_utf8_from(|name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(utf8(&[("LANG", "en_US.UTF-8")]), Some(true));
+        assert_eq!(utf8(&[("LANG", "de_DE.utf8@euro")]), Some(true));
+        assert_eq!(utf8(&[("LANG", "de_DE.ISO-8859-1")]), Some(false));
+        assert_eq!(
+            utf8(&[("LANG", "en_US.UTF-8"), ("LC_CTYPE", "fr_FR.ISO-8859-15")]),
+            Some(false)
+        );
+        assert_eq!(
+            utf8(&[("LC_ALL", "en_US.UTF-8"), ("LC_CTYPE", "fr_FR.ISO-8859-15")]),
+            Some(true)
+        );
+        assert_eq!(utf8(&[("LC_ALL", ""), ("LANG", "C.UTF-8")]), Some(true));
+        assert_eq!(utf8(&[("LANG", "C")]), None);
+        assert_eq!(utf8(&[]), None);
+
+        let caps = parse(&[("TERM", "xterm"), ("LANG", "de_DE.ISO-8859-1")]);
+        assert!(!caps.unicode);
+    }
+
+    #[test]
+    fn size_fallback() {
+        let vars = |vars: &'static [(&str, &str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            size_from(Some((80, 24)), vars(&[("COLUMNS", "100")])),
+            Some((80, 24))
+        );
+        assert_eq!(
+            size_from(None, vars(&[("COLUMNS", "100"), ("LINES", " 40 ")])),
+            Some((100, 40))
+        );
+        assert_eq!(size_from(None, vars(&[("COLUMNS", "100")])), None);
+        assert_eq!(
+            size_from(None, vars(&[("COLUMNS", "0"), ("LINES", "40")])),
+            None
+        );
+        assert_eq!(env_size("COLUMNS", vars(&[("COLUMNS", "wide")])), None);
+    }
+}
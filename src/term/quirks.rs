@@ -0,0 +1,93 @@
+//! Quirks of known terminal emulators.
+
+use super::ColorDepth;
+use crate::plain::Density;
+
+/// Known terminal emulator, with [quirks](Quirks) adjusting how to draw in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terminal {
+    /// Windows Terminal, detected through `WT_SESSION`.
+    WindowsTerminal,
+
+    /// Alacritty.
+    Alacritty,
+
+    /// kitty.
+    Kitty,
+
+    /// Terminals built on VTE, such as GNOME Terminal, detected through `VTE_VERSION`.
+    Vte,
+
+    /// The Terminal app of macOS.
+    AppleTerminal,
+
+    /// mintty, used by Git Bash, Cygwin and MSYS2.
+    Mintty,
+}
+
+/// How to best draw in a terminal, differing from what its name suggests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    /// Colors the terminal can draw, regardless of `TERM`.
+    pub color: Option<ColorDepth>,
+
+    /// Glyphs drawing pixels without gaps.
+    pub density: Density,
+
+    /// Whether the terminal supports the sixel graphics protocol.
+    pub sixel: bool,
+
+    /// Whether the terminal supports the kitty graphics protocol.
+    pub kitty: bool,
+}
+
+impl Terminal {
+    /// Detect the terminal from variables looked up through `var`.
+    pub(super) fn parse<F: Fn(&str) -> Option<String>>(var: F) -> Option<Self> {
+        let term = var("TERM").unwrap_or_default();
+        match var("TERM_PROGRAM").as_deref() {
+            Some("Apple_Terminal") => return Some(Terminal::AppleTerminal),
+            Some("mintty") => return Some(Terminal::Mintty),
+            _ => {}
+        }
+        if var("WT_SESSION").is_some() {
+            Some(Terminal::WindowsTerminal)
+        } else if term == "xterm-kitty" || var("KITTY_WINDOW_ID").is_some() {
+            Some(Terminal::Kitty)
+        } else if term.starts_with("alacritty") || var("ALACRITTY_WINDOW_ID").is_some() {
+            Some(Terminal::Alacritty)
+        } else if var("VTE_VERSION").is_some() {
+            Some(Terminal::Vte)
+        } else {
+            None
+        }
+    }
+
+    /// Get the quirks of this terminal.
+    pub fn quirks(self) -> Quirks {
+        let quirks = Quirks {
+            color: Some(ColorDepth::TrueColor),
+            density: Density::HalfBlock,
+            sixel: false,
+            kitty: false,
+        };
+        match self {
+            // Sixel support since Windows Terminal 1.22
+            Terminal::WindowsTerminal | Terminal::Mintty => Quirks {
+                sixel: true,
+                ..quirks
+            },
+            Terminal::Alacritty | Terminal::Vte => quirks,
+            Terminal::Kitty => Quirks {
+                kitty: true,
+                ..quirks
+            },
+            // Line spacing leaves gaps between rows of half blocks, backgrounds fill them
+            Terminal::AppleTerminal => Quirks {
+                color: Some(ColorDepth::Ansi256),
+                density: Density::Full,
+                ..quirks
+            },
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Terminfo database lookup.
+
+#[cfg(feature = "terminfo")]
+use std::path::PathBuf;
+#[cfg(feature = "terminfo")]
+use std::{env, fs};
+
+/// Magic number of the legacy format, storing numbers as 16 bit integers.
+const MAGIC_LEGACY: i16 = 0o432;
+
+/// Magic number of the extended number format, storing numbers as 32 bit integers.
+const MAGIC_32BIT: i16 = 0o1036;
+
+/// Index of the `colors` number capability.
+const COLORS: usize = 13;
+
+/// Index of the `acsc` string capability, mapping line drawing characters.
+const ACSC: usize = 146;
+
+/// Capabilities of a terminal, as described by its terminfo entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Terminfo {
+    /// Number of colors the terminal supports, if given.
+    pub colors: Option<u32>,
+
+    /// Whether the terminal supports 24 bit colors, through the `RGB` or `Tc` extension.
+    pub truecolor: bool,
+
+    /// Whether the terminal maps line drawing characters, through `acsc`.
+    pub acs: bool,
+
+    /// Whether the terminal uses UTF-8 for line drawing, through the `U8` extension.
+    pub unicode: bool,
+}
+
+impl Terminfo {
+    /// Load the terminfo entry for the given terminal name from the database.
+    ///
+    /// Searches `$TERMINFO`, `~/.terminfo`, `$TERMINFO_DIRS` and the system directories.
+    /// Returns `None` if there is no entry, or it can't be parsed.
+    #[cfg(feature = "terminfo")]
+    pub fn load(term: &str) -> Option<Self> {
+        let first = term.chars().next()?;
+        if term.contains(['/', '\\']) || term.starts_with('.') {
+            return None;
+        }
+
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        dirs.extend(env::var_os("TERMINFO").map(PathBuf::from));
+        dirs.extend(env::var_os("HOME").map(|home| PathBuf::from(home).join(".terminfo")));
+        if let Some(list) = env::var_os("TERMINFO_DIRS") {
+            dirs.extend(env::split_paths(&list).filter(|dir| !dir.as_os_str().is_empty()));
+        }
+        dirs.extend(
+            [
+                "/etc/terminfo",
+                "/lib/terminfo",
+                "/usr/share/terminfo",
+                "/usr/lib/terminfo",
+            ]
+            .iter()
+            .map(PathBuf::from),
+        );
+
+        // Entries are grouped by their first character, or its hex code on macOS
+        let groups = [first.to_string(), format!("{:x}", first as u32)];
+        dirs.iter()
+            .flat_map(|dir| groups.iter().map(move |group| dir.join(group).join(term)))
+            .find_map(|path| fs::read(path).ok())
+            .and_then(|data| Self::parse(&data))
+    }
+
+    /// Parse a compiled terminfo entry.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader { data, pos: 0 };
+        let number_size = match reader.i16()? {
+            MAGIC_LEGACY => 2,
+            MAGIC_32BIT => 4,
+            _ => return None,
+        };
+        let names_size = reader.count()?;
+        let bools = reader.count()?;
+        let numbers = reader.count()?;
+        let strings = reader.count()?;
+        let table_size = reader.count()?;
+
+        reader.skip(names_size + bools)?;
+        reader.align();
+        let numbers: Vec<i32> = (0..numbers)
+            .map(|_| reader.number(number_size))
+            .collect::<Option<_>>()?;
+        let offsets: Vec<i16> = (0..strings).map(|_| reader.i16()).collect::<Option<_>>()?;
+        reader.skip(table_size)?;
+
+        let mut info = Terminfo {
+            colors: numbers
+                .get(COLORS)
+                .and_then(|colors| u32::try_from(*colors).ok()),
+            acs: offsets.get(ACSC).map_or(false, |offset| *offset >= 0),
+            ..Default::default()
+        };
+        reader.align();
+        if let Some(extended) = reader.extended(number_size) {
+            for (name, value) in extended {
+                match name {
+                    "RGB" | "Tc" => info.truecolor |= value > 0,
+                    "U8" => info.unicode |= value > 0,
+                    _ => {}
+                }
+            }
+        }
+        Some(info)
+    }
+}
+
+/// Reader over a compiled terminfo entry, in little endian.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    /// Skip a padding byte to align to an even offset.
+    fn align(&mut self) {
+        if self.pos % 2 == 1 {
+            self.pos += 1;
+        }
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        let bytes = self.bytes(2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Read a count or size, which must not be negative.
+    fn count(&mut self) -> Option<usize> {
+        usize::try_from(self.i16()?).ok()
+    }
+
+    fn number(&mut self, size: usize) -> Option<i32> {
+        match size {
+            2 => self.i16().map(i32::from),
+            _ => {
+                let b = self.bytes(4)?;
+                Some(i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            }
+        }
+    }
+
+    /// Read the extended boolean and number capabilities, as names with their values.
+    ///
+    /// Booleans are 1 if set, extended strings are skipped.
+    fn extended(&mut self, number_size: usize) -> Option<Vec<(&'a str, i32)>> {
+        let bools = self.count()?;
+        let numbers = self.count()?;
+        let strings = self.count()?;
+        let _entries = self.count()?;
+        let table_size = self.count()?;
+
+        let mut values: Vec<i32> = self
+            .bytes(bools)?
+            .iter()
+            .map(|value| *value as i32)
+            .collect();
+        self.align();
+        for _ in 0..numbers {
+            values.push(self.number(number_size)?);
+        }
+        let string_offsets: Vec<i16> = (0..strings).map(|_| self.i16()).collect::<Option<_>>()?;
+        let name_offsets: Vec<i16> = (0..bools + numbers + strings)
+            .map(|_| self.i16())
+            .collect::<Option<_>>()?;
+        let table = self.bytes(table_size)?;
+
+        // Names follow the string values in the table
+        let string_at = |offset: usize| {
+            let bytes = table.get(offset..)?;
+            let end = bytes.iter().position(|b| *b == 0)?;
+            std::str::from_utf8(&bytes[..end]).ok()
+        };
+        let mut names_start = 0;
+        for offset in string_offsets {
+            let Ok(offset) = usize::try_from(offset) else {
+                continue;
+            };
+            names_start = names_start.max(offset + string_at(offset)?.len() + 1);
+        }
+        name_offsets
+            .iter()
+            .take(values.len())
+            .zip(values)
+            .map(|(offset, value)| {
+                let offset = usize::try_from(*offset).ok()?;
+                Some((string_at(names_start + offset)?, value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compile a minimal legacy entry with the given colors, `acsc` and an `RGB` extension.
+    fn entry(colors: i16, acsc: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        let push = |data: &mut Vec<u8>, value: i16| data.extend(value.to_le_bytes());
+        let names = b"test|Test terminal\0";
+        let table = b"\x1b[m\0";
+        for value in [
+            MAGIC_LEGACY,
+            names.len() as i16,
+            1,
+            14,
+            147,
+            table.len() as i16,
+        ] {
+            push(&mut data, value);
+        }
+        data.extend(names);
+        data.push(1);
+        for i in 0..14 {
+            push(&mut data, if i == COLORS as i16 { colors } else { -1 });
+        }
+        for i in 0..147 {
+            push(&mut data, if i == ACSC && acsc { 0 } else { -1 });
+        }
+        data.extend(table);
+
+        // Extended section with one boolean and no numbers or strings
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        for value in [1, 0, 0, 1, 4] {
+            push(&mut data, value);
+        }
+        data.extend([1, 0]);
+        push(&mut data, 0);
+        data.extend(b"RGB\0");
+        data
+    }
+
+    #[test]
+    fn parse_entry() {
+        let info = Terminfo::parse(&entry(256, true)).unwrap();
+        assert_eq!(info.colors, Some(256));
+        assert!(info.acs && info.truecolor && !info.unicode);
+
+        let info = Terminfo::parse(&entry(-1, false)).unwrap();
+        assert_eq!(info.colors, None);
+        assert!(!info.acs);
+
+        let data = entry(8, false);
+        assert!(Terminfo::parse(&data[..40]).is_none());
+        assert!(Terminfo::parse(b"not terminfo").is_none());
+    }
+}
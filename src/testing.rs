@@ -0,0 +1,189 @@
+//! Utilities for testing rendered output.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use qr2term::render::Renderer;
+//! use qr2term::testing::SizeTracker;
+//!
+//! let mut matrix = qr2term::qr::Qr::from("https://rust-lang.org/")
+//!     .unwrap()
+//!     .to_matrix();
+//! matrix.surround(qr2term::QUIET_ZONE_WIDTH, qr2term::render::QrLight);
+//!
+//! let mut tracker = SizeTracker::new();
+//! Renderer::default().render(&matrix, &mut tracker).unwrap();
+//! assert_eq!(tracker.width(), 29);
+//! assert_eq!(tracker.height(), 15);
+//! ```
+
+use std::io::{Result as IoResult, Write};
+
+use crate::matrix::Matrix;
+use crate::render::{Color, Styled, TermColor, Theme};
+
+/// Color of modules that differ between both matrices in [`render_diff`].
+pub const DIFF_COLOR: TermColor = TermColor::Rgb(220, 50, 47);
+
+/// Writer tracking how many lines and visible characters per line are written.
+#[derive(Debug, Default, Clone)]
+pub struct SizeTracker {
+    data: Vec<u8>,
+}
+
+impl SizeTracker {
+    /// Construct a new tracker, without anything written to it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written to this tracker so far.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Everything written so far as text, without ANSI escape sequences.
+    ///
+    /// Invalid UTF-8 is replaced with the replacement character.
+    pub fn text(&self) -> String {
+        strip_ansi(&String::from_utf8_lossy(&self.data))
+    }
+
+    /// Length of the longest line, in visible characters.
+    pub fn width(&self) -> usize {
+        self.text()
+            .split('\n')
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Number of lines, being the number of newlines written.
+    pub fn height(&self) -> usize {
+        self.data.iter().filter(|&byte| *byte == b'\n').count()
+    }
+}
+
+impl Write for SizeTracker {
+    fn write(&mut self, data: &[u8]) -> IoResult<usize> {
+        self.data.extend(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Render two matrices overlaid to `target`, highlighting modules that differ.
+///
+/// Matching modules are drawn in the [classic](Theme::CLASSIC) colors and differing ones
+/// in [`DIFF_COLOR`], two characters per module. Matrices of different sizes are aligned
+/// at the top left, modules only one of them has count as differing.
+///
+/// Returns the number of differing modules, for debugging encoder or renderer
+/// regressions visually in the terminal.
+///
+/// # Examples
+///
+/// ```rust
+/// use qr2term::qr::Qr;
+///
+/// let expected = Qr::from("https://rust-lang.org/").unwrap().to_matrix();
+/// let actual = Qr::from("https://rust-lang.org").unwrap().to_matrix();
+/// let diff = qr2term::testing::render_diff(&expected, &actual, &mut std::io::stdout()).unwrap();
+/// assert!(diff > 0);
+/// ```
+pub fn render_diff<W: Write>(
+    expected: &Matrix<Color>,
+    actual: &Matrix<Color>,
+    target: &mut W,
+) -> IoResult<usize> {
+    let pixel = |matrix: &Matrix<Color>, x: usize, y: usize| {
+        let size = matrix.size();
+        (x < size && y < size).then(|| matrix.pixels()[y * size + x])
+    };
+
+    let size = expected.size().max(actual.size());
+    let mut diff = 0;
+    for y in 0..size {
+        for x in 0..size {
+            let color = match (pixel(expected, x, y), pixel(actual, x, y)) {
+                (Some(Color::Dark), Some(Color::Dark)) => Theme::CLASSIC.dark,
+                (Some(Color::Light), Some(Color::Light)) => Theme::CLASSIC.light,
+                _ => {
+                    diff += 1;
+                    DIFF_COLOR
+                }
+            };
+            write!(target, "{}", Styled::new("  ", None, color))?;
+        }
+        writeln!(target)?;
+    }
+    Ok(diff)
+}
+
+/// Remove ANSI escape sequences from the given text.
+///
+/// Strips control sequences such as colors (`ESC [ ... m`) as well as operating system
+/// commands (`ESC ] ... BEL`), used for hyperlinks and clipboard access.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control sequence, ends with a byte in the @ to ~ range
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Operating system command, ends with BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{QrDark, QrLight};
+
+    #[test]
+    fn strip_sequences() {
+        assert_eq!(
+            strip_ansi("\x1b[48;5;0m\x1b[38;5;15m▄\x1b[49m\x1b[39m\n"),
+            "▄\n"
+        );
+        assert_eq!(strip_ansi("a\x1b]52;c;aGk=\x07b\x1b]8;;x\x1b\\c"), "abc");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn diff() {
+        let a = Matrix::new(vec![QrDark, QrLight, QrLight, QrDark]);
+        let b = Matrix::new(vec![QrDark, QrDark, QrLight, QrDark]);
+        let mut out = Vec::new();
+        assert_eq!(render_diff(&a, &a, &mut out).unwrap(), 0);
+        assert_eq!(render_diff(&a, &b, &mut out).unwrap(), 1);
+
+        let mut tracker = SizeTracker::new();
+        let larger = Matrix::new(vec![QrLight; 9]);
+        assert_eq!(render_diff(&a, &larger, &mut tracker).unwrap(), 7);
+        assert_eq!((tracker.width(), tracker.height()), (6, 3));
+    }
+}